@@ -121,7 +121,7 @@ function App() {
   const [excludes, setExcludes] = useState<string[]>([]);
   const [output, setOutput] = useState(DEFAULT_OUTPUT);
   const [maxDepth, setMaxDepth] = useState<number | undefined>(undefined);
-  const [backend, setBackend] = useState<"native" | "pdu_library">("native");
+  const [backend, setBackend] = useState<"native" | "pdu_library" | "parallel">("native");
   const [dedupe, setDedupe] = useState(true);
 
   const [scanId, setScanId] = useState<string | null>(null);
@@ -734,10 +734,11 @@ function App() {
               Backend
               <select
                 value={backend}
-                onChange={(event) => setBackend(event.target.value as "native" | "pdu_library")}
+                onChange={(event) => setBackend(event.target.value as "native" | "pdu_library" | "parallel")}
               >
                 <option value="native">native</option>
                 <option value="pdu_library">pdu_library</option>
+                <option value="parallel">parallel</option>
               </select>
             </label>
           </div>
@@ -1365,6 +1366,14 @@ function App() {
                       {formatBytes(path.right_total_size_bytes)}
                     </p>
                     <p>delta {formatSignedBytes(path.total_size_delta_bytes)}</p>
+                    {(path.directory_growth ?? []).slice(0, 3).map((growth) => (
+                      <p key={growth.key} className="muted">
+                        {growth.key} {formatSignedBytes(growth.delta_bytes)}
+                      </p>
+                    ))}
+                    {(path.new_large_files ?? []).length > 0 ? (
+                      <p className="muted">new large files {(path.new_large_files ?? []).length}</p>
+                    ) : null}
                   </div>
                 ))}
               </article>
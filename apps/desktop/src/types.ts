@@ -8,7 +8,23 @@ export interface ScanRequest {
   excludes: string[];
   dedupe: boolean;
   dedupe_min_size: number;
-  backend: "native" | "pdu_library";
+  dedupe_hash_cache?: boolean;
+  dedupe_workers?: number;
+  dedupe_hdd_workers?: number;
+  dedupe_max_bytes_per_second?: number;
+  dedupe_directories?: boolean;
+  dedupe_directory_min_overlap?: number;
+  dedupe_archives?: boolean;
+  similar_images?: boolean;
+  similar_image_max_distance?: number;
+  policy_profile?: string;
+  smart_dir?: string;
+  smartctl?: boolean;
+  disk_inventory?: string;
+  directory_tree?: boolean;
+  directory_tree_min_bytes?: number;
+  size_mode?: "apparent" | "allocated" | "hardlink_deduplicated";
+  backend: "native" | "pdu_library" | "parallel";
   progress: boolean;
   min_ratio?: number;
   emit_progress_events: boolean;
@@ -16,6 +32,8 @@ export interface ScanRequest {
   incremental_cache?: boolean;
   cache_dir?: string;
   cache_ttl_seconds?: number;
+  journal_max_age_seconds?: number;
+  journal_verify_files?: boolean;
   record_history?: boolean;
   report_store_dir?: string;
 }
@@ -60,10 +78,12 @@ export interface Recommendation {
   next_steps: string[];
   estimated_impact: EstimatedImpact;
   risk_level: RiskLevel;
+  priority_score?: number;
+  rank?: number;
 }
 
 export interface RecommendationEvidence {
-  kind: "disk" | "directory" | "duplicate_group" | "history_delta" | "warning" | "other";
+  kind: "disk" | "directory" | "duplicate_group" | "duplicate_directory_group" | "similar_image_group" | "history_delta" | "warning" | "other";
   label: string;
   detail: string;
   path?: string | null;
@@ -77,11 +97,22 @@ export interface EstimatedImpact {
   risk_notes?: string | null;
 }
 
+export interface DirectoryNode {
+  path: string;
+  size_bytes: number;
+  file_count: number;
+  latest_modified?: string | null;
+  children: DirectoryNode[];
+}
+
 export interface PathStats {
   root_path: string;
   file_count: number;
   directory_count: number;
   total_size_bytes: number;
+  directory_tree?: DirectoryNode | null;
+  hardlinked_files?: number;
+  hardlink_savings_bytes?: number;
 }
 
 export interface CategorySuggestion {
@@ -99,6 +130,42 @@ export interface DuplicateGroup {
   intent?: { label: string; rationale: string };
 }
 
+export interface DuplicateDirectoryGroup {
+  match_kind: "identical" | "near_identical";
+  tree_hash?: string | null;
+  overlap_ratio: number;
+  total_wasted_bytes: number;
+  directories: Array<{ path: string; file_count: number; size_bytes: number }>;
+  intent?: { label: string; rationale: string };
+}
+
+export interface ArchiveExtraction {
+  archive_path: string;
+  format: "zip" | "tar" | "tar_gz";
+  archive_size_bytes: number;
+  extracted_path: string;
+  member_count: number;
+  extracted_bytes: number;
+}
+
+export interface AppliedPolicyProfile {
+  source?: string | null;
+  hash: string;
+  profile: {
+    name?: string | null;
+    thresholds: Record<string, number>;
+    disabled_rules: string[];
+    risk_levels: Record<string, "low" | "medium" | "high">;
+    custom_rules?: Array<{ id: string; title: string; scope: string; risk_level: string }>;
+  };
+}
+
+export interface SimilarImageGroup {
+  max_distance: number;
+  lower_resolution_bytes: number;
+  files: Array<{ path: string; width: number; height: number; size_bytes: number; distance: number }>;
+}
+
 export interface RuleTrace {
   rule_id: string;
   status: "emitted" | "skipped" | "rejected";
@@ -129,6 +196,76 @@ export interface DiskInfo {
   eligible_for_local_target: boolean;
   ineligible_reasons: string[];
   role_hint: DiskRoleHint;
+  device_id?: number | null;
+  mount_kind?: "filesystem" | "bind" | "btrfs_subvolume" | "zfs_dataset" | "overlay" | "unknown";
+  storage_pool?: string | null;
+  parent_mount?: string | null;
+  reserved_bytes?: number | null;
+  snapshot_bytes?: number | null;
+  logical_used_bytes?: number | null;
+  effective_free_space_bytes?: number | null;
+  health?: DiskHealth | null;
+}
+
+export interface DiskHealth {
+  source: string;
+  device?: string | null;
+  model?: string | null;
+  serial?: string | null;
+  smart_passed?: boolean | null;
+  reallocated_sectors?: number | null;
+  pending_sectors?: number | null;
+  media_errors?: number | null;
+  power_on_hours?: number | null;
+  wear_percent_used?: number | null;
+  temperature_celsius?: number | null;
+}
+
+export interface GitRepository {
+  path: string;
+  disk_mount?: string | null;
+  last_commit_at?: string | null;
+  last_ref_update_at?: string | null;
+  latest_worktree_modified_at?: string | null;
+  index_modified_at?: string | null;
+  files_newer_than_index: number;
+  pack_bytes: number;
+  git_dir_bytes: number;
+  worktree_bytes: number;
+}
+
+export interface DevProject {
+  path: string;
+  markers: string[];
+  last_activity_at?: string | null;
+}
+
+export interface CapacityForecast {
+  scope: "disk" | "path";
+  target: string;
+  disk_mount?: string | null;
+  samples: number;
+  first_sample_at: string;
+  last_sample_at: string;
+  used_bytes: number;
+  capacity_bytes: number;
+  growth_bytes_per_day: number;
+  growth_bytes_per_day_low: number;
+  growth_bytes_per_day_high: number;
+  projected_full_at?: string | null;
+  projected_full_earliest?: string | null;
+  projected_full_latest?: string | null;
+  days_until_full?: number | null;
+}
+
+export interface TieringMove {
+  recommendation_id: string;
+  source_path: string;
+  source_mount: string;
+  bytes: number;
+  target_mount: string;
+  last_modified?: string | null;
+  rationale: string;
 }
 
 export interface Report {
@@ -139,6 +276,14 @@ export interface Report {
   paths?: PathStats[];
   categories?: CategorySuggestion[];
   duplicates?: DuplicateGroup[];
+  duplicate_directories?: DuplicateDirectoryGroup[];
+  archive_extractions?: ArchiveExtraction[];
+  similar_images?: SimilarImageGroup[];
+  git_repositories?: GitRepository[];
+  dev_projects?: DevProject[] | null;
+  capacity_forecasts?: CapacityForecast[];
+  tiering_plan?: TieringMove[];
+  policy_profile?: AppliedPolicyProfile | null;
   recommendations: Recommendation[];
   policy_decisions?: PolicyDecision[];
   rule_traces?: RuleTrace[];
@@ -150,7 +295,7 @@ export interface ReportSummary {
   generated_at: string;
   report_version: string;
   roots: string[];
-  backend: "native" | "pdu_library";
+  backend: "native" | "pdu_library" | "parallel";
   warnings_count: number;
   recommendation_count: number;
   stored_report_path: string;
@@ -178,6 +323,23 @@ export interface PathDiff {
   left_file_count?: number | null;
   right_file_count?: number | null;
   file_count_delta: number;
+  directory_growth?: GrowthAttribution[];
+  extension_growth?: GrowthAttribution[];
+  new_large_files?: FileEntry[];
+  vanished_large_files?: FileEntry[];
+}
+
+export interface FileEntry {
+  path: string;
+  size_bytes: number;
+  modified?: string | null;
+}
+
+export interface GrowthAttribution {
+  key: string;
+  left_bytes: number;
+  right_bytes: number;
+  delta_bytes: number;
 }
 
 export interface RecommendationChange {
@@ -223,6 +385,7 @@ export interface RecommendationBundle {
   policy_decisions?: PolicyDecision[];
   rule_traces?: RuleTrace[];
   contradiction_count?: number;
+  tiering_plan?: TieringMove[];
 }
 
 export interface ScenarioRiskMix {
@@ -6,11 +6,12 @@ use clap::ArgAction;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::Serialize;
 use storage_strategist_core::{
-    build_diagnostics_bundle, build_scenario_plan, collect_doctor_info, compare_backends,
-    compare_reports as compare_saved_reports, evaluate_suite_file, generate_recommendation_bundle,
-    get_report as load_saved_report, import_report as import_saved_report,
-    list_reports as list_saved_reports, render_markdown_summary, run_scan, store_report, Report,
-    ScanBackendKind, ScanOptions,
+    apply_policy_profile, build_diagnostics_bundle, build_scenario_plan, capture_disk_inventory,
+    collect_doctor_info, compare_backends, compare_reports as compare_saved_reports,
+    evaluate_suite_file, generate_recommendation_bundle, get_report as load_saved_report,
+    import_report as import_saved_report, list_reports as list_saved_reports, load_policy_profile,
+    render_markdown_summary, run_scan, store_report, Report, ScanBackendKind, ScanOptions,
+    SizeMode,
 };
 use tracing_subscriber::EnvFilter;
 
@@ -28,16 +29,18 @@ struct Cli {
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// Scan paths/disks and emit a JSON report.
-    Scan(ScanArgs),
+    Scan(Box<ScanArgs>),
     /// Re-run recommendation rules from an existing report.
     Recommend(RecommendArgs),
     /// Show environment and detected disk information.
     Doctor,
+    /// Capture this machine's disk layout as an inventory file for `scan --disk-inventory`.
+    Inventory(InventoryArgs),
     /// Evaluate recommendation quality against fixture suite.
     Eval(EvalArgs),
     /// Run scan benchmark loop and emit throughput metrics.
     Benchmark(BenchmarkArgs),
-    /// Compare native and pdu_library backend outputs for parity checks.
+    /// Compare native, pdu_library, and parallel backend outputs for parity checks.
     Parity(ParityArgs),
     /// Build read-only what-if scenario projections from a report.
     Plan(PlanArgs),
@@ -52,6 +55,25 @@ enum CliBackendKind {
     Native,
     #[value(name = "pdu_library", alias = "pdu-library", alias = "pdu")]
     PduLibrary,
+    Parallel,
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+enum CliSizeMode {
+    Apparent,
+    Allocated,
+    #[value(name = "hardlink_deduplicated", alias = "hardlink-deduplicated")]
+    HardlinkDeduplicated,
+}
+
+impl From<CliSizeMode> for SizeMode {
+    fn from(value: CliSizeMode) -> Self {
+        match value {
+            CliSizeMode::Apparent => SizeMode::Apparent,
+            CliSizeMode::Allocated => SizeMode::Allocated,
+            CliSizeMode::HardlinkDeduplicated => SizeMode::HardlinkDeduplicated,
+        }
+    }
 }
 
 impl From<CliBackendKind> for ScanBackendKind {
@@ -59,6 +81,7 @@ impl From<CliBackendKind> for ScanBackendKind {
         match value {
             CliBackendKind::Native => ScanBackendKind::Native,
             CliBackendKind::PduLibrary => ScanBackendKind::PduLibrary,
+            CliBackendKind::Parallel => ScanBackendKind::Parallel,
         }
     }
 }
@@ -93,7 +116,71 @@ struct ScanArgs {
     #[arg(long, default_value_t = 1_048_576, value_name = "BYTES")]
     dedupe_min_size: u64,
 
-    /// Scanner backend (`native` or `pdu-library`).
+    /// Reuse content hashes from the on-disk dedupe hash cache for unchanged files.
+    #[arg(long)]
+    dedupe_hash_cache: bool,
+
+    /// Maximum number of files hashed concurrently during dedupe.
+    #[arg(long, default_value_t = 4, value_name = "N")]
+    dedupe_workers: usize,
+
+    /// Files hashed concurrently on each HDD or rotational mount, within `--dedupe-workers`.
+    #[arg(long, default_value_t = 1, value_name = "N")]
+    dedupe_hdd_workers: usize,
+
+    /// Cap dedupe hashing reads per disk mount, in bytes per second.
+    #[arg(long, value_name = "BYTES")]
+    dedupe_max_bytes_per_second: Option<u64>,
+
+    /// Also report duplicated directory trees (hashes small files too).
+    #[arg(long)]
+    dedupe_directories: bool,
+
+    /// Minimum share of bytes two directories must duplicate to be near-identical.
+    #[arg(long, default_value_t = 0.9, value_name = "RATIO")]
+    dedupe_directory_min_overlap: f32,
+
+    /// Also report zip/tar archives whose members are all extracted unchanged.
+    #[arg(long)]
+    dedupe_archives: bool,
+
+    /// Group visually similar jpeg/png images using perceptual hashes.
+    #[arg(long)]
+    similar_images: bool,
+
+    /// Maximum perceptual-hash distance (0-64 bits) for images to count as similar.
+    #[arg(long, default_value_t = 6, value_name = "BITS")]
+    similar_image_max_distance: u32,
+
+    /// TOML or JSON policy profile overriding recommendation thresholds and rules.
+    #[arg(long, value_name = "FILE")]
+    policy_profile: Option<PathBuf>,
+
+    /// Directory of saved `smartctl --json` outputs to read drive health from.
+    #[arg(long, value_name = "DIR")]
+    smart_dir: Option<PathBuf>,
+
+    /// Query drive health with `smartctl` when it is installed.
+    #[arg(long)]
+    smartctl: bool,
+
+    /// Analyze disks from a captured inventory file instead of probing this machine.
+    #[arg(long, value_name = "FILE")]
+    disk_inventory: Option<PathBuf>,
+
+    /// Include a hierarchical directory tree in each path summary.
+    #[arg(long)]
+    directory_tree: bool,
+
+    /// Prune directory tree nodes smaller than this many bytes.
+    #[arg(long, default_value_t = 67_108_864, value_name = "BYTES")]
+    directory_tree_min_bytes: u64,
+
+    /// How file sizes are summed (`apparent`, `allocated`, or `hardlink-deduplicated`).
+    #[arg(long, default_value = "apparent")]
+    size_mode: CliSizeMode,
+
+    /// Scanner backend (`native`, `pdu-library`, or `parallel`).
     #[arg(long, default_value = "native")]
     backend: CliBackendKind,
 
@@ -117,6 +204,16 @@ struct ScanArgs {
     #[arg(long, default_value_t = 900, value_name = "SECONDS")]
     cache_ttl_seconds: u64,
 
+    /// Maximum age in seconds before a journaled directory listing is re-read even
+    /// though its mtime is unchanged.
+    #[arg(long, default_value_t = 604_800, value_name = "SECONDS")]
+    journal_max_age_seconds: u64,
+
+    /// Re-stat every journaled file before replaying its directory, so files edited in
+    /// place (which leave the directory mtime alone) are picked up.
+    #[arg(long)]
+    journal_verify_files: bool,
+
     /// Persist scan history metadata for trend analysis.
     #[arg(long, action = ArgAction::Set, default_value_t = true)]
     record_history: bool,
@@ -139,6 +236,10 @@ struct RecommendArgs {
     /// Optional markdown summary output file.
     #[arg(long, value_name = "FILE")]
     md: Option<PathBuf>,
+
+    /// Policy profile to apply instead of the one recorded in the report.
+    #[arg(long, value_name = "FILE")]
+    policy_profile: Option<PathBuf>,
 }
 
 #[derive(Debug, Args)]
@@ -152,6 +253,25 @@ struct EvalArgs {
     output: Option<PathBuf>,
 }
 
+#[derive(Debug, Args)]
+struct InventoryArgs {
+    /// Disk inventory output file.
+    #[arg(
+        long,
+        value_name = "FILE",
+        default_value = "storage-strategist-disk-inventory.json"
+    )]
+    output: PathBuf,
+
+    /// Directory of saved `smartctl --json` outputs to store as drive health.
+    #[arg(long, value_name = "DIR")]
+    smart_dir: Option<PathBuf>,
+
+    /// Query drive health with `smartctl` when it is installed.
+    #[arg(long)]
+    smartctl: bool,
+}
+
 #[derive(Debug, Args)]
 struct BenchmarkArgs {
     /// Paths to benchmark. If omitted, uses auto-detected mount points.
@@ -166,7 +286,7 @@ struct BenchmarkArgs {
     #[arg(long, default_value_t = 1)]
     iterations: usize,
 
-    /// Scanner backend (`native` or `pdu-library`).
+    /// Scanner backend (`native`, `pdu-library`, or `parallel`).
     #[arg(long, default_value = "native")]
     backend: CliBackendKind,
 
@@ -275,12 +395,13 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Scan(args) => run_scan_command(args),
+        Commands::Scan(args) => run_scan_command(*args),
         Commands::Recommend(args) => run_recommend_command(args),
         Commands::Doctor => {
             run_doctor_command();
             Ok(())
         }
+        Commands::Inventory(args) => run_inventory_command(args),
         Commands::Eval(args) => run_eval_command(args),
         Commands::Benchmark(args) => run_benchmark_command(args),
         Commands::Parity(args) => run_parity_command(args),
@@ -298,12 +419,30 @@ fn run_scan_command(args: ScanArgs) -> Result<()> {
         exclude,
         dedupe,
         dedupe_min_size,
+        dedupe_hash_cache,
+        dedupe_workers,
+        dedupe_hdd_workers,
+        dedupe_max_bytes_per_second,
+        dedupe_directories,
+        dedupe_directory_min_overlap,
+        dedupe_archives,
+        similar_images,
+        similar_image_max_distance,
+        policy_profile,
+        smart_dir,
+        smartctl,
+        disk_inventory,
+        directory_tree,
+        directory_tree_min_bytes,
+        size_mode,
         backend,
         progress,
         min_ratio,
         incremental_cache,
         cache_dir,
         cache_ttl_seconds,
+        journal_max_age_seconds,
+        journal_verify_files,
         record_history,
         report_store_dir,
         dry_run,
@@ -315,12 +454,30 @@ fn run_scan_command(args: ScanArgs) -> Result<()> {
         excludes: exclude,
         dedupe,
         dedupe_min_size,
+        dedupe_hash_cache,
+        dedupe_workers,
+        dedupe_hdd_workers,
+        dedupe_max_bytes_per_second,
+        dedupe_directories,
+        dedupe_directory_min_overlap,
+        dedupe_archives,
+        similar_images,
+        similar_image_max_distance,
+        policy_profile,
+        smart_dir,
+        smartctl,
+        disk_inventory,
+        directory_tree,
+        directory_tree_min_bytes,
+        size_mode: size_mode.into(),
         backend: backend.into(),
         progress,
         min_ratio,
         incremental_cache,
         cache_dir,
         cache_ttl_seconds,
+        journal_max_age_seconds,
+        journal_verify_files,
         record_history,
         report_store_dir: report_store_dir.clone(),
         dry_run: true,
@@ -365,11 +522,15 @@ fn run_recommend_command(args: RecommendArgs) -> Result<()> {
         .with_context(|| format!("failed to read {}", args.report.display()))?;
     let mut report: Report = serde_json::from_str(&data)
         .with_context(|| format!("failed to parse {}", args.report.display()))?;
+    if let Some(path) = &args.policy_profile {
+        report.policy_profile = Some(apply_policy_profile(Some(path), load_policy_profile(path)?));
+    }
 
     let bundle = generate_recommendation_bundle(&report);
     report.recommendations = bundle.recommendations.clone();
     report.rule_traces = bundle.rule_traces.clone();
     report.policy_decisions = bundle.policy_decisions.clone();
+    report.tiering_plan = bundle.tiering_plan.clone();
 
     if report.recommendations.is_empty() {
         println!(
@@ -384,8 +545,14 @@ fn run_recommend_command(args: RecommendArgs) -> Result<()> {
         );
         for item in &report.recommendations {
             println!(
-                "- [{:?} | conf {:.2} | safe {}] {}: {}",
-                item.risk_level, item.confidence, item.policy_safe, item.title, item.rationale
+                "{}. [{:?} | score {:.3} | conf {:.2} | safe {}] {}: {}",
+                item.rank,
+                item.risk_level,
+                item.priority_score,
+                item.confidence,
+                item.policy_safe,
+                item.title,
+                item.rationale
             );
         }
     }
@@ -404,20 +571,22 @@ fn run_recommend_command(args: RecommendArgs) -> Result<()> {
 fn run_eval_command(args: EvalArgs) -> Result<()> {
     let result = evaluate_suite_file(&args.suite)?;
     println!(
-        "Eval: {}/{} cases passed | precision@3 {:.3} | contradiction_rate {:.3} | unsafe {}",
+        "Eval: {}/{} cases passed | precision@3 {:.3} | ndcg@3 {:.3} | contradiction_rate {:.3} | unsafe {}",
         result.passed_cases,
         result.total_cases,
         result.precision_at_3,
+        result.ndcg_at_3,
         result.contradiction_rate,
         result.unsafe_recommendations
     );
 
     for case in &result.case_results {
         println!(
-            "- [{}] {} | p@3 {:.3} | forbidden hits: {}",
+            "- [{}] {} | p@3 {:.3} | ndcg@3 {:.3} | forbidden hits: {}",
             if case.passed { "PASS" } else { "FAIL" },
             case.name,
             case.precision_at_3,
+            case.ndcg_at_3,
             if case.forbidden_hits.is_empty() {
                 "none".to_string()
             } else {
@@ -527,8 +696,12 @@ fn run_parity_command(args: ParityArgs) -> Result<()> {
         parity.scanned_bytes_delta
     );
     println!(
-        "Elapsed native={}ms pdu_library={}ms",
-        parity.native_elapsed_ms, parity.pdu_library_elapsed_ms
+        "Parallel: scanned_files_delta={} scanned_bytes_delta={}",
+        parity.parallel_scanned_files_delta, parity.parallel_scanned_bytes_delta
+    );
+    println!(
+        "Elapsed native={}ms pdu_library={}ms parallel={}ms",
+        parity.native_elapsed_ms, parity.pdu_library_elapsed_ms, parity.parallel_elapsed_ms
     );
 
     if let Some(output) = args.output {
@@ -553,6 +726,7 @@ fn run_plan_command(args: PlanArgs) -> Result<()> {
     report.recommendations = bundle.recommendations;
     report.rule_traces = bundle.rule_traces;
     report.policy_decisions = bundle.policy_decisions;
+    report.tiering_plan = bundle.tiering_plan;
 
     let plan = build_scenario_plan(&report);
     println!(
@@ -582,6 +756,29 @@ fn run_plan_command(args: PlanArgs) -> Result<()> {
     Ok(())
 }
 
+fn run_inventory_command(args: InventoryArgs) -> Result<()> {
+    let mut warnings = Vec::new();
+    let inventory = capture_disk_inventory(args.smart_dir.as_deref(), args.smartctl, &mut warnings);
+    let payload =
+        serde_json::to_string_pretty(&inventory).context("failed to serialize disk inventory")?;
+    fs::write(&args.output, payload)
+        .with_context(|| format!("failed to write disk inventory {}", args.output.display()))?;
+
+    println!(
+        "Disk inventory written to {} ({} disk(s), {} mount(s), {} platform hint(s), {} health reading(s))",
+        args.output.display(),
+        inventory.probes.len(),
+        inventory.mounts.len(),
+        inventory.platform_hints.len(),
+        inventory.health.len()
+    );
+    for warning in warnings {
+        println!("warning: {warning}");
+    }
+
+    Ok(())
+}
+
 fn run_diagnostics_command(args: DiagnosticsArgs) -> Result<()> {
     let data = fs::read_to_string(&args.report)
         .with_context(|| format!("failed to read {}", args.report.display()))?;
@@ -662,6 +859,25 @@ fn run_reports_command(args: ReportsArgs) -> Result<()> {
                 diff.duplicate_wasted_bytes_delta,
                 diff.recommendation_changes.len()
             );
+            for path in &diff.path_diffs {
+                println!(
+                    "{} {}",
+                    path.root_path,
+                    signed_bytes(path.total_size_delta_bytes)
+                );
+                for growth in &path.directory_growth {
+                    println!("  dir {} {}", growth.key, signed_bytes(growth.delta_bytes));
+                }
+                for growth in &path.extension_growth {
+                    println!("  .{} {}", growth.key, signed_bytes(growth.delta_bytes));
+                }
+                for file in &path.new_large_files {
+                    println!("  new {} ({})", file.path, human_bytes(file.size_bytes));
+                }
+                for file in &path.vanished_large_files {
+                    println!("  gone {} ({})", file.path, human_bytes(file.size_bytes));
+                }
+            }
             if let Some(output) = diff_args.output {
                 let payload = serde_json::to_string_pretty(&diff)
                     .context("failed to serialize report diff")?;
@@ -715,6 +931,11 @@ fn init_tracing() {
     let _ = tracing_subscriber::fmt().with_env_filter(filter).try_init();
 }
 
+fn signed_bytes(delta: i64) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{sign}{}", human_bytes(delta.unsigned_abs()))
+}
+
 fn human_bytes(value: u64) -> String {
     const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
     if value == 0 {
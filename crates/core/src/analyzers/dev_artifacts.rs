@@ -1,99 +1,308 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, SecondsFormat, Utc};
 
 use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerResult};
 use crate::model::{
-    EstimatedImpact, Recommendation, Report, RiskLevel, RuleTrace, RuleTraceStatus,
+    DevProject, EstimatedImpact, PathStats, PolicyThresholds, Recommendation,
+    RecommendationEvidence, RecommendationEvidenceKind, Report, RiskLevel, RuleTrace,
+    RuleTraceStatus,
 };
+use crate::tree::flatten_directory_tree;
 
 pub struct DevArtifactsAnalyzer;
 
-const NODE_MODULES_MIN_SIZE_BYTES: u64 = 1_000_000_000; // 1 GB
-const TARGET_DIR_MIN_SIZE_BYTES: u64 = 2_000_000_000; // 2 GB
+/// How many directories above an artifact are searched for project markers, so a
+/// `__pycache__` inside a nested package still finds the project root.
+const MAX_MARKER_DEPTH: usize = 4;
+const MAX_EVIDENCE_ITEMS: usize = 5;
+
+struct ArtifactKind {
+    id: &'static str,
+    label: &'static str,
+    dir_names: &'static [&'static str],
+    dir_prefixes: &'static [&'static str],
+    /// Files or directories that confirm the owning project. `*.ext` matches by extension.
+    markers: &'static [&'static str],
+    risk_note: &'static str,
+}
+
+impl ArtifactKind {
+    fn matches_name(&self, name: &str) -> bool {
+        self.dir_names.contains(&name)
+            || self
+                .dir_prefixes
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+    }
+
+    fn min_size_bytes(&self, thresholds: &PolicyThresholds) -> u64 {
+        match self.id {
+            "node-modules" => thresholds.node_modules_min_size_bytes,
+            "target-dirs" => thresholds.target_dir_min_size_bytes,
+            _ => thresholds.dev_artifact_min_size_bytes,
+        }
+    }
+}
+
+// Kinds sharing a directory name (`target`, `build`) are told apart by their
+// markers; the first confirmed kind wins.
+const ARTIFACT_KINDS: &[ArtifactKind] = &[
+    ArtifactKind {
+        id: "node-modules",
+        label: "`node_modules`",
+        dir_names: &["node_modules"],
+        dir_prefixes: &[],
+        markers: &["package.json"],
+        risk_note: "Deleting `node_modules` requires reinstalling dependencies with `npm install` or similar before resuming development.",
+    },
+    ArtifactKind {
+        id: "target-dirs",
+        label: "Rust `target`",
+        dir_names: &["target"],
+        dir_prefixes: &[],
+        markers: &["Cargo.toml"],
+        risk_note: "`cargo clean` will remove all build artifacts, requiring a full recompile of the project.",
+    },
+    ArtifactKind {
+        id: "maven-target",
+        label: "Maven `target`",
+        dir_names: &["target"],
+        dir_prefixes: &[],
+        markers: &["pom.xml"],
+        risk_note: "`mvn clean` removes compiled classes and packaged artifacts; the next build recreates them.",
+    },
+    ArtifactKind {
+        id: "python-venvs",
+        label: "Python virtual environment",
+        dir_names: &[".venv", "venv", ".tox", ".nox"],
+        dir_prefixes: &[],
+        markers: &["pyproject.toml", "requirements.txt", "setup.py", "setup.cfg", "Pipfile"],
+        risk_note: "Deleting a virtual environment requires recreating it and reinstalling dependencies before running the project.",
+    },
+    ArtifactKind {
+        id: "python-bytecode",
+        label: "Python `__pycache__`",
+        dir_names: &["__pycache__", ".pytest_cache", ".mypy_cache"],
+        dir_prefixes: &[],
+        markers: &["pyproject.toml", "requirements.txt", "setup.py", "setup.cfg", "Pipfile"],
+        risk_note: "Bytecode and tool caches are regenerated automatically on the next run.",
+    },
+    ArtifactKind {
+        id: "gradle-build",
+        label: "Gradle build",
+        dir_names: &[".gradle", "build"],
+        dir_prefixes: &[],
+        markers: &[
+            "build.gradle",
+            "build.gradle.kts",
+            "settings.gradle",
+            "settings.gradle.kts",
+        ],
+        risk_note: "`gradle clean` removes build outputs; the next build recompiles the project.",
+    },
+    ArtifactKind {
+        id: "cmake-build",
+        label: "CMake build tree",
+        dir_names: &["build", "out"],
+        dir_prefixes: &["cmake-build-"],
+        markers: &["CMakeLists.txt"],
+        risk_note: "Deleting a CMake build tree requires reconfiguring and rebuilding the project.",
+    },
+    ArtifactKind {
+        id: "js-build-output",
+        label: "JavaScript build output",
+        dir_names: &[".next", ".nuxt", "dist", ".parcel-cache", ".turbo"],
+        dir_prefixes: &[],
+        markers: &["package.json"],
+        risk_note: "Build output and bundler caches are recreated by the next build.",
+    },
+    ArtifactKind {
+        id: "xcode-derived-data",
+        label: "Xcode DerivedData",
+        dir_names: &["DerivedData"],
+        dir_prefixes: &[],
+        markers: &["*.xcodeproj", "*.xcworkspace", "Package.swift"],
+        risk_note: "Xcode rebuilds DerivedData on the next build; indexing restarts from scratch.",
+    },
+    ArtifactKind {
+        id: "terraform-providers",
+        label: "`.terraform`",
+        dir_names: &[".terraform"],
+        dir_prefixes: &[],
+        markers: &["*.tf"],
+        risk_note: "`terraform init` must be run again to download providers and modules.",
+    },
+    ArtifactKind {
+        id: "bazel-output",
+        label: "Bazel output",
+        dir_names: &[],
+        dir_prefixes: &["bazel-"],
+        markers: &["WORKSPACE", "WORKSPACE.bazel", "MODULE.bazel"],
+        risk_note: "`bazel clean` removes outputs; the next build repopulates them from the cache or rebuilds.",
+    },
+];
+
+// Kinds that reports without recorded projects still match by directory name alone,
+// as the analyzer did before project markers were checked.
+const NAME_ONLY_KINDS: &[&str] = &["node-modules", "target-dirs"];
+
+struct ArtifactFinding {
+    path: String,
+    size_bytes: u64,
+    project: String,
+    last_activity: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct KindSummary {
+    stale: Vec<ArtifactFinding>,
+    active: usize,
+}
 
 impl Analyzer for DevArtifactsAnalyzer {
     fn id(&self) -> &'static str {
         "dev_artifacts"
     }
 
-    fn analyze(&self, report: &Report, _context: &AnalyzerContext) -> AnalyzerResult {
-        let mut findings: HashMap<&str, (u64, Vec<String>)> = HashMap::new();
+    fn analyze(&self, report: &Report, context: &AnalyzerContext) -> AnalyzerResult {
+        let thresholds = &context.policy_profile.thresholds;
+        let now = report
+            .generated_at
+            .parse::<DateTime<Utc>>()
+            .unwrap_or_else(|_| Utc::now());
+        let stale_days = i64::from(thresholds.dev_artifact_stale_days);
+        let Some(dev_projects) = &report.dev_projects else {
+            return self.analyze_by_name(report, thresholds);
+        };
+        let projects = dev_projects
+            .iter()
+            .map(|project| (project.path.as_str(), project))
+            .collect::<HashMap<_, _>>();
+        let mut summaries: BTreeMap<usize, KindSummary> = BTreeMap::new();
+        let mut unconfirmed = 0usize;
         let mut traces = Vec::new();
 
-        for path_stats in &report.paths {
-            for dir_usage in &path_stats.largest_directories {
-                let path = &dir_usage.path;
-                let size = dir_usage.size_bytes;
-
-                if is_node_modules(path) {
-                    let entry = findings.entry("node_modules").or_default();
-                    entry.0 += size;
-                    entry.1.push(path.clone());
-                } else if is_target_dir(path) {
-                    let entry = findings.entry("target").or_default();
-                    entry.0 += size;
-                    entry.1.push(path.clone());
-                }
+        for (path, size_bytes) in artifact_candidates(&report.paths) {
+            let name = last_component(path);
+            let confirmed = ARTIFACT_KINDS
+                .iter()
+                .enumerate()
+                .filter(|(_, kind)| kind.matches_name(name))
+                .find_map(|(index, kind)| {
+                    find_project(path, kind.markers, &projects).map(|project| (index, project))
+                });
+            let Some((index, project)) = confirmed else {
+                unconfirmed += 1;
+                continue;
+            };
+            let Some(last_activity) = project
+                .last_activity_at
+                .as_deref()
+                .and_then(|value| value.parse::<DateTime<Utc>>().ok())
+            else {
+                unconfirmed += 1;
+                continue;
+            };
+            let summary = summaries.entry(index).or_default();
+            if (now - last_activity).num_days() < stale_days {
+                summary.active += 1;
+            } else {
+                summary.stale.push(ArtifactFinding {
+                    path: path.to_string(),
+                    size_bytes,
+                    project: project.path.clone(),
+                    last_activity,
+                });
             }
         }
 
         let mut recommendations = Vec::new();
-
-        if let Some((total_size, paths)) = findings.get("node_modules") {
-            if *total_size > NODE_MODULES_MIN_SIZE_BYTES {
-                let rec = Recommendation {
-                    id: "cleanup-node-modules".to_string(),
-                    title: "Review large `node_modules` directories".to_string(),
-                    rationale: format!(
-                        "Found {} `node_modules` director(y/ies) totaling {}. These can often be pruned or deleted in inactive projects to reclaim space.",
-                        paths.len(),
-                        human_bytes(*total_size)
+        for (index, summary) in &mut summaries {
+            let kind = &ARTIFACT_KINDS[*index];
+            if summary.active > 0 {
+                traces.push(RuleTrace {
+                    rule_id: self.id().to_string(),
+                    status: RuleTraceStatus::Skipped,
+                    detail: format!(
+                        "Skipped {} {} director(y/ies) in projects active within the last {} days.",
+                        summary.active, kind.label, stale_days
                     ),
-                    confidence: 0.8,
-                    target_mount: None,
-                    policy_safe: true,
-                    policy_rules_applied: vec![],
-                    policy_rules_blocked: vec![],
-                    evidence: Vec::new(),
-                    next_steps: Vec::new(),
-                    estimated_impact: EstimatedImpact {
-                        space_saving_bytes: Some(*total_size),
-                        performance: None,
-                        risk_notes: Some("Deleting `node_modules` requires reinstalling dependencies with `npm install` or similar before resuming development.".to_string()),
-                    },
-                    risk_level: RiskLevel::Low,
-                };
-                recommendations.push(rec);
+                    recommendation_id: None,
+                    confidence: None,
+                });
             }
+            let total_size: u64 = summary.stale.iter().map(|item| item.size_bytes).sum();
+            if summary.stale.is_empty() || total_size <= kind.min_size_bytes(thresholds) {
+                continue;
+            }
+            recommendations.push(stale_artifact_recommendation(
+                kind,
+                &mut summary.stale,
+                total_size,
+                stale_days,
+            ));
         }
 
-        if let Some((total_size, paths)) = findings.get("target") {
-            if *total_size > TARGET_DIR_MIN_SIZE_BYTES {
-                let rec = Recommendation {
-                    id: "cleanup-target-dirs".to_string(),
-                    title: "Review large Rust `target` directories".to_string(),
-                    rationale: format!(
-                        "Found {} Rust `target` director(y/ies) totaling {}. These directories contain build artifacts and can be cleaned with `cargo clean` to reclaim significant space.",
-                        paths.len(),
-                        human_bytes(*total_size)
-                    ),
-                    confidence: 0.8,
-                    target_mount: None,
-                    policy_safe: true,
-                    policy_rules_applied: vec![],
-                    policy_rules_blocked: vec![],
-                    evidence: Vec::new(),
-                    next_steps: Vec::new(),
-                    estimated_impact: EstimatedImpact {
-                        space_saving_bytes: Some(*total_size),
-                        performance: None,
-                        risk_notes: Some("`cargo clean` will remove all build artifacts, requiring a full recompile of the project.".to_string()),
-                    },
-                    risk_level: RiskLevel::Low,
-                };
-                recommendations.push(rec);
+        if unconfirmed > 0 {
+            traces.push(RuleTrace {
+                rule_id: self.id().to_string(),
+                status: RuleTraceStatus::Skipped,
+                detail: format!(
+                    "Ignored {unconfirmed} artifact-like director(y/ies) without a project marker or readable project activity."
+                ),
+                recommendation_id: None,
+                confidence: None,
+            });
+        }
+
+        self.finish(recommendations, traces)
+    }
+}
+
+impl DevArtifactsAnalyzer {
+    /// Reports written before the scan recorded projects carry no marker or activity
+    /// data, so only `node_modules` and `target` are matched, by name and size.
+    fn analyze_by_name(&self, report: &Report, thresholds: &PolicyThresholds) -> AnalyzerResult {
+        let mut findings: BTreeMap<usize, (u64, usize)> = BTreeMap::new();
+        for (path, size_bytes) in artifact_candidates(&report.paths) {
+            let name = last_component(path);
+            let Some(index) = ARTIFACT_KINDS
+                .iter()
+                .position(|kind| NAME_ONLY_KINDS.contains(&kind.id) && kind.matches_name(name))
+            else {
+                continue;
+            };
+            let (total, count) = findings.entry(index).or_default();
+            *total = total.saturating_add(size_bytes);
+            *count += 1;
+        }
+
+        let mut recommendations = Vec::new();
+        for (index, (total_size, count)) in findings {
+            let kind = &ARTIFACT_KINDS[index];
+            if total_size <= kind.min_size_bytes(thresholds) {
+                continue;
             }
+            recommendations.push(name_only_recommendation(kind, count, total_size));
         }
+        let traces = vec![RuleTrace {
+            rule_id: self.id().to_string(),
+            status: RuleTraceStatus::Skipped,
+            detail: "Report has no recorded dev projects; matched `node_modules` and `target` by name only.".to_string(),
+            recommendation_id: None,
+            confidence: None,
+        }];
+        self.finish(recommendations, traces)
+    }
 
+    fn finish(
+        &self,
+        recommendations: Vec<Recommendation>,
+        mut traces: Vec<RuleTrace>,
+    ) -> AnalyzerResult {
         if recommendations.is_empty() {
             traces.push(RuleTrace {
                 rule_id: self.id().to_string(),
@@ -121,12 +330,275 @@ impl Analyzer for DevArtifactsAnalyzer {
     }
 }
 
-fn is_node_modules(path: &str) -> bool {
-    path.ends_with("/node_modules") || path.ends_with("\\node_modules")
+fn name_only_recommendation(kind: &ArtifactKind, count: usize, total_size: u64) -> Recommendation {
+    Recommendation {
+        id: format!("cleanup-{}", kind.id),
+        title: format!("Review large {} directories", kind.label),
+        rationale: format!(
+            "Found {} {} director(y/ies) totaling {}. Project activity was not recorded in this report, so check that the projects are inactive before deleting.",
+            count,
+            kind.label,
+            human_bytes(total_size)
+        ),
+        confidence: 0.6,
+        target_mount: None,
+        policy_safe: true,
+        policy_rules_applied: vec![],
+        policy_rules_blocked: vec![],
+        evidence: Vec::new(),
+        next_steps: Vec::new(),
+        estimated_impact: EstimatedImpact {
+            space_saving_bytes: Some(total_size),
+            performance: None,
+            risk_notes: Some(kind.risk_note.to_string()),
+        },
+        risk_level: RiskLevel::Low,
+        priority_score: 0.0,
+        rank: 0,
+    }
+}
+
+/// Projects above artifact-named directories in the scanned paths, with the
+/// markers and activity the analyzer needs. This is the only place the analyzer's
+/// catalog touches the filesystem.
+pub(crate) fn find_dev_projects(paths: &[PathStats]) -> Vec<DevProject> {
+    let markers = ARTIFACT_KINDS
+        .iter()
+        .flat_map(|kind| kind.markers.iter().copied())
+        .collect::<Vec<_>>();
+    let mut visited = HashSet::new();
+    let mut projects = Vec::new();
+    for (path, _) in artifact_candidates(paths) {
+        for directory in ancestor_dirs(path).take(MAX_MARKER_DEPTH) {
+            if !visited.insert(directory.to_string()) {
+                continue;
+            }
+            let present = present_markers(Path::new(directory), &markers);
+            if present.is_empty() {
+                continue;
+            }
+            projects.push(DevProject {
+                path: directory.to_string(),
+                markers: present,
+                last_activity_at: project_last_activity(Path::new(directory))
+                    .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            });
+        }
+    }
+    projects.sort_by(|left, right| left.path.cmp(&right.path));
+    projects
+}
+
+fn stale_artifact_recommendation(
+    kind: &ArtifactKind,
+    findings: &mut [ArtifactFinding],
+    total_size: u64,
+    stale_days: i64,
+) -> Recommendation {
+    findings.sort_by(|left, right| {
+        right
+            .size_bytes
+            .cmp(&left.size_bytes)
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    let projects: HashSet<&str> = findings.iter().map(|item| item.project.as_str()).collect();
+    let most_recent = findings
+        .iter()
+        .map(|item| item.last_activity)
+        .max()
+        .unwrap_or_default();
+    let evidence = findings
+        .iter()
+        .take(MAX_EVIDENCE_ITEMS)
+        .map(|item| RecommendationEvidence {
+            kind: RecommendationEvidenceKind::Directory,
+            label: "Stale project artifact".to_string(),
+            detail: format!(
+                "{} in project {}, last active {}.",
+                human_bytes(item.size_bytes),
+                item.project,
+                item.last_activity.format("%Y-%m-%d")
+            ),
+            path: Some(item.path.clone()),
+            mount_point: None,
+            duplicate_hash: None,
+        })
+        .collect();
+
+    Recommendation {
+        id: format!("cleanup-{}", kind.id),
+        title: format!("Review {} directories in stale projects", kind.label),
+        rationale: format!(
+            "Found {} {} director(y/ies) totaling {} in {} project(s) with no activity for at least {} days (most recent activity {}). These can be deleted and rebuilt if the projects are resumed.",
+            findings.len(),
+            kind.label,
+            human_bytes(total_size),
+            projects.len(),
+            stale_days,
+            most_recent.format("%Y-%m-%d")
+        ),
+        confidence: 0.8,
+        target_mount: None,
+        policy_safe: true,
+        policy_rules_applied: vec![],
+        policy_rules_blocked: vec![],
+        evidence,
+        next_steps: Vec::new(),
+        estimated_impact: EstimatedImpact {
+            space_saving_bytes: Some(total_size),
+            performance: None,
+            risk_notes: Some(kind.risk_note.to_string()),
+        },
+        risk_level: RiskLevel::Low,
+        priority_score: 0.0,
+        rank: 0,
+    }
 }
 
-fn is_target_dir(path: &str) -> bool {
-    path.ends_with("/target") || path.ends_with("\\target")
+/// Artifact-named largest directories and tree nodes, outermost first. Paths inside
+/// another candidate or inside `node_modules` (packages ship their own `dist` and
+/// `build`) are dropped so their bytes are not counted twice.
+fn artifact_candidates(paths: &[PathStats]) -> Vec<(&str, u64)> {
+    let mut seen = HashSet::new();
+    let mut named = Vec::new();
+    for path_stats in paths {
+        let tree_nodes = path_stats
+            .directory_tree
+            .as_ref()
+            .map(flatten_directory_tree)
+            .unwrap_or_default();
+        let directories = path_stats
+            .largest_directories
+            .iter()
+            .map(|dir_usage| (dir_usage.path.as_str(), dir_usage.size_bytes))
+            .chain(
+                tree_nodes
+                    .into_iter()
+                    .map(|node| (node.path.as_str(), node.size_bytes)),
+            );
+        for (path, size_bytes) in directories {
+            let components = path_components(path);
+            let Some((name, ancestors)) = components.split_last() else {
+                continue;
+            };
+            if is_artifact_name(name) && !ancestors.contains(&"node_modules") && seen.insert(path) {
+                named.push((components, path, size_bytes));
+            }
+        }
+    }
+
+    named.sort_by_key(|(components, _, _)| components.len());
+    let mut candidates: Vec<(&str, u64)> = Vec::new();
+    let mut accepted: Vec<Vec<&str>> = Vec::new();
+    for (components, path, size_bytes) in named {
+        if accepted
+            .iter()
+            .any(|outer| components.starts_with(outer.as_slice()))
+        {
+            continue;
+        }
+        accepted.push(components);
+        candidates.push((path, size_bytes));
+    }
+    candidates
+}
+
+fn is_artifact_name(name: &str) -> bool {
+    ARTIFACT_KINDS.iter().any(|kind| kind.matches_name(name))
+}
+
+fn path_components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\'])
+        .filter(|component| !component.is_empty())
+        .collect()
+}
+
+fn last_component(path: &str) -> &str {
+    path_components(path).pop().unwrap_or(path)
+}
+
+/// The nearest recorded project above `artifact` that has one of `markers`.
+fn find_project<'a>(
+    artifact: &str,
+    markers: &[&str],
+    projects: &HashMap<&str, &'a DevProject>,
+) -> Option<&'a DevProject> {
+    ancestor_dirs(artifact)
+        .take(MAX_MARKER_DEPTH)
+        .filter_map(|directory| projects.get(directory).copied())
+        .find(|project| {
+            project
+                .markers
+                .iter()
+                .any(|marker| markers.contains(&marker.as_str()))
+        })
+}
+
+/// Parent directories of `path`, nearest first. Both separators are split on so a
+/// report taken on Windows is read the same way everywhere.
+fn ancestor_dirs(path: &str) -> impl Iterator<Item = &str> {
+    let mut current = path.trim_end_matches(['/', '\\']);
+    std::iter::from_fn(move || {
+        let index = current.rfind(['/', '\\'])?;
+        let parent = if index == 0 {
+            &current[..1]
+        } else {
+            &current[..index]
+        };
+        if parent == current {
+            return None;
+        }
+        current = parent;
+        Some(parent)
+    })
+}
+
+fn present_markers(dir: &Path, markers: &[&str]) -> Vec<String> {
+    let (extensions, names): (Vec<&str>, Vec<&str>) =
+        markers.iter().partition(|marker| marker.starts_with("*."));
+    let mut present = names
+        .into_iter()
+        .filter(|name| dir.join(name).exists())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if !extensions.is_empty() {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let extension = path.extension().and_then(|ext| ext.to_str());
+                for marker in &extensions {
+                    if extension == Some(&marker[2..]) {
+                        present.push(marker.to_string());
+                    }
+                }
+            }
+        }
+    }
+    present.sort();
+    present.dedup();
+    present
+}
+
+/// Latest modification time of the project directory and its top-level entries,
+/// ignoring artifact directories, which builds touch without any real project work.
+fn project_last_activity(project: &Path) -> Option<DateTime<Utc>> {
+    let modified = |path: &Path| {
+        fs::symlink_metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::<Utc>::from)
+    };
+    let mut latest = modified(project)?;
+    for entry in fs::read_dir(project).ok()?.flatten() {
+        let name = entry.file_name();
+        if is_artifact_name(&name.to_string_lossy()) {
+            continue;
+        }
+        if let Some(time) = modified(&entry.path()) {
+            latest = latest.max(time);
+        }
+    }
+    Some(latest)
 }
 
 fn human_bytes(value: u64) -> String {
@@ -142,3 +614,134 @@ fn human_bytes(value: u64) -> String {
     }
     format!("{size:.1} {}", UNITS[unit])
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use tempfile::TempDir;
+
+    use super::{find_dev_projects, DevArtifactsAnalyzer};
+    use crate::analyzers::{Analyzer, AnalyzerContext};
+    use crate::model::{DirectoryUsage, Report};
+
+    fn age(path: &Path, days: u64) {
+        let time = SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+        File::open(path)
+            .and_then(|file| file.set_modified(time))
+            .expect("set mtime");
+    }
+
+    #[test]
+    fn flags_only_artifacts_of_confirmed_stale_projects() {
+        let temp = TempDir::new().expect("tempdir");
+        let root = temp.path();
+        for dir in [
+            "old-crate/target/debug",
+            "old-crate/src",
+            "web/node_modules/react/node_modules",
+            "loose/node_modules",
+            "old-tf/.terraform",
+        ] {
+            fs::create_dir_all(root.join(dir)).expect("mkdir");
+        }
+        for file in ["old-crate/Cargo.toml", "web/package.json", "old-tf/main.tf"] {
+            fs::write(root.join(file), "").expect("write marker");
+        }
+        for path in [
+            "old-crate/Cargo.toml",
+            "old-crate/src",
+            "old-crate",
+            "old-tf/main.tf",
+            "old-tf",
+        ] {
+            age(&root.join(path), 200);
+        }
+
+        let mut report: Report =
+            serde_json::from_str(include_str!("../../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        report.generated_at = chrono::Utc::now().to_rfc3339();
+        report.paths[0].directory_tree = None;
+        report.paths[0].largest_directories = [
+            ("old-crate/target", 3_000_000_000),
+            ("old-crate/target/debug", 2_900_000_000),
+            ("web/node_modules", 2_000_000_000),
+            ("web/node_modules/react/node_modules", 1_000_000_000),
+            ("loose/node_modules", 2_000_000_000),
+            ("old-tf/.terraform", 100_000_000),
+        ]
+        .into_iter()
+        .map(|(path, size_bytes)| DirectoryUsage {
+            path: root.join(path).to_string_lossy().to_string(),
+            size_bytes,
+        })
+        .collect();
+        report.dev_projects = Some(find_dev_projects(&report.paths));
+
+        // Analysis reads only the recorded projects, so it does not depend on the
+        // scanned tree still being there.
+        drop(temp);
+        let result = DevArtifactsAnalyzer.analyze(&report, &AnalyzerContext::default());
+
+        let ids: Vec<&str> = result
+            .recommendations
+            .iter()
+            .map(|recommendation| recommendation.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["cleanup-target-dirs"]);
+        let recommendation = &result.recommendations[0];
+        assert_eq!(
+            recommendation.estimated_impact.space_saving_bytes,
+            Some(3_000_000_000)
+        );
+        assert!(recommendation.rationale.contains("in 1 project(s)"));
+        assert_eq!(recommendation.evidence.len(), 1);
+
+        let details: Vec<&str> = result
+            .traces
+            .iter()
+            .map(|trace| trace.detail.as_str())
+            .collect();
+        assert!(details.contains(
+            &"Skipped 1 `node_modules` director(y/ies) in projects active within the last 90 days."
+        ));
+        assert!(details.contains(
+            &"Ignored 1 artifact-like director(y/ies) without a project marker or readable project activity."
+        ));
+    }
+
+    #[test]
+    fn reports_without_recorded_projects_match_by_name_only() {
+        let mut report: Report =
+            serde_json::from_str(include_str!("../../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        assert!(report.dev_projects.is_none());
+        report.paths[0].directory_tree = None;
+        report.paths[0].largest_directories = [
+            ("/gone/app/node_modules", 2_000_000_000),
+            ("/gone/crate/target", 3_000_000_000),
+            ("/gone/site/dist", 4_000_000_000),
+        ]
+        .into_iter()
+        .map(|(path, size_bytes)| DirectoryUsage {
+            path: path.to_string(),
+            size_bytes,
+        })
+        .collect();
+
+        let result = DevArtifactsAnalyzer.analyze(&report, &AnalyzerContext::default());
+        let ids: Vec<&str> = result
+            .recommendations
+            .iter()
+            .map(|recommendation| recommendation.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["cleanup-node-modules", "cleanup-target-dirs"]);
+        assert!(result
+            .traces
+            .iter()
+            .any(|trace| trace.detail.contains("by name only")));
+    }
+}
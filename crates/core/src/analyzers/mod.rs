@@ -1,18 +1,33 @@
+use std::collections::BTreeMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::PathBuf;
+use std::time::Instant;
 
-use crate::model::{Recommendation, Report, RuleTrace};
+use crate::model::{PolicyProfile, Recommendation, Report, RuleTrace, RuleTraceStatus};
+use crate::policy_profile::disabled_rule_trace;
 
 pub mod dev_artifacts;
+pub mod stale_repos;
 pub mod system_caches;
 pub mod trend_analyzer;
 
 #[derive(Debug, Clone, Default)]
 pub struct AnalyzerContext {
     pub report_store_dir: Option<PathBuf>,
+    /// Thresholds and rule switches analyzers must honor.
+    pub policy_profile: PolicyProfile,
+    /// Free-form settings keyed by analyzer id, for analyzers registered by embedders.
+    pub analyzer_config: BTreeMap<String, serde_json::Value>,
+}
+
+impl AnalyzerContext {
+    pub fn config_for(&self, analyzer_id: &str) -> Option<&serde_json::Value> {
+        self.analyzer_config.get(analyzer_id)
+    }
 }
 
 /// A trait for application-specific or pattern-specific analysis.
-pub trait Analyzer {
+pub trait Analyzer: Send + Sync {
     fn id(&self) -> &'static str;
     fn analyze(&self, report: &Report, context: &AnalyzerContext) -> AnalyzerResult;
 }
@@ -24,16 +39,237 @@ pub struct AnalyzerResult {
     pub traces: Vec<RuleTrace>,
 }
 
-/// Runs all registered analyzers and returns their combined results.
+/// Ordered set of analyzers run by the recommendation engine. Ids are unique;
+/// registering an analyzer with an existing id replaces it in place.
+pub struct AnalyzerRegistry {
+    analyzers: Vec<Box<dyn Analyzer>>,
+}
+
+impl Default for AnalyzerRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(dev_artifacts::DevArtifactsAnalyzer);
+        registry.register(system_caches::SystemCachesAnalyzer);
+        registry.register(trend_analyzer::TrendAnalyzer);
+        registry.register(stale_repos::StaleReposAnalyzer);
+        registry
+    }
+}
+
+impl AnalyzerRegistry {
+    /// Registry without the built-in analyzers.
+    pub fn empty() -> Self {
+        Self {
+            analyzers: Vec::new(),
+        }
+    }
+
+    /// Adds an analyzer, replacing any registered analyzer with the same id.
+    pub fn register(&mut self, analyzer: impl Analyzer + 'static) -> &mut Self {
+        let analyzer: Box<dyn Analyzer> = Box::new(analyzer);
+        match self
+            .analyzers
+            .iter()
+            .position(|existing| existing.id() == analyzer.id())
+        {
+            Some(index) => self.analyzers[index] = analyzer,
+            None => self.analyzers.push(analyzer),
+        }
+        self
+    }
+
+    /// Removes the analyzer with `id`, returning whether one was registered.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.analyzers.len();
+        self.analyzers.retain(|analyzer| analyzer.id() != id);
+        self.analyzers.len() != before
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.analyzers.iter().any(|analyzer| analyzer.id() == id)
+    }
+
+    pub fn ids(&self) -> Vec<&'static str> {
+        self.analyzers
+            .iter()
+            .map(|analyzer| analyzer.id())
+            .collect()
+    }
+
+    /// Runs every registered analyzer in order. Each result ends with an
+    /// `analyzer:<id>` trace recording the runtime and outcome; a panicking
+    /// analyzer is contained and reported as rejected.
+    pub fn run(&self, report: &Report, context: &AnalyzerContext) -> Vec<AnalyzerResult> {
+        self.analyzers
+            .iter()
+            .map(|analyzer| run_analyzer(analyzer.as_ref(), report, context))
+            .collect()
+    }
+}
+
+fn run_analyzer(
+    analyzer: &dyn Analyzer,
+    report: &Report,
+    context: &AnalyzerContext,
+) -> AnalyzerResult {
+    let id = analyzer.id();
+    if context.policy_profile.is_rule_disabled(id) {
+        return AnalyzerResult {
+            recommendations: Vec::new(),
+            traces: vec![disabled_rule_trace(id)],
+        };
+    }
+
+    let started = Instant::now();
+    let outcome = catch_unwind(AssertUnwindSafe(|| analyzer.analyze(report, context)));
+    let elapsed_ms = started.elapsed().as_millis();
+    match outcome {
+        Ok(mut result) => {
+            let count = result.recommendations.len();
+            result.traces.push(RuleTrace {
+                rule_id: format!("analyzer:{id}"),
+                status: if count > 0 {
+                    RuleTraceStatus::Emitted
+                } else {
+                    RuleTraceStatus::Skipped
+                },
+                detail: format!(
+                    "Analyzer completed in {elapsed_ms} ms with {count} recommendation(s) and {} trace(s).",
+                    result.traces.len()
+                ),
+                recommendation_id: None,
+                confidence: None,
+            });
+            result
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            AnalyzerResult {
+                recommendations: Vec::new(),
+                traces: vec![RuleTrace {
+                    rule_id: format!("analyzer:{id}"),
+                    status: RuleTraceStatus::Rejected,
+                    detail: format!("Analyzer failed after {elapsed_ms} ms: {message}"),
+                    recommendation_id: None,
+                    confidence: None,
+                }],
+            }
+        }
+    }
+}
+
+/// Runs the built-in analyzers and returns their combined results.
 pub fn run_analyzers(report: &Report, context: &AnalyzerContext) -> Vec<AnalyzerResult> {
-    let analyzers: Vec<Box<dyn Analyzer>> = vec![
-        Box::new(dev_artifacts::DevArtifactsAnalyzer),
-        Box::new(system_caches::SystemCachesAnalyzer),
-        Box::new(trend_analyzer::TrendAnalyzer),
-    ];
-
-    analyzers
-        .iter()
-        .map(|analyzer| analyzer.analyze(report, context))
-        .collect()
+    AnalyzerRegistry::default().run(report, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Analyzer, AnalyzerContext, AnalyzerRegistry, AnalyzerResult};
+    use crate::model::{EstimatedImpact, Recommendation, Report, RiskLevel, RuleTraceStatus};
+    use crate::recommend::generate_recommendation_bundle_with_context;
+
+    struct ReviewArchives;
+
+    impl Analyzer for ReviewArchives {
+        fn id(&self) -> &'static str {
+            "review_archives"
+        }
+
+        fn analyze(&self, _report: &Report, context: &AnalyzerContext) -> AnalyzerResult {
+            let title = context
+                .config_for(self.id())
+                .and_then(|config| config.get("title"))
+                .and_then(|title| title.as_str())
+                .unwrap_or("Review archives");
+            AnalyzerResult {
+                recommendations: vec![Recommendation {
+                    id: "review-archives".to_string(),
+                    title: title.to_string(),
+                    rationale: "Embedded analyzer output.".to_string(),
+                    confidence: 0.6,
+                    target_mount: None,
+                    policy_safe: true,
+                    policy_rules_applied: Vec::new(),
+                    policy_rules_blocked: Vec::new(),
+                    evidence: Vec::new(),
+                    next_steps: Vec::new(),
+                    estimated_impact: EstimatedImpact {
+                        space_saving_bytes: None,
+                        performance: None,
+                        risk_notes: None,
+                    },
+                    risk_level: RiskLevel::Low,
+                    priority_score: 0.0,
+                    rank: 0,
+                }],
+                traces: Vec::new(),
+            }
+        }
+    }
+
+    struct BrokenCaches;
+
+    impl Analyzer for BrokenCaches {
+        fn id(&self) -> &'static str {
+            "system_caches"
+        }
+
+        fn analyze(&self, _report: &Report, _context: &AnalyzerContext) -> AnalyzerResult {
+            panic!("cache catalog unavailable");
+        }
+    }
+
+    #[test]
+    fn registry_adds_replaces_and_removes_analyzers_with_traced_outcomes() {
+        let report: Report =
+            serde_json::from_str(include_str!("../../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        let mut registry = AnalyzerRegistry::default();
+        registry.register(ReviewArchives).register(BrokenCaches);
+        assert!(registry.remove("trend_analyzer"));
+        assert!(!registry.remove("trend_analyzer"));
+        assert_eq!(
+            registry.ids(),
+            vec![
+                "dev_artifacts",
+                "system_caches",
+                "stale_repos",
+                "review_archives"
+            ]
+        );
+
+        let mut context = AnalyzerContext::default();
+        context.analyzer_config.insert(
+            "review_archives".to_string(),
+            serde_json::json!({ "title": "Review archive folders" }),
+        );
+        let bundle = generate_recommendation_bundle_with_context(&report, &context, &registry);
+
+        let recommendation = bundle
+            .recommendations
+            .iter()
+            .find(|recommendation| recommendation.id == "review-archives")
+            .expect("embedded analyzer recommendation");
+        assert_eq!(recommendation.title, "Review archive folders");
+
+        let trace = |rule_id: &str| {
+            bundle
+                .rule_traces
+                .iter()
+                .find(|trace| trace.rule_id == rule_id)
+        };
+        let emitted = trace("analyzer:review_archives").expect("runtime trace");
+        assert_eq!(emitted.status, RuleTraceStatus::Emitted);
+        assert!(emitted.detail.contains(" ms with 1 recommendation(s)"));
+        let failed = trace("analyzer:system_caches").expect("failure trace");
+        assert_eq!(failed.status, RuleTraceStatus::Rejected);
+        assert!(failed.detail.contains("cache catalog unavailable"));
+        assert!(trace("analyzer:dev_artifacts").is_some());
+        assert!(trace("analyzer:trend_analyzer").is_none());
+    }
 }
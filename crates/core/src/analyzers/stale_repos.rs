@@ -0,0 +1,288 @@
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerResult};
+use crate::model::{
+    Category, EstimatedImpact, GitRepository, Recommendation, RecommendationEvidence,
+    RecommendationEvidenceKind, Report, RiskLevel, RuleTrace, RuleTraceStatus,
+};
+
+pub struct StaleReposAnalyzer;
+
+const MAX_EVIDENCE_ITEMS: usize = 10;
+
+impl Analyzer for StaleReposAnalyzer {
+    fn id(&self) -> &'static str {
+        "stale_repos"
+    }
+
+    fn analyze(&self, report: &Report, context: &AnalyzerContext) -> AnalyzerResult {
+        let months = context.policy_profile.thresholds.stale_repo_months;
+        let mut traces = Vec::new();
+        if report.git_repositories.is_empty() {
+            traces.push(RuleTrace {
+                rule_id: self.id().to_string(),
+                status: RuleTraceStatus::Skipped,
+                detail: "No git repositories found in scanned paths.".to_string(),
+                recommendation_id: None,
+                confidence: None,
+            });
+            return AnalyzerResult {
+                recommendations: Vec::new(),
+                traces,
+            };
+        }
+
+        let now = report
+            .generated_at
+            .parse::<DateTime<Utc>>()
+            .unwrap_or_else(|_| Utc::now());
+        let cutoff = now - Duration::days(i64::from(months) * 30);
+        let mut stale: Vec<(&GitRepository, DateTime<Utc>)> = Vec::new();
+        let mut unknown = 0usize;
+        for repository in &report.git_repositories {
+            match last_activity(repository) {
+                Some(time) if time < cutoff => stale.push((repository, time)),
+                Some(_) => {}
+                None => unknown += 1,
+            }
+        }
+        if unknown > 0 {
+            traces.push(RuleTrace {
+                rule_id: self.id().to_string(),
+                status: RuleTraceStatus::Skipped,
+                detail: format!(
+                    "Skipped {unknown} repositor(y/ies) without readable reflogs or file times."
+                ),
+                recommendation_id: None,
+                confidence: None,
+            });
+        }
+        if stale.is_empty() {
+            traces.push(RuleTrace {
+                rule_id: self.id().to_string(),
+                status: RuleTraceStatus::Skipped,
+                detail: format!(
+                    "None of {} repositor(y/ies) has been untouched for more than {months} months.",
+                    report.git_repositories.len()
+                ),
+                recommendation_id: None,
+                confidence: None,
+            });
+            return AnalyzerResult {
+                recommendations: Vec::new(),
+                traces,
+            };
+        }
+
+        let recommendation = stale_repos_recommendation(report, &mut stale, months);
+        traces.push(RuleTrace {
+            rule_id: self.id().to_string(),
+            status: RuleTraceStatus::Emitted,
+            detail: format!("Emitted recommendation '{}'", recommendation.id),
+            recommendation_id: Some(recommendation.id.clone()),
+            confidence: Some(recommendation.confidence),
+        });
+        AnalyzerResult {
+            recommendations: vec![recommendation],
+            traces,
+        }
+    }
+}
+
+fn stale_repos_recommendation(
+    report: &Report,
+    stale: &mut [(&GitRepository, DateTime<Utc>)],
+    months: u32,
+) -> Recommendation {
+    stale.sort_by(|(left, _), (right, _)| {
+        repository_bytes(right)
+            .cmp(&repository_bytes(left))
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    let total_bytes: u64 = stale.iter().map(|(repo, _)| repository_bytes(repo)).sum();
+    let pack_bytes: u64 = stale.iter().map(|(repo, _)| repo.pack_bytes).sum();
+    let with_changes = stale
+        .iter()
+        .filter(|(repo, _)| repo.files_newer_than_index > 0)
+        .count();
+    let in_work_roots = stale
+        .iter()
+        .filter(|(repo, _)| in_work_category(report, repo))
+        .count();
+
+    let evidence = stale
+        .iter()
+        .take(MAX_EVIDENCE_ITEMS)
+        .map(|(repo, activity)| {
+            let mut detail = format!(
+                "{} ({} in packs), last commit {}, last activity {}",
+                human_bytes(repository_bytes(repo)),
+                human_bytes(repo.pack_bytes),
+                repo.last_commit_at
+                    .as_deref()
+                    .and_then(|text| text.get(..10))
+                    .unwrap_or("unknown"),
+                activity.format("%Y-%m-%d")
+            );
+            if repo.files_newer_than_index > 0 {
+                detail.push_str(&format!(
+                    "; {} file(s) changed after the index was written, possibly uncommitted",
+                    repo.files_newer_than_index
+                ));
+            }
+            if in_work_category(report, repo) {
+                detail.push_str("; inside a root categorized as work");
+            }
+            detail.push('.');
+            RecommendationEvidence {
+                kind: RecommendationEvidenceKind::Directory,
+                label: "Stale repository".to_string(),
+                detail,
+                path: Some(repo.path.clone()),
+                mount_point: repo.disk_mount.clone(),
+                duplicate_hash: None,
+            }
+        })
+        .collect();
+
+    let mut next_steps = vec![
+        "Push every branch to its remote, or create a `git bundle create <name>.bundle --all` per repository.".to_string(),
+        "Move the bundles or working copies to an archive disk, then remove the local clones manually.".to_string(),
+    ];
+    if with_changes > 0 {
+        next_steps.insert(
+            0,
+            "Run `git status` in the flagged repositories and commit or stash any work before archiving.".to_string(),
+        );
+    }
+
+    Recommendation {
+        id: "archive-stale-repos".to_string(),
+        title: "Archive git repositories that have not been touched in months".to_string(),
+        rationale: format!(
+            "Found {} git repositor(y/ies) with no commits, checkouts or file changes for more than {} months, holding {} ({} in pack files). {} of them sit in roots categorized as work. Archiving them keeps history while freeing the working disk.",
+            stale.len(),
+            months,
+            human_bytes(total_bytes),
+            human_bytes(pack_bytes),
+            in_work_roots
+        ),
+        confidence: if in_work_roots * 2 >= stale.len() { 0.75 } else { 0.65 },
+        target_mount: None,
+        policy_safe: true,
+        policy_rules_applied: vec![],
+        policy_rules_blocked: vec![],
+        evidence,
+        next_steps,
+        estimated_impact: EstimatedImpact {
+            space_saving_bytes: Some(total_bytes),
+            performance: None,
+            risk_notes: Some(if with_changes > 0 {
+                format!("{with_changes} repositor(y/ies) may have uncommitted changes that exist nowhere else.")
+            } else {
+                "Unpushed branches and stashes exist only locally; bundle them before removing a clone.".to_string()
+            }),
+        },
+        risk_level: if with_changes > 0 {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        },
+        priority_score: 0.0,
+        rank: 0,
+    }
+}
+
+/// Latest sign of use: any reflog entry, commit or working-tree modification.
+fn last_activity(repository: &GitRepository) -> Option<DateTime<Utc>> {
+    [
+        repository.last_commit_at.as_deref(),
+        repository.last_ref_update_at.as_deref(),
+        repository.latest_worktree_modified_at.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|text| text.parse::<DateTime<Utc>>().ok())
+    .max()
+}
+
+fn repository_bytes(repository: &GitRepository) -> u64 {
+    repository
+        .git_dir_bytes
+        .saturating_add(repository.worktree_bytes)
+}
+
+fn in_work_category(report: &Report, repository: &GitRepository) -> bool {
+    report.categories.iter().any(|suggestion| {
+        suggestion.category == Category::Work
+            && Path::new(&repository.path).starts_with(Path::new(&suggestion.target))
+    })
+}
+
+fn human_bytes(value: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if value == 0 {
+        return "0 B".to_string();
+    }
+    let mut size = value as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StaleReposAnalyzer;
+    use crate::analyzers::{Analyzer, AnalyzerContext};
+    use crate::model::{GitRepository, Report, RiskLevel};
+
+    fn repository(path: &str, last_commit: &str, files_newer_than_index: u64) -> GitRepository {
+        GitRepository {
+            path: path.to_string(),
+            disk_mount: Some("D:\\".to_string()),
+            last_commit_at: Some(last_commit.to_string()),
+            last_ref_update_at: Some(last_commit.to_string()),
+            latest_worktree_modified_at: None,
+            index_modified_at: None,
+            files_newer_than_index,
+            pack_bytes: 300_000_000,
+            git_dir_bytes: 400_000_000,
+            worktree_bytes: 600_000_000,
+        }
+    }
+
+    #[test]
+    fn archives_only_repos_untouched_past_the_threshold() {
+        let mut report: Report =
+            serde_json::from_str(include_str!("../../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        report.generated_at = "2026-06-01T00:00:00Z".to_string();
+        report.git_repositories = vec![
+            repository("D:\\Work\\old-tool", "2024-01-10T00:00:00Z", 0),
+            repository("D:\\Work\\half-done", "2024-11-02T00:00:00Z", 3),
+            repository("D:\\Work\\current", "2026-05-20T00:00:00Z", 5),
+        ];
+
+        let result = StaleReposAnalyzer.analyze(&report, &AnalyzerContext::default());
+        assert_eq!(result.recommendations.len(), 1);
+        let recommendation = &result.recommendations[0];
+        assert_eq!(recommendation.id, "archive-stale-repos");
+        assert!(recommendation
+            .rationale
+            .starts_with("Found 2 git repositor(y/ies)"));
+        assert_eq!(
+            recommendation.estimated_impact.space_saving_bytes,
+            Some(2_000_000_000)
+        );
+        assert_eq!(recommendation.risk_level, RiskLevel::Medium);
+        assert_eq!(recommendation.evidence.len(), 2);
+        assert!(recommendation.evidence[0]
+            .detail
+            .contains("3 file(s) changed after the index was written"));
+    }
+}
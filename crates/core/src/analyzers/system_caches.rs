@@ -2,18 +2,55 @@ use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerResult};
 use crate::model::{
     EstimatedImpact, Recommendation, Report, RiskLevel, RuleTrace, RuleTraceStatus,
 };
-use std::collections::HashMap;
-use std::path::Path;
+use crate::tree::flatten_directory_tree;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 pub struct SystemCachesAnalyzer;
 
-const MIN_CACHE_SIZE_BYTES: u64 = 500_000_000; // 500 MB
-
 struct CacheTarget {
     id: &'static str,
     name: &'static str,
     paths: Vec<String>,
     cleanup_command: &'static str,
+    /// Only directories below `paths` count, for catch-all roots such as `~/.cache`
+    /// whose entries are individual caches.
+    children_only: bool,
+}
+
+impl CacheTarget {
+    fn new(
+        id: &'static str,
+        name: &'static str,
+        paths: Vec<PathBuf>,
+        cleanup_command: &'static str,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            paths: paths
+                .into_iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            cleanup_command,
+            children_only: false,
+        }
+    }
+
+    fn children_only(mut self) -> Self {
+        self.children_only = true;
+        self
+    }
+
+    /// Length of the most specific cache root containing `dir`, if any.
+    fn match_depth(&self, dir: &Path) -> Option<usize> {
+        self.paths
+            .iter()
+            .map(Path::new)
+            .filter(|root| dir.starts_with(root) && !(self.children_only && dir == *root))
+            .map(|root| root.components().count())
+            .max()
+    }
 }
 
 impl Analyzer for SystemCachesAnalyzer {
@@ -21,7 +58,8 @@ impl Analyzer for SystemCachesAnalyzer {
         "system_caches"
     }
 
-    fn analyze(&self, report: &Report, _context: &AnalyzerContext) -> AnalyzerResult {
+    fn analyze(&self, report: &Report, context: &AnalyzerContext) -> AnalyzerResult {
+        let thresholds = &context.policy_profile.thresholds;
         let mut recommendations = Vec::new();
         let mut traces = Vec::new();
         let cache_targets = get_os_cache_targets();
@@ -40,25 +78,11 @@ impl Analyzer for SystemCachesAnalyzer {
             };
         }
 
-        let mut findings: HashMap<&str, (u64, Vec<String>)> = HashMap::new();
-
-        for target in &cache_targets {
-            for path_stats in &report.paths {
-                for dir_usage in &path_stats.largest_directories {
-                    for cache_path in &target.paths {
-                        if Path::new(&dir_usage.path) == Path::new(cache_path) {
-                            let entry = findings.entry(target.id).or_default();
-                            entry.0 += dir_usage.size_bytes;
-                            entry.1.push(dir_usage.path.clone());
-                        }
-                    }
-                }
-            }
-        }
+        let findings = find_cache_usage(report, &cache_targets);
 
         for target in &cache_targets {
             if let Some((total_size, paths)) = findings.get(target.id) {
-                if *total_size > MIN_CACHE_SIZE_BYTES {
+                if *total_size > thresholds.cache_min_size_bytes {
                     let rec = Recommendation {
                         id: format!("cleanup-{}", target.id),
                         title: format!("Review {} cache", target.name),
@@ -84,6 +108,8 @@ impl Analyzer for SystemCachesAnalyzer {
                             ),
                         },
                         risk_level: RiskLevel::Low,
+                        priority_score: 0.0,
+                        rank: 0,
                     };
                     recommendations.push(rec);
                 }
@@ -117,36 +143,290 @@ impl Analyzer for SystemCachesAnalyzer {
     }
 }
 
-#[cfg(target_os = "windows")]
+/// Sums scanned directories at or below each cache root. Every directory goes to
+/// the target with the most specific matching root, and directories nested in an
+/// already matched one are skipped so their bytes are not counted twice.
+fn find_cache_usage<'a>(
+    report: &Report,
+    targets: &'a [CacheTarget],
+) -> HashMap<&'a str, (u64, Vec<String>)> {
+    let mut seen = HashSet::new();
+    let mut matched: Vec<(&str, &str, u64)> = Vec::new();
+    for path_stats in &report.paths {
+        let tree_nodes = path_stats
+            .directory_tree
+            .as_ref()
+            .map(flatten_directory_tree)
+            .unwrap_or_default();
+        let directories = path_stats
+            .largest_directories
+            .iter()
+            .map(|dir_usage| (dir_usage.path.as_str(), dir_usage.size_bytes))
+            .chain(
+                tree_nodes
+                    .into_iter()
+                    .map(|node| (node.path.as_str(), node.size_bytes)),
+            );
+        for (path, size_bytes) in directories {
+            if !seen.insert(path) {
+                continue;
+            }
+            let best = targets
+                .iter()
+                .filter_map(|target| {
+                    target
+                        .match_depth(Path::new(path))
+                        .map(|depth| (depth, target))
+                })
+                .max_by_key(|(depth, _)| *depth);
+            if let Some((_, target)) = best {
+                matched.push((target.id, path, size_bytes));
+            }
+        }
+    }
+
+    // Shorter paths first, so an outer cache directory claims its children.
+    matched.sort_by_key(|(_, path, _)| Path::new(path).components().count());
+    let mut findings: HashMap<&str, (u64, Vec<String>)> = HashMap::new();
+    let mut claimed: Vec<&str> = Vec::new();
+    for (target_id, path, size_bytes) in matched {
+        if claimed
+            .iter()
+            .any(|outer| Path::new(path).starts_with(Path::new(outer)))
+        {
+            continue;
+        }
+        claimed.push(path);
+        let entry = findings.entry(target_id).or_default();
+        entry.0 += size_bytes;
+        entry.1.push(path.to_string());
+    }
+    findings
+}
+
 fn get_os_cache_targets() -> Vec<CacheTarget> {
+    let env = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+    if cfg!(target_os = "windows") {
+        windows_cache_targets(&env)
+    } else if cfg!(target_os = "macos") {
+        macos_cache_targets(&env)
+    } else {
+        linux_cache_targets(&env)
+    }
+}
+
+fn windows_cache_targets(env: &dyn Fn(&str) -> Option<String>) -> Vec<CacheTarget> {
     let mut targets = Vec::new();
-    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+    if let Some(local_app_data) = env("LOCALAPPDATA") {
         targets.push(CacheTarget {
             id: "docker-wsl",
             name: "Docker WSL data",
             paths: vec![format!("{}\\Docker\\wsl", local_app_data)],
             cleanup_command: "docker system prune -a",
+            children_only: false,
         });
         targets.push(CacheTarget {
             id: "pip-cache",
             name: "pip cache",
             paths: vec![format!("{}\\pip\\Cache", local_app_data)],
             cleanup_command: "pip cache purge",
+            children_only: false,
         });
         targets.push(CacheTarget {
             id: "nvidia-glcache",
             name: "NVIDIA GL Cache",
             paths: vec![format!("{}\\NVIDIA\\GLCache", local_app_data)],
             cleanup_command: "Manually delete files",
+            children_only: false,
         });
     }
     targets
 }
 
-#[cfg(not(target_os = "windows"))]
-fn get_os_cache_targets() -> Vec<CacheTarget> {
-    // TODO: Add paths for Linux and macOS
-    Vec::new()
+/// Package-manager caches that live under the home directory on every Unix.
+fn home_toolchain_cache_targets(
+    home: &Path,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Vec<CacheTarget> {
+    let cargo_home = env("CARGO_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".cargo"));
+    let gradle_home = env("GRADLE_USER_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".gradle"));
+    let npm_cache = env("npm_config_cache")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".npm"));
+    vec![
+        CacheTarget::new(
+            "cargo-registry",
+            "Cargo registry",
+            vec![cargo_home.join("registry"), cargo_home.join("git")],
+            "cargo cache --autoclean (cargo-cache), or delete ~/.cargo/registry/cache",
+        ),
+        CacheTarget::new(
+            "npm-cache",
+            "npm cache",
+            vec![npm_cache],
+            "npm cache clean --force",
+        ),
+        CacheTarget::new(
+            "gradle-cache",
+            "Gradle cache",
+            vec![
+                gradle_home.join("caches"),
+                gradle_home.join("wrapper").join("dists"),
+            ],
+            "gradle --stop, then delete ~/.gradle/caches",
+        ),
+        CacheTarget::new(
+            "maven-repository",
+            "Maven local repository",
+            vec![home.join(".m2").join("repository")],
+            "mvn dependency:purge-local-repository",
+        ),
+    ]
+}
+
+fn linux_cache_targets(env: &dyn Fn(&str) -> Option<String>) -> Vec<CacheTarget> {
+    let mut targets = vec![
+        CacheTarget::new(
+            "docker-data",
+            "Docker data",
+            vec![PathBuf::from("/var/lib/docker")],
+            "docker system prune -a",
+        ),
+        CacheTarget::new(
+            "journal-logs",
+            "systemd journal",
+            vec![PathBuf::from("/var/log/journal")],
+            "journalctl --vacuum-time=4weeks",
+        ),
+        CacheTarget::new(
+            "snap-revisions",
+            "snap package revisions",
+            vec![PathBuf::from("/var/lib/snapd/snaps")],
+            "snap set system refresh.retain=2, then snap remove --revision for disabled revisions",
+        ),
+    ];
+    let mut container_paths = vec![PathBuf::from("/var/lib/containers/storage")];
+    let mut flatpak_paths = vec![PathBuf::from("/var/lib/flatpak")];
+
+    if let Some(home) = env("HOME").map(PathBuf::from) {
+        let cache_home = env("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".cache"));
+        let data_home = env("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".local").join("share"));
+        let pip_cache = env("PIP_CACHE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| cache_home.join("pip"));
+        container_paths.push(data_home.join("containers").join("storage"));
+        flatpak_paths.push(data_home.join("flatpak"));
+
+        targets.extend(home_toolchain_cache_targets(&home, env));
+        targets.extend([
+            CacheTarget::new("pip-cache", "pip cache", vec![pip_cache], "pip cache purge"),
+            CacheTarget::new(
+                "yarn-cache",
+                "Yarn cache",
+                vec![cache_home.join("yarn")],
+                "yarn cache clean",
+            ),
+            CacheTarget::new(
+                "pnpm-store",
+                "pnpm store",
+                vec![data_home.join("pnpm").join("store")],
+                "pnpm store prune",
+            ),
+            CacheTarget::new(
+                "user-cache",
+                "user application",
+                vec![cache_home],
+                "Delete unused entries under ~/.cache; applications rebuild them on demand",
+            )
+            .children_only(),
+        ]);
+    }
+
+    targets.push(CacheTarget::new(
+        "container-storage",
+        "Podman/containers storage",
+        container_paths,
+        "podman system prune -a",
+    ));
+    targets.push(CacheTarget::new(
+        "flatpak-unused",
+        "Flatpak runtime",
+        flatpak_paths,
+        "flatpak uninstall --unused",
+    ));
+    targets
+}
+
+fn macos_cache_targets(env: &dyn Fn(&str) -> Option<String>) -> Vec<CacheTarget> {
+    let Some(home) = env("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let caches = home.join("Library").join("Caches");
+    let mut targets = home_toolchain_cache_targets(&home, env);
+    targets.extend([
+        CacheTarget::new(
+            "pip-cache",
+            "pip cache",
+            vec![env("PIP_CACHE_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| caches.join("pip"))],
+            "pip cache purge",
+        ),
+        CacheTarget::new(
+            "yarn-cache",
+            "Yarn cache",
+            vec![caches.join("Yarn")],
+            "yarn cache clean",
+        ),
+        CacheTarget::new(
+            "pnpm-store",
+            "pnpm store",
+            vec![home.join("Library").join("pnpm").join("store")],
+            "pnpm store prune",
+        ),
+        CacheTarget::new(
+            "homebrew-cache",
+            "Homebrew cache",
+            vec![caches.join("Homebrew")],
+            "brew cleanup --prune=all",
+        ),
+        CacheTarget::new(
+            "docker-desktop",
+            "Docker Desktop data",
+            vec![home
+                .join("Library")
+                .join("Containers")
+                .join("com.docker.docker")
+                .join("Data")],
+            "docker system prune -a",
+        ),
+        CacheTarget::new(
+            "ios-simulator-cache",
+            "iOS Simulator",
+            vec![home
+                .join("Library")
+                .join("Developer")
+                .join("CoreSimulator")
+                .join("Caches")],
+            "xcrun simctl delete unavailable",
+        ),
+        CacheTarget::new(
+            "user-cache",
+            "user application",
+            vec![caches],
+            "Delete unused entries under ~/Library/Caches; applications rebuild them on demand",
+        )
+        .children_only(),
+    ]);
+    targets
 }
 
 fn human_bytes(value: u64) -> String {
@@ -162,3 +442,49 @@ fn human_bytes(value: u64) -> String {
     }
     format!("{size:.1} {}", UNITS[unit])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cache_usage, linux_cache_targets};
+    use crate::model::{DirectoryUsage, Report};
+
+    #[test]
+    fn linux_catalog_matches_nested_cache_directories_once() {
+        let env = |key: &str| match key {
+            "HOME" => Some("/home/ana".to_string()),
+            "CARGO_HOME" => Some("/opt/cargo".to_string()),
+            _ => None,
+        };
+        let targets = linux_cache_targets(&env);
+        let mut report: Report =
+            serde_json::from_str(include_str!("../../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        report.paths[0].directory_tree = None;
+        report.paths[0].largest_directories = [
+            ("/home/ana/.cache", 9_000),
+            ("/home/ana/.cache/pip", 3_000),
+            ("/home/ana/.cache/pip/http", 2_500),
+            ("/home/ana/.cache/thumbnails", 1_000),
+            ("/opt/cargo/registry/cache", 4_000),
+            ("/home/ana/.cargo/registry", 7_000),
+            ("/var/lib/docker/overlay2", 6_000),
+            ("/var/lib", 20_000),
+        ]
+        .into_iter()
+        .map(|(path, size_bytes)| DirectoryUsage {
+            path: path.to_string(),
+            size_bytes,
+        })
+        .collect();
+
+        let findings = find_cache_usage(&report, &targets);
+        assert_eq!(
+            findings["pip-cache"],
+            (3_000, vec!["/home/ana/.cache/pip".to_string()])
+        );
+        assert_eq!(findings["user-cache"].0, 1_000);
+        assert_eq!(findings["cargo-registry"].0, 4_000);
+        assert_eq!(findings["docker-data"].0, 6_000);
+        assert_eq!(findings.len(), 4);
+    }
+}
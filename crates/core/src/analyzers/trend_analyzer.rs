@@ -1,62 +1,59 @@
 use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerResult};
 use crate::history;
 use crate::model::{
-    EstimatedImpact, Recommendation, Report, RiskLevel, RuleTrace, RuleTraceStatus,
+    CapacityForecast, EstimatedImpact, ForecastScope, PolicyThresholds, Recommendation,
+    RecommendationEvidence, RecommendationEvidenceKind, Report, RiskLevel, RuleTrace,
+    RuleTraceStatus,
 };
+use crate::recommend::AFFECTED_DISK_LABEL;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-pub struct TrendAnalyzer;
+/// Growing roots listed as evidence on a disk-full forecast.
+const MAX_GROWING_ROOTS: usize = 3;
 
-const MIN_PCT_CHANGE: f64 = 0.10; // 10%
-const MIN_ABS_CHANGE_BYTES: u64 = 1_073_741_824; // 1 GB
+pub struct TrendAnalyzer;
 
 impl Analyzer for TrendAnalyzer {
     fn id(&self) -> &'static str {
         "trend_analyzer"
     }
 
-    fn analyze(&self, _report: &Report, context: &AnalyzerContext) -> AnalyzerResult {
+    fn analyze(&self, report: &Report, context: &AnalyzerContext) -> AnalyzerResult {
         let mut result = AnalyzerResult::default();
-        let history = match history::load_history(context.report_store_dir.as_deref()) {
-            Ok(h) => h,
-            Err(e) => {
-                result.traces.push(RuleTrace {
-                    rule_id: self.id().to_string(),
-                    status: RuleTraceStatus::Skipped,
-                    detail: format!("Failed to load scan history: {}", e),
-                    recommendation_id: None,
-                    confidence: None,
-                });
-                return result;
-            }
-        };
+        let thresholds = &context.policy_profile.thresholds;
+        analyze_capacity_forecasts(report, thresholds, &mut result.recommendations);
+        let forecast_mounts: HashSet<String> = result
+            .recommendations
+            .iter()
+            .flat_map(|rec| &rec.evidence)
+            .filter(|evidence| evidence.label == AFFECTED_DISK_LABEL)
+            .filter_map(|evidence| evidence.mount_point.clone())
+            .collect();
 
-        if history.snapshots.len() < 2 {
-            result.traces.push(RuleTrace {
+        match history::load_history(context.report_store_dir.as_deref()) {
+            Ok(history) if history.snapshots.len() >= 2 => analyze_recent_trends(
+                &history,
+                thresholds,
+                &forecast_mounts,
+                &mut result.recommendations,
+            ),
+            Ok(_) => result.traces.push(RuleTrace {
                 rule_id: self.id().to_string(),
                 status: RuleTraceStatus::Skipped,
                 detail: "Not enough historical data to analyze trends.".to_string(),
                 recommendation_id: None,
                 confidence: None,
-            });
-            return result;
+            }),
+            Err(e) => result.traces.push(RuleTrace {
+                rule_id: self.id().to_string(),
+                status: RuleTraceStatus::Skipped,
+                detail: format!("Failed to load scan history: {}", e),
+                recommendation_id: None,
+                confidence: None,
+            }),
         }
 
-        let latest = &history.snapshots[history.snapshots.len() - 1];
-        let previous = &history.snapshots[history.snapshots.len() - 2];
-
-        let latest_ts = latest.generated_at.parse::<DateTime<Utc>>().ok();
-        let previous_ts = previous.generated_at.parse::<DateTime<Utc>>().ok();
-        let duration_days = if let (Some(l), Some(p)) = (latest_ts, previous_ts) {
-            (l - p).num_days()
-        } else {
-            0
-        };
-
-        analyze_disk_trends(latest, previous, duration_days, &mut result.recommendations);
-        analyze_path_trends(latest, previous, duration_days, &mut result.recommendations);
-
         if result.recommendations.is_empty() {
             result.traces.push(RuleTrace {
                 rule_id: self.id().to_string(),
@@ -81,10 +78,171 @@ impl Analyzer for TrendAnalyzer {
     }
 }
 
+/// Compares the last two snapshots against the fixed change thresholds. Disks that
+/// already carry a disk-full forecast are skipped.
+fn analyze_recent_trends(
+    history: &crate::model::ScanHistory,
+    thresholds: &PolicyThresholds,
+    forecast_mounts: &HashSet<String>,
+    recommendations: &mut Vec<Recommendation>,
+) {
+    let latest = &history.snapshots[history.snapshots.len() - 1];
+    let previous = &history.snapshots[history.snapshots.len() - 2];
+
+    let latest_ts = latest.generated_at.parse::<DateTime<Utc>>().ok();
+    let previous_ts = previous.generated_at.parse::<DateTime<Utc>>().ok();
+    let duration_days = if let (Some(l), Some(p)) = (latest_ts, previous_ts) {
+        (l - p).num_days()
+    } else {
+        0
+    };
+
+    analyze_disk_trends(
+        latest,
+        previous,
+        duration_days,
+        thresholds,
+        forecast_mounts,
+        recommendations,
+    );
+    analyze_path_trends(latest, previous, duration_days, thresholds, recommendations);
+}
+
+/// Warns about disks whose fitted growth fills them within the forecast horizon.
+fn analyze_capacity_forecasts(
+    report: &Report,
+    thresholds: &PolicyThresholds,
+    recommendations: &mut Vec<Recommendation>,
+) {
+    let horizon = f64::from(thresholds.forecast_horizon_days);
+    for forecast in report
+        .capacity_forecasts
+        .iter()
+        .filter(|forecast| forecast.scope == ForecastScope::Disk)
+    {
+        let Some(days) = forecast.days_until_full.filter(|days| *days <= horizon) else {
+            continue;
+        };
+        let mut growing_roots: Vec<&CapacityForecast> = report
+            .capacity_forecasts
+            .iter()
+            .filter(|path| {
+                path.scope == ForecastScope::Path
+                    && path.disk_mount.as_deref() == Some(forecast.target.as_str())
+                    && path.growth_bytes_per_day > 0.0
+            })
+            .collect();
+        growing_roots.sort_by(|left, right| {
+            right
+                .growth_bytes_per_day
+                .total_cmp(&left.growth_bytes_per_day)
+        });
+
+        let mut evidence = vec![RecommendationEvidence {
+            kind: RecommendationEvidenceKind::Disk,
+            label: AFFECTED_DISK_LABEL.to_string(),
+            detail: format!(
+                "{} of {} used, growing {}/day (95% interval {} to {}/day) over {} scans.",
+                human_bytes(forecast.used_bytes),
+                human_bytes(forecast.capacity_bytes),
+                human_rate(forecast.growth_bytes_per_day),
+                human_rate(forecast.growth_bytes_per_day_low),
+                human_rate(forecast.growth_bytes_per_day_high),
+                forecast.samples
+            ),
+            path: None,
+            mount_point: Some(forecast.target.clone()),
+            duplicate_hash: None,
+        }];
+        evidence.extend(growing_roots.iter().take(MAX_GROWING_ROOTS).map(|root| {
+            RecommendationEvidence {
+                kind: RecommendationEvidenceKind::HistoryDelta,
+                label: "Growing root".to_string(),
+                detail: format!(
+                    "{} now, growing {}/day over {} scans.",
+                    human_bytes(root.used_bytes),
+                    human_rate(root.growth_bytes_per_day),
+                    root.samples
+                ),
+                path: Some(root.target.clone()),
+                mount_point: root.disk_mount.clone(),
+                duplicate_hash: None,
+            }
+        }));
+
+        let full_at = forecast
+            .projected_full_at
+            .as_deref()
+            .and_then(|text| text.get(..10))
+            .unwrap_or("unknown");
+        let interval = format!(
+            "between {} and {}",
+            forecast
+                .projected_full_earliest
+                .as_deref()
+                .and_then(|text| text.get(..10))
+                .unwrap_or("now"),
+            forecast
+                .projected_full_latest
+                .as_deref()
+                .and_then(|text| text.get(..10))
+                .unwrap_or("never at the slowest plausible growth")
+        );
+        // Confidence grows with the number of scans and drops when the slowest
+        // plausible growth would never fill the disk.
+        let mut confidence = (0.6 + 0.05 * forecast.samples.saturating_sub(3) as f32).min(0.85);
+        if forecast.projected_full_latest.is_none() {
+            confidence -= 0.1;
+        }
+
+        recommendations.push(Recommendation {
+            id: format!("disk-full-forecast-{}", sanitize_id(&forecast.target)),
+            title: format!(
+                "Disk {} is projected to fill in about {} days",
+                forecast.target,
+                days.ceil()
+            ),
+            rationale: format!(
+                "A robust fit over {} scans since {} puts disk {} at full capacity around {} ({}), within the {}-day forecast horizon.",
+                forecast.samples,
+                forecast.first_sample_at.get(..10).unwrap_or(&forecast.first_sample_at),
+                forecast.target,
+                full_at,
+                interval,
+                thresholds.forecast_horizon_days
+            ),
+            confidence,
+            target_mount: None,
+            policy_safe: true,
+            policy_rules_applied: vec![],
+            policy_rules_blocked: vec![],
+            evidence,
+            next_steps: vec![
+                "Review the growing roots and act on cleanup recommendations for this disk first.".to_string(),
+                "Plan extra capacity or move cold data before the projected date.".to_string(),
+            ],
+            estimated_impact: EstimatedImpact {
+                space_saving_bytes: None,
+                performance: None,
+                risk_notes: Some("A full disk can stop downloads, builds and OS updates and may corrupt files being written.".to_string()),
+            },
+            risk_level: if days <= horizon / 3.0 {
+                RiskLevel::High
+            } else {
+                RiskLevel::Medium
+            },
+            priority_score: 0.0,
+            rank: 0,
+        });
+    }
+}
+
 fn analyze_disk_trends(
     latest: &crate::model::ScanSnapshot,
     previous: &crate::model::ScanSnapshot,
     duration_days: i64,
+    thresholds: &PolicyThresholds,
+    forecast_mounts: &HashSet<String>,
     recommendations: &mut Vec<Recommendation>,
 ) {
     let prev_disks: HashMap<_, _> = previous
@@ -93,12 +251,18 @@ fn analyze_disk_trends(
         .map(|d| (d.mount_point.clone(), d))
         .collect();
 
-    for disk in &latest.disks {
+    for disk in latest
+        .disks
+        .iter()
+        .filter(|disk| !forecast_mounts.contains(&disk.mount_point))
+    {
         if let Some(prev_disk) = prev_disks.get(&disk.mount_point) {
             let change = prev_disk.free_space_bytes as i64 - disk.free_space_bytes as i64;
             let pct_change = change as f64 / disk.total_space_bytes.max(1) as f64;
 
-            if change.unsigned_abs() > MIN_ABS_CHANGE_BYTES && pct_change > MIN_PCT_CHANGE {
+            if change.unsigned_abs() > thresholds.trend_min_change_bytes
+                && pct_change > thresholds.trend_min_change_ratio
+            {
                 recommendations.push(Recommendation {
                     id: format!("disk-growth-{}", sanitize_id(&disk.mount_point)),
                     title: format!("Review storage growth on disk {}", disk.mount_point),
@@ -121,6 +285,8 @@ fn analyze_disk_trends(
                         risk_notes: Some("Unmanaged growth can lead to performance issues or data loss if the disk becomes full.".to_string()),
                     },
                     risk_level: RiskLevel::Medium,
+                    priority_score: 0.0,
+                    rank: 0,
                 });
             }
         }
@@ -131,6 +297,7 @@ fn analyze_path_trends(
     latest: &crate::model::ScanSnapshot,
     previous: &crate::model::ScanSnapshot,
     duration_days: i64,
+    thresholds: &PolicyThresholds,
     recommendations: &mut Vec<Recommendation>,
 ) {
     let prev_paths: HashMap<_, _> = previous
@@ -145,7 +312,9 @@ fn analyze_path_trends(
                 let change = path.total_size_bytes - prev_path.total_size_bytes;
                 let pct_change = change as f64 / prev_path.total_size_bytes.max(1) as f64;
 
-                if change > MIN_ABS_CHANGE_BYTES && pct_change > MIN_PCT_CHANGE {
+                if change > thresholds.trend_min_change_bytes
+                    && pct_change > thresholds.trend_min_change_ratio
+                {
                     recommendations.push(Recommendation {
                         id: format!("path-growth-{}", sanitize_id(&path.root_path)),
                         title: format!("Review storage growth in {}", path.root_path),
@@ -168,6 +337,8 @@ fn analyze_path_trends(
                             risk_notes: Some("Identifying the source of growth can help manage storage proactively.".to_string()),
                         },
                         risk_level: RiskLevel::Low,
+                        priority_score: 0.0,
+                        rank: 0,
                     });
                 }
             }
@@ -176,10 +347,16 @@ fn analyze_path_trends(
 }
 
 fn sanitize_id(value: &str) -> String {
-    value
+    let id: String = value
         .chars()
         .filter(|c| c.is_ascii_alphanumeric())
-        .collect()
+        .collect();
+    // `/` has no alphanumeric characters of its own.
+    if id.is_empty() {
+        "root".to_string()
+    } else {
+        id
+    }
 }
 
 fn human_bytes(value: u64) -> String {
@@ -196,6 +373,14 @@ fn human_bytes(value: u64) -> String {
     format!("{:.1} {}", size, UNITS[unit])
 }
 
+fn human_rate(bytes_per_day: f64) -> String {
+    if bytes_per_day < 0.0 {
+        format!("-{}", human_bytes(bytes_per_day.abs() as u64))
+    } else {
+        human_bytes(bytes_per_day as u64)
+    }
+}
+
 fn human_duration(days: i64) -> String {
     if days <= 1 {
         "day".to_string()
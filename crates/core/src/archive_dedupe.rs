@@ -0,0 +1,544 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+
+use crate::dedupe::{hash_file_stage, hash_reader, DedupeOptions, FileRecord, HashIo, HashPool};
+use crate::hash_cache::HashCache;
+use crate::model::{ArchiveExtraction, ArchiveFormat, DedupeStage, DuplicateGroup};
+
+/// Extraction roots considered per archive; more candidates than this means the
+/// member names are too generic to pin down a single extracted copy.
+const MAX_CANDIDATE_ROOTS: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct ArchiveDedupeOptions {
+    /// Archives smaller than this are not opened.
+    pub min_size_bytes: u64,
+    /// Hash cache, worker count, per-mount read limits and cancel flag shared with
+    /// the dedupe stage; its `min_size_bytes` is not used here.
+    pub hashing: DedupeOptions,
+}
+
+impl Default for ArchiveDedupeOptions {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: 1_048_576,
+            hashing: DedupeOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArchiveMember {
+    relative_path: PathBuf,
+    size_bytes: u64,
+    hash: String,
+}
+
+pub fn archive_format(path: &Path) -> Option<ArchiveFormat> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveFormat::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveFormat::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveFormat::Tar)
+    } else {
+        None
+    }
+}
+
+/// Finds zip/tar archives whose members all exist, byte for byte, at the same
+/// relative paths under one directory among the scanned files. Archives are only
+/// read; unreadable or unsupported archives are skipped with a warning.
+///
+/// Archives and loose files are read through the dedupe hash pool, so per-mount
+/// concurrency and rate limits apply. Loose files already hashed by `duplicate_groups`
+/// or found in the hash cache are not read again.
+pub fn find_archive_extractions(
+    records: &[FileRecord],
+    duplicate_groups: &[DuplicateGroup],
+    options: &ArchiveDedupeOptions,
+    warnings: &mut Vec<String>,
+) -> Vec<ArchiveExtraction> {
+    let by_path = records
+        .iter()
+        .map(|record| (record.path.as_path(), record))
+        .collect::<HashMap<_, _>>();
+    let mut by_name_and_size: HashMap<(&OsStr, u64), Vec<&FileRecord>> = HashMap::new();
+    for record in records {
+        if let Some(name) = record.path.file_name() {
+            by_name_and_size
+                .entry((name, record.size_bytes))
+                .or_default()
+                .push(record);
+        }
+    }
+
+    let archives = records
+        .iter()
+        .filter(|record| record.size_bytes >= options.min_size_bytes)
+        .filter_map(|record| archive_format(&record.path).map(|format| (record, format)))
+        .collect::<Vec<_>>();
+    let pool = HashPool::new(&options.hashing);
+    let listings = pool.run(
+        &job_mounts(archives.iter().map(|(record, _)| *record)),
+        |job, io| {
+            let (archive, format) = archives[job];
+            read_members(&archive.path, format, io)
+        },
+    );
+    if pool.is_cancelled() {
+        warnings.push(canceled_warning());
+        return Vec::new();
+    }
+
+    // Archives with at least one directory laid out like their members, with the
+    // roots to compare once the loose files are hashed.
+    let mut matchable = Vec::new();
+    for ((archive, format), listing) in archives.into_iter().zip(listings) {
+        match listing {
+            Some(Ok(members)) => {
+                let roots = laid_out_roots(&members, &by_path, &by_name_and_size);
+                if !roots.is_empty() {
+                    matchable.push((archive, format, members, roots));
+                }
+            }
+            Some(Err(err)) => warnings.push(format!(
+                "archive matching skipped {}: {err:#}",
+                archive.path.display()
+            )),
+            None => {}
+        }
+    }
+
+    let mut loose = BTreeMap::new();
+    for (_, _, members, roots) in &matchable {
+        for root in roots {
+            for member in members {
+                if let Some(record) = by_path.get(root.join(&member.relative_path).as_path()) {
+                    loose.insert(record.path.as_path(), *record);
+                }
+            }
+        }
+    }
+    let loose_hashes = hash_loose_files(
+        loose.into_values().collect(),
+        duplicate_groups,
+        options,
+        &pool,
+        warnings,
+    );
+    if pool.is_cancelled() {
+        warnings.push(canceled_warning());
+        return Vec::new();
+    }
+
+    let mut extractions = matchable
+        .into_iter()
+        .filter_map(|(archive, format, members, roots)| {
+            let root = roots.into_iter().find(|root| {
+                members.iter().all(|member| {
+                    loose_hashes.get(&root.join(&member.relative_path)) == Some(&member.hash)
+                })
+            })?;
+            let extracted_path = match top_level(&members) {
+                Some(top) => root.join(top),
+                None => root.to_path_buf(),
+            };
+            Some(ArchiveExtraction {
+                archive_path: archive.path.to_string_lossy().to_string(),
+                disk_mount: archive.disk_mount.clone(),
+                format,
+                archive_size_bytes: archive.size_bytes,
+                extracted_path: extracted_path.to_string_lossy().to_string(),
+                member_count: members.len() as u64,
+                extracted_bytes: members.iter().map(|member| member.size_bytes).sum(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    extractions.sort_by(|a, b| {
+        b.archive_size_bytes
+            .cmp(&a.archive_size_bytes)
+            .then_with(|| a.archive_path.cmp(&b.archive_path))
+    });
+    extractions
+}
+
+fn canceled_warning() -> String {
+    "scan canceled during archive matching; archive extractions were not reported".to_string()
+}
+
+fn job_mounts<'a>(records: impl Iterator<Item = &'a FileRecord>) -> Vec<&'a str> {
+    records
+        .map(|record| record.disk_mount.as_deref().unwrap_or_default())
+        .collect()
+}
+
+/// Directories where every member exists with its size, found from the loose copies
+/// of the largest member that sit at the member's relative path.
+fn laid_out_roots<'a>(
+    members: &[ArchiveMember],
+    by_path: &HashMap<&Path, &FileRecord>,
+    by_name_and_size: &HashMap<(&OsStr, u64), Vec<&'a FileRecord>>,
+) -> Vec<&'a Path> {
+    let Some(anchor) = members.iter().max_by_key(|member| member.size_bytes) else {
+        return Vec::new();
+    };
+    let Some(anchor_name) = anchor.relative_path.file_name() else {
+        return Vec::new();
+    };
+    if anchor.size_bytes == 0 {
+        return Vec::new();
+    }
+
+    let depth = anchor.relative_path.components().count();
+    by_name_and_size
+        .get(&(anchor_name, anchor.size_bytes))
+        .into_iter()
+        .flatten()
+        .filter(|record| record.path.ends_with(&anchor.relative_path))
+        .filter_map(|record| record.path.ancestors().nth(depth))
+        .take(MAX_CANDIDATE_ROOTS)
+        .filter(|root| {
+            members.iter().all(|member| {
+                by_path
+                    .get(root.join(&member.relative_path).as_path())
+                    .is_some_and(|loose| loose.size_bytes == member.size_bytes)
+            })
+        })
+        .collect()
+}
+
+/// Full-content hashes of `records`, taken from duplicate groups and the hash cache
+/// where possible and read through the pool otherwise. Files that fail to hash are
+/// left out with a warning, so archives needing them do not match.
+fn hash_loose_files(
+    records: Vec<&FileRecord>,
+    duplicate_groups: &[DuplicateGroup],
+    options: &ArchiveDedupeOptions,
+    pool: &HashPool,
+    warnings: &mut Vec<String>,
+) -> HashMap<PathBuf, String> {
+    let known = duplicate_groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .files
+                .iter()
+                .map(|file| (Path::new(&file.path), group.hash.as_str()))
+        })
+        .collect::<HashMap<_, _>>();
+    let mut hash_cache = options
+        .hashing
+        .hash_cache_path
+        .as_deref()
+        .map(|path| HashCache::load(path, warnings));
+
+    let mut hashes = HashMap::new();
+    let mut pending = Vec::new();
+    for record in records {
+        let reused = known
+            .get(record.path.as_path())
+            .map(|hash| hash.to_string())
+            .or_else(|| {
+                hash_cache
+                    .as_mut()
+                    .and_then(|cache| cache.get(record, DedupeStage::FullHash))
+            });
+        match reused {
+            Some(hash) => {
+                hashes.insert(record.path.clone(), hash);
+            }
+            None => pending.push(record),
+        }
+    }
+
+    let results = pool.run(&job_mounts(pending.iter().copied()), |job, io| {
+        let record = pending[job];
+        hash_file_stage(&record.path, record.size_bytes, DedupeStage::FullHash, io)
+    });
+    for (record, result) in pending.into_iter().zip(results) {
+        match result {
+            Some(Ok((hash, _))) => {
+                if let Some(cache) = hash_cache.as_mut() {
+                    cache.insert(record, DedupeStage::FullHash, &hash);
+                }
+                hashes.insert(record.path.clone(), hash);
+            }
+            Some(Err(err)) => warnings.push(format!(
+                "archive matching skipped {}: {err:#}",
+                record.path.display()
+            )),
+            None => {}
+        }
+    }
+    if let Some(cache) = hash_cache {
+        cache.persist(warnings);
+    }
+    hashes
+}
+
+/// The single top-level directory every member lives under, if there is one.
+fn top_level(members: &[ArchiveMember]) -> Option<PathBuf> {
+    let mut tops = members.iter().map(|member| {
+        let mut components = member.relative_path.components();
+        let first = components.next()?;
+        components.next().map(|_| PathBuf::from(first.as_os_str()))
+    });
+    let first = tops.next()??;
+    tops.all(|top| top.as_ref() == Some(&first))
+        .then_some(first)
+}
+
+/// Rejects absolute paths and `..` so member names can only address files below
+/// the candidate extraction root.
+fn safe_relative_path(path: &Path) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+/// Lists the regular-file members of an archive and hashes each one in the same
+/// pass, so compressed archives are only decompressed once. Bytes charged to the
+/// mount's rate limit are the decompressed member bytes.
+fn read_members(path: &Path, format: ArchiveFormat, io: &HashIo) -> Result<Vec<ArchiveMember>> {
+    let mut members = Vec::new();
+    visit_members(path, format, |relative_path, size_bytes, reader| {
+        let mut hasher = blake3::Hasher::new();
+        hash_reader(reader, &mut hasher, path, io)?;
+        members.push(ArchiveMember {
+            relative_path,
+            size_bytes,
+            hash: hasher.finalize().to_hex().to_string(),
+        });
+        Ok(())
+    })?;
+    Ok(members)
+}
+
+/// Calls `visit` with the relative path, size, and content reader of every regular
+/// file member. Members with unsafe names make the whole archive unmatchable.
+fn visit_members<F>(path: &Path, format: ArchiveFormat, mut visit: F) -> Result<()>
+where
+    F: FnMut(PathBuf, u64, &mut dyn Read) -> Result<()>,
+{
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(BufReader::new(file))?;
+            for index in 0..archive.len() {
+                let mut member = archive.by_index(index)?;
+                if !member.is_file() {
+                    continue;
+                }
+                let relative_path = member
+                    .enclosed_name()
+                    .and_then(|name| safe_relative_path(&name))
+                    .ok_or_else(|| anyhow!("unsafe member name {}", member.name()))?;
+                let size_bytes = member.size();
+                visit(relative_path, size_bytes, &mut member)?;
+            }
+        }
+        ArchiveFormat::Tar => visit_tar_members(BufReader::new(file), &mut visit)?,
+        ArchiveFormat::TarGz => {
+            visit_tar_members(GzDecoder::new(BufReader::new(file)), &mut visit)?
+        }
+    }
+    Ok(())
+}
+
+fn visit_tar_members<R, F>(reader: R, visit: &mut F) -> Result<()>
+where
+    R: Read,
+    F: FnMut(PathBuf, u64, &mut dyn Read) -> Result<()>,
+{
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.into_owned();
+        let relative_path = safe_relative_path(&name)
+            .ok_or_else(|| anyhow!("unsafe member name {}", name.display()))?;
+        let size_bytes = entry.size();
+        visit(relative_path, size_bytes, &mut entry)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::TempDir;
+    use walkdir::WalkDir;
+
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use super::{find_archive_extractions, ArchiveDedupeOptions};
+    use crate::dedupe::{DedupeOptions, FileRecord};
+    use crate::model::{
+        ArchiveFormat, DuplicateFile, DuplicateGroup, DuplicateIntent, DuplicateIntentLabel,
+    };
+
+    #[test]
+    fn reports_archives_only_when_every_member_is_extracted_unchanged() {
+        let temp = TempDir::new().expect("tempdir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("release/docs")).expect("mkdir");
+        fs::write(root.join("release/app.bin"), vec![7_u8; 4_096]).expect("write");
+        fs::write(root.join("release/docs/README"), b"read me").expect("write");
+
+        let mut zip = zip::ZipWriter::new(File::create(root.join("release.zip")).expect("zip"));
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("release/app.bin", options).expect("member");
+        zip.write_all(&[7_u8; 4_096]).expect("member data");
+        zip.start_file("release/docs/README", options)
+            .expect("member");
+        zip.write_all(b"read me").expect("member data");
+        zip.finish().expect("finish zip");
+
+        // Same layout, but one member differs from the loose copy.
+        let encoder = GzEncoder::new(
+            File::create(root.join("release-old.tar.gz")).expect("tar.gz"),
+            Compression::default(),
+        );
+        let mut tar = tar::Builder::new(encoder);
+        for (name, data) in [
+            ("release/app.bin", vec![7_u8; 4_096]),
+            ("release/docs/README", b"read ME".to_vec()),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, data.as_slice())
+                .expect("append");
+        }
+        tar.into_inner()
+            .expect("tar")
+            .finish()
+            .expect("finish gzip");
+
+        let records = WalkDir::new(root)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| {
+                FileRecord::from_path(entry.path().to_path_buf(), None, None).expect("record")
+            })
+            .collect::<Vec<_>>();
+        let mut warnings = Vec::new();
+        let extractions = find_archive_extractions(
+            &records,
+            &[],
+            &ArchiveDedupeOptions {
+                min_size_bytes: 1,
+                ..ArchiveDedupeOptions::default()
+            },
+            &mut warnings,
+        );
+
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(extractions.len(), 1, "{extractions:#?}");
+        let extraction = &extractions[0];
+        assert_eq!(extraction.format, ArchiveFormat::Zip);
+        assert!(extraction.archive_path.ends_with("release.zip"));
+        assert_eq!(
+            extraction.extracted_path,
+            root.join("release").to_string_lossy()
+        );
+        assert_eq!(extraction.member_count, 2);
+        assert_eq!(extraction.extracted_bytes, 4_096 + 7);
+    }
+
+    fn extracted_zip(root: &std::path::Path) -> Vec<FileRecord> {
+        fs::create_dir_all(root.join("release")).expect("mkdir");
+        fs::write(root.join("release/app.bin"), vec![7_u8; 4_096]).expect("write");
+        let mut zip = zip::ZipWriter::new(File::create(root.join("release.zip")).expect("zip"));
+        zip.start_file("release/app.bin", zip::write::SimpleFileOptions::default())
+            .expect("member");
+        zip.write_all(&[7_u8; 4_096]).expect("member data");
+        zip.finish().expect("finish zip");
+        ["release.zip", "release/app.bin"]
+            .into_iter()
+            .map(|name| FileRecord::from_path(root.join(name), None, None).expect("record"))
+            .collect()
+    }
+
+    #[test]
+    fn reuses_loose_hashes_from_duplicate_groups() {
+        let temp = TempDir::new().expect("tempdir");
+        let records = extracted_zip(temp.path());
+        let options = ArchiveDedupeOptions {
+            min_size_bytes: 1,
+            ..ArchiveDedupeOptions::default()
+        };
+        let mut warnings = Vec::new();
+        assert_eq!(
+            find_archive_extractions(&records, &[], &options, &mut warnings).len(),
+            1
+        );
+
+        // A hash already known from the dedupe stage is trusted instead of re-reading
+        // the loose file, so a mismatching one rules the archive out.
+        let loose = records[1].path.to_string_lossy().to_string();
+        let groups = vec![DuplicateGroup {
+            size_bytes: 4_096,
+            hash: "not-the-member-hash".to_string(),
+            files: vec![DuplicateFile {
+                path: loose,
+                disk_mount: None,
+                modified: None,
+            }],
+            total_wasted_bytes: 0,
+            intent: DuplicateIntent {
+                label: DuplicateIntentLabel::LikelyRedundant,
+                rationale: String::new(),
+            },
+        }];
+        assert!(find_archive_extractions(&records, &groups, &options, &mut warnings).is_empty());
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn canceled_matching_reports_nothing() {
+        let temp = TempDir::new().expect("tempdir");
+        let records = extracted_zip(temp.path());
+        let mut warnings = Vec::new();
+        let extractions = find_archive_extractions(
+            &records,
+            &[],
+            &ArchiveDedupeOptions {
+                min_size_bytes: 1,
+                hashing: DedupeOptions {
+                    cancel_flag: Some(Arc::new(AtomicBool::new(true))),
+                    ..DedupeOptions::default()
+                },
+            },
+            &mut warnings,
+        );
+
+        assert!(extractions.is_empty());
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].contains("canceled during archive matching"));
+    }
+}
@@ -454,6 +454,9 @@ mod tests {
                 stale_files: 20,
                 unknown_modified_files: 20,
             },
+            directory_tree: None,
+            hardlinked_files: 0,
+            hardlink_savings_bytes: 0,
         }
     }
 
@@ -527,6 +530,15 @@ mod tests {
                 metadata_notes: Vec::new(),
                 role_hint: Default::default(),
                 target_role_eligibility: Vec::new(),
+                device_id: None,
+                mount_kind: Default::default(),
+                storage_pool: None,
+                parent_mount: None,
+                reserved_bytes: None,
+                snapshot_bytes: None,
+                logical_used_bytes: None,
+                effective_free_space_bytes: None,
+                health: None,
             },
             DiskInfo {
                 name: "Black Rider (Games and Apps)".to_string(),
@@ -554,6 +566,15 @@ mod tests {
                 metadata_notes: Vec::new(),
                 role_hint: Default::default(),
                 target_role_eligibility: Vec::new(),
+                device_id: None,
+                mount_kind: Default::default(),
+                storage_pool: None,
+                parent_mount: None,
+                reserved_bytes: None,
+                snapshot_bytes: None,
+                logical_used_bytes: None,
+                effective_free_space_bytes: None,
+                health: None,
             },
         ];
 
@@ -0,0 +1,661 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Context, Result};
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::device::effective_free_ratio;
+use crate::model::{
+    Category, CustomRule, CustomRuleConditions, CustomRuleScope, DiskInfo, DuplicateIntentLabel,
+    EstimatedImpact, PolicyProfile, Recommendation, RecommendationEvidence,
+    RecommendationEvidenceKind, Report, RuleTrace, RuleTraceStatus,
+};
+use crate::policy_profile::disabled_rule_trace;
+use crate::tree::flatten_directory_tree;
+
+/// Matches listed as evidence on a custom recommendation.
+const MAX_CUSTOM_RULE_EVIDENCE: usize = 5;
+
+/// A report item a custom rule can match, reduced to what conditions look at.
+struct RuleItem<'a> {
+    path: &'a str,
+    size_bytes: u64,
+    disk_mount: Option<&'a str>,
+    category: Option<&'a Category>,
+    confidence: Option<f32>,
+    duplicate_intent: Option<&'a DuplicateIntentLabel>,
+}
+
+/// Trace id for a custom rule, kept apart from built-in rule ids.
+pub fn custom_rule_trace_id(rule: &CustomRule) -> String {
+    format!("custom:{}", rule.id)
+}
+
+/// Recommendation id for a custom rule. The `custom-` namespace keeps it from
+/// colliding with built-in ids, or from being caught by a built-in family key such
+/// as `risky-disk` in `risk_levels`.
+pub fn custom_recommendation_id(rule: &CustomRule) -> String {
+    format!("custom-{}", rule.id)
+}
+
+pub fn validate_custom_rules(rules: &[CustomRule]) -> Result<()> {
+    let mut ids = HashSet::new();
+    for rule in rules {
+        if rule.id.trim().is_empty() || rule.title.trim().is_empty() {
+            bail!("custom rules need a non-empty id and title");
+        }
+        if !ids.insert(rule.id.as_str()) {
+            bail!("custom rule id {} is defined more than once", rule.id);
+        }
+        if !(0.0..=1.0).contains(&rule.confidence) {
+            bail!("custom rule {} confidence must be between 0 and 1", rule.id);
+        }
+        if rule
+            .when
+            .max_free_ratio
+            .is_some_and(|ratio| !(0.0..=1.0).contains(&ratio))
+        {
+            bail!(
+                "custom rule {} max_free_ratio must be between 0 and 1",
+                rule.id
+            );
+        }
+        compile_glob(&rule.when)
+            .with_context(|| format!("custom rule {} has an invalid path_glob", rule.id))?;
+    }
+    Ok(())
+}
+
+/// Evaluates every custom rule in `profile` against `report`. Each rule yields at
+/// most one recommendation plus a trace, like the built-in rules.
+pub fn run_custom_rules(
+    report: &Report,
+    profile: &PolicyProfile,
+) -> (Vec<Recommendation>, Vec<RuleTrace>) {
+    let mut recommendations = Vec::new();
+    let mut traces = Vec::new();
+    for rule in &profile.custom_rules {
+        let rule_id = custom_rule_trace_id(rule);
+        if profile.is_rule_disabled(&rule_id) {
+            traces.push(disabled_rule_trace(&rule_id));
+            continue;
+        }
+        match evaluate_custom_rule(report, rule) {
+            Ok(Some((recommendation, matched))) => {
+                traces.push(RuleTrace {
+                    rule_id,
+                    status: RuleTraceStatus::Emitted,
+                    detail: format!("Custom rule matched {matched} item(s)."),
+                    recommendation_id: Some(recommendation.id.clone()),
+                    confidence: Some(recommendation.confidence),
+                });
+                recommendations.push(recommendation);
+            }
+            Ok(None) => traces.push(RuleTrace {
+                rule_id,
+                status: RuleTraceStatus::Skipped,
+                detail: "Rule conditions were not met.".to_string(),
+                recommendation_id: None,
+                confidence: None,
+            }),
+            Err(err) => traces.push(RuleTrace {
+                rule_id,
+                status: RuleTraceStatus::Skipped,
+                detail: format!("Custom rule could not be evaluated: {err:#}"),
+                recommendation_id: None,
+                confidence: None,
+            }),
+        }
+    }
+    (recommendations, traces)
+}
+
+/// Returns the rule's recommendation and match count when enough items match.
+pub fn evaluate_custom_rule(
+    report: &Report,
+    rule: &CustomRule,
+) -> Result<Option<(Recommendation, usize)>> {
+    let glob = compile_glob(&rule.when)?;
+    let disks = report
+        .disks
+        .iter()
+        .map(|disk| (disk.mount_point.as_str(), disk))
+        .collect::<HashMap<_, _>>();
+
+    let mut matches = collect_items(report, rule.scope)
+        .into_iter()
+        .filter(|item| item_matches(item, &rule.when, glob.as_ref(), &disks))
+        .collect::<Vec<_>>();
+    let total_bytes = matches
+        .iter()
+        .map(|item| item.size_bytes)
+        .fold(0_u64, u64::saturating_add);
+    if matches.len() < rule.when.min_matches.unwrap_or(1).max(1)
+        || total_bytes < rule.when.min_total_bytes.unwrap_or(0)
+    {
+        return Ok(None);
+    }
+    matches.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.path.cmp(b.path)));
+
+    let largest = &matches[0];
+    let rationale = rule
+        .rationale
+        .as_deref()
+        .unwrap_or("Custom rule matched {count} item(s) totaling {total_size} (largest at {largest_path}).")
+        .replace("{count}", &matches.len().to_string())
+        .replace("{total_size}", &human_bytes(total_bytes))
+        .replace("{largest_path}", largest.path)
+        .replace("{largest_size}", &human_bytes(largest.size_bytes));
+    let reclaims_space = matches!(
+        rule.scope,
+        CustomRuleScope::Files | CustomRuleScope::Directories | CustomRuleScope::DuplicateGroups
+    );
+    let evidence = matches
+        .iter()
+        .take(MAX_CUSTOM_RULE_EVIDENCE)
+        .map(|item| RecommendationEvidence {
+            kind: evidence_kind(rule.scope),
+            label: format!("Matched by custom rule {}", rule.id),
+            detail: format!("{} bytes", item.size_bytes),
+            path: Some(item.path.to_string()),
+            mount_point: item.disk_mount.map(str::to_string),
+            duplicate_hash: None,
+        })
+        .collect();
+
+    let recommendation = Recommendation {
+        id: custom_recommendation_id(rule),
+        title: rule.title.clone(),
+        rationale,
+        confidence: rule.confidence,
+        target_mount: rule.target_mount.clone(),
+        policy_safe: true,
+        policy_rules_applied: vec![],
+        policy_rules_blocked: vec![],
+        evidence,
+        next_steps: rule.next_steps.clone(),
+        estimated_impact: EstimatedImpact {
+            space_saving_bytes: reclaims_space.then_some(total_bytes),
+            performance: None,
+            risk_notes: Some(
+                "Site-specific rule from the policy profile; confirm its intent before acting."
+                    .to_string(),
+            ),
+        },
+        risk_level: rule.risk_level.clone(),
+        priority_score: 0.0,
+        rank: 0,
+    };
+    Ok(Some((recommendation, matches.len())))
+}
+
+fn compile_glob(conditions: &CustomRuleConditions) -> Result<Option<GlobMatcher>> {
+    conditions
+        .path_glob
+        .as_deref()
+        .map(|pattern| {
+            Ok(GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()?
+                .compile_matcher())
+        })
+        .transpose()
+}
+
+fn collect_items(report: &Report, scope: CustomRuleScope) -> Vec<RuleItem<'_>> {
+    let item = |path, size_bytes, disk_mount| RuleItem {
+        path,
+        size_bytes,
+        disk_mount,
+        category: None,
+        confidence: None,
+        duplicate_intent: None,
+    };
+    match scope {
+        CustomRuleScope::Files => report
+            .paths
+            .iter()
+            .flat_map(|stats| {
+                stats.largest_files.entries.iter().map(|file| {
+                    item(
+                        file.path.as_str(),
+                        file.size_bytes,
+                        stats.disk_mount.as_deref(),
+                    )
+                })
+            })
+            .collect(),
+        CustomRuleScope::Directories => {
+            // Largest directories and tree nodes overlap; each path is matched once.
+            let mut seen = HashSet::new();
+            let mut items = Vec::new();
+            for stats in &report.paths {
+                let tree_nodes = stats
+                    .directory_tree
+                    .as_ref()
+                    .map(flatten_directory_tree)
+                    .unwrap_or_default();
+                let directories = stats
+                    .largest_directories
+                    .iter()
+                    .map(|directory| (directory.path.as_str(), directory.size_bytes))
+                    .chain(
+                        tree_nodes
+                            .into_iter()
+                            .map(|node| (node.path.as_str(), node.size_bytes)),
+                    );
+                for (path, size_bytes) in directories {
+                    if seen.insert(path) {
+                        items.push(item(path, size_bytes, stats.disk_mount.as_deref()));
+                    }
+                }
+            }
+            items
+        }
+        CustomRuleScope::Disks => report
+            .disks
+            .iter()
+            .map(|disk| {
+                item(
+                    disk.mount_point.as_str(),
+                    disk.total_space_bytes.saturating_sub(disk.free_space_bytes),
+                    Some(disk.mount_point.as_str()),
+                )
+            })
+            .collect(),
+        CustomRuleScope::Categories => report
+            .categories
+            .iter()
+            .map(|suggestion| RuleItem {
+                category: Some(&suggestion.category),
+                confidence: Some(suggestion.confidence),
+                ..item(
+                    suggestion.target.as_str(),
+                    0,
+                    suggestion.disk_mount.as_deref(),
+                )
+            })
+            .collect(),
+        CustomRuleScope::DuplicateGroups => report
+            .duplicates
+            .iter()
+            .filter_map(|group| {
+                let first = group.files.first()?;
+                Some(RuleItem {
+                    duplicate_intent: Some(&group.intent.label),
+                    ..item(
+                        first.path.as_str(),
+                        group.total_wasted_bytes,
+                        first.disk_mount.as_deref(),
+                    )
+                })
+            })
+            .collect(),
+    }
+}
+
+fn item_matches(
+    item: &RuleItem<'_>,
+    when: &CustomRuleConditions,
+    glob: Option<&GlobMatcher>,
+    disks: &HashMap<&str, &DiskInfo>,
+) -> bool {
+    if glob.is_some_and(|glob| !glob.is_match(item.path.replace('\\', "/"))) {
+        return false;
+    }
+    if when.min_size_bytes.is_some_and(|min| item.size_bytes < min)
+        || when.max_size_bytes.is_some_and(|max| item.size_bytes > max)
+    {
+        return false;
+    }
+    if when
+        .category
+        .as_ref()
+        .is_some_and(|category| item.category != Some(category))
+        || when
+            .min_confidence
+            .is_some_and(|min| item.confidence.is_none_or(|confidence| confidence < min))
+        || when
+            .duplicate_intent
+            .as_ref()
+            .is_some_and(|intent| item.duplicate_intent != Some(intent))
+    {
+        return false;
+    }
+
+    let needs_disk = when.disk_mount.is_some()
+        || when.on_os_drive.is_some()
+        || when.locality.is_some()
+        || when.storage_type.is_some()
+        || when.max_free_ratio.is_some();
+    if !needs_disk {
+        return true;
+    }
+    let Some(disk) = item.disk_mount.and_then(|mount| disks.get(mount)) else {
+        return false;
+    };
+    let free_ratio = effective_free_ratio(disk).unwrap_or(1.0);
+    when.disk_mount
+        .as_deref()
+        .is_none_or(|mount| disk.mount_point == mount)
+        && when.on_os_drive.is_none_or(|os| disk.is_os_drive == os)
+        && when
+            .locality
+            .as_ref()
+            .is_none_or(|locality| &disk.locality_class == locality)
+        && when
+            .storage_type
+            .as_ref()
+            .is_none_or(|storage_type| &disk.storage_type == storage_type)
+        && when.max_free_ratio.is_none_or(|max| free_ratio <= max)
+}
+
+fn evidence_kind(scope: CustomRuleScope) -> RecommendationEvidenceKind {
+    match scope {
+        CustomRuleScope::Files | CustomRuleScope::Directories => {
+            RecommendationEvidenceKind::Directory
+        }
+        CustomRuleScope::Disks => RecommendationEvidenceKind::Disk,
+        CustomRuleScope::DuplicateGroups => RecommendationEvidenceKind::DuplicateGroup,
+        CustomRuleScope::Categories => RecommendationEvidenceKind::Other,
+    }
+}
+
+fn human_bytes(value: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if value == 0 {
+        return "0 B".to_string();
+    }
+    let mut size = value as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::{evaluate_custom_rule, validate_custom_rules};
+    use crate::analyzers::{AnalyzerContext, AnalyzerRegistry};
+    use crate::model::{
+        Category, CustomRule, CustomRuleConditions, CustomRuleScope, DuplicateIntentLabel,
+        FileEntry, PolicyProfile, Report, RiskLevel, RuleTraceStatus,
+    };
+    use crate::policy_profile::load_policy_profile;
+    use crate::recommend::generate_recommendation_bundle_with_context;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn sample_report() -> Report {
+        serde_json::from_str(include_str!("../../../fixtures/sample-report.json")).expect("fixture")
+    }
+
+    fn rule(id: &str, target_mount: Option<&str>) -> CustomRule {
+        CustomRule {
+            id: id.to_string(),
+            title: "Move VM images off the OS drive".to_string(),
+            rationale: Some("{count} VM image(s) use {total_size} on the OS drive.".to_string()),
+            scope: CustomRuleScope::Files,
+            when: CustomRuleConditions {
+                path_glob: Some("**/VMs/*.vhdx".to_string()),
+                min_size_bytes: Some(50 * GIB),
+                on_os_drive: Some(true),
+                ..CustomRuleConditions::default()
+            },
+            risk_level: RiskLevel::Medium,
+            confidence: 0.7,
+            target_mount: target_mount.map(str::to_string),
+            next_steps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn custom_rules_match_report_items_and_pass_through_policies() {
+        let mut report = sample_report();
+        report.paths[0].largest_files.entries = vec![
+            FileEntry {
+                path: "C:\\Media\\VMs\\dev.vhdx".to_string(),
+                size_bytes: 80 * GIB,
+                modified: None,
+            },
+            FileEntry {
+                path: "C:\\Media\\VMs\\small.vhdx".to_string(),
+                size_bytes: 10 * GIB,
+                modified: None,
+            },
+        ];
+        let profile = PolicyProfile {
+            custom_rules: vec![
+                rule("vm-images-on-os-drive", None),
+                rule("vm-images-to-cloud", Some("J:\\")),
+            ],
+            ..PolicyProfile::default()
+        };
+
+        let bundle = generate_recommendation_bundle_with_context(
+            &report,
+            &AnalyzerContext {
+                policy_profile: profile,
+                ..AnalyzerContext::default()
+            },
+            &AnalyzerRegistry::default(),
+        );
+
+        let recommendation = bundle
+            .recommendations
+            .iter()
+            .find(|item| item.id == "custom-vm-images-on-os-drive")
+            .expect("custom recommendation");
+        assert_eq!(
+            recommendation.rationale,
+            "1 VM image(s) use 80.0 GB on the OS drive."
+        );
+        assert_eq!(recommendation.risk_level, RiskLevel::Medium);
+        assert_eq!(
+            recommendation.estimated_impact.space_saving_bytes,
+            Some(80 * GIB)
+        );
+        assert!(bundle.rule_traces.iter().any(|trace| {
+            trace.rule_id == "custom:vm-images-on-os-drive"
+                && trace.status == RuleTraceStatus::Emitted
+        }));
+        // The cloud-backed target is ineligible, so the safety policy rejects it.
+        assert!(!bundle
+            .recommendations
+            .iter()
+            .any(|item| item.id == "custom-vm-images-to-cloud"));
+        assert!(bundle.rule_traces.iter().any(|trace| {
+            trace.rule_id == "safe_target_policy"
+                && trace.recommendation_id.as_deref() == Some("custom-vm-images-to-cloud")
+        }));
+    }
+
+    fn scoped_rule(scope: CustomRuleScope, when: CustomRuleConditions) -> CustomRule {
+        CustomRule {
+            id: "scoped".to_string(),
+            title: "Scoped rule".to_string(),
+            rationale: None,
+            scope,
+            when,
+            risk_level: RiskLevel::Low,
+            confidence: 0.5,
+            target_mount: None,
+            next_steps: Vec::new(),
+        }
+    }
+
+    /// Paths of the items `rule` matched, largest first; empty when it did not fire.
+    fn matched_paths(report: &Report, rule: &CustomRule) -> Vec<String> {
+        evaluate_custom_rule(report, rule)
+            .expect("rule evaluates")
+            .map(|(recommendation, _)| {
+                recommendation
+                    .evidence
+                    .into_iter()
+                    .filter_map(|evidence| evidence.path)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn max_free_ratio_uses_effective_free_space() {
+        let mut report = sample_report();
+        let rule = scoped_rule(
+            CustomRuleScope::Disks,
+            CustomRuleConditions {
+                max_free_ratio: Some(0.2),
+                ..CustomRuleConditions::default()
+            },
+        );
+        assert_eq!(matched_paths(&report, &rule), vec!["J:\\"]);
+
+        // A third of D:\ is free, but snapshots leave only a tenth of it writable.
+        report.disks[1].effective_free_space_bytes = Some(300_000_000_000);
+        assert_eq!(matched_paths(&report, &rule), vec!["D:\\", "J:\\"]);
+    }
+
+    #[test]
+    fn disk_scope_matches_disk_attributes() {
+        let report = sample_report();
+        let mut rule = scoped_rule(
+            CustomRuleScope::Disks,
+            CustomRuleConditions {
+                on_os_drive: Some(false),
+                ..CustomRuleConditions::default()
+            },
+        );
+        assert_eq!(matched_paths(&report, &rule), vec!["D:\\", "J:\\"]);
+
+        rule.when.disk_mount = Some("J:\\".to_string());
+        assert_eq!(matched_paths(&report, &rule), vec!["J:\\"]);
+    }
+
+    #[test]
+    fn category_scope_matches_category_and_confidence() {
+        let report = sample_report();
+        let rule = scoped_rule(
+            CustomRuleScope::Categories,
+            CustomRuleConditions {
+                category: Some(Category::Work),
+                min_confidence: Some(0.8),
+                ..CustomRuleConditions::default()
+            },
+        );
+        // J:\DriveFS is also Work, but only at 0.6 confidence.
+        assert_eq!(matched_paths(&report, &rule), vec!["D:\\Projects"]);
+    }
+
+    #[test]
+    fn duplicate_scope_matches_group_intent() {
+        let report = sample_report();
+        let mut rule = scoped_rule(
+            CustomRuleScope::DuplicateGroups,
+            CustomRuleConditions {
+                duplicate_intent: Some(DuplicateIntentLabel::LikelyRedundant),
+                ..CustomRuleConditions::default()
+            },
+        );
+        let (recommendation, matched) = evaluate_custom_rule(&report, &rule)
+            .expect("rule evaluates")
+            .expect("redundant group matches");
+        assert_eq!(matched, 1);
+        assert_eq!(
+            recommendation.evidence[0].path.as_deref(),
+            Some("C:\\Media\\movie-copy-1.mkv")
+        );
+        assert_eq!(
+            recommendation.estimated_impact.space_saving_bytes,
+            Some(report.duplicates[0].total_wasted_bytes)
+        );
+
+        rule.when.duplicate_intent = Some(DuplicateIntentLabel::LikelyIntentional);
+        assert!(matched_paths(&report, &rule).is_empty());
+    }
+
+    #[test]
+    fn custom_ids_are_namespaced_away_from_built_in_families() {
+        let report = sample_report();
+        let mut rule = scoped_rule(
+            CustomRuleScope::Disks,
+            CustomRuleConditions {
+                on_os_drive: Some(false),
+                ..CustomRuleConditions::default()
+            },
+        );
+        rule.id = "risky-disk-d".to_string();
+        let profile = PolicyProfile {
+            custom_rules: vec![rule],
+            risk_levels: [("risky-disk".to_string(), RiskLevel::High)].into(),
+            ..PolicyProfile::default()
+        };
+
+        let bundle = generate_recommendation_bundle_with_context(
+            &report,
+            &AnalyzerContext {
+                policy_profile: profile,
+                ..AnalyzerContext::default()
+            },
+            &AnalyzerRegistry::default(),
+        );
+        let recommendation = bundle
+            .recommendations
+            .iter()
+            .find(|item| item.id == "custom-risky-disk-d")
+            .expect("custom recommendation");
+        // The built-in `risky-disk` override does not reach the custom rule.
+        assert_eq!(recommendation.risk_level, RiskLevel::Low);
+    }
+
+    #[test]
+    fn min_total_bytes_applies_to_the_summed_matches() {
+        let report = sample_report();
+        // D:\ uses 2,000 GB and J:\ 193.7 GB.
+        let mut rule = scoped_rule(
+            CustomRuleScope::Disks,
+            CustomRuleConditions {
+                on_os_drive: Some(false),
+                min_total_bytes: Some(2_100_000_000_000),
+                ..CustomRuleConditions::default()
+            },
+        );
+        assert_eq!(matched_paths(&report, &rule).len(), 2);
+
+        rule.when.min_total_bytes = Some(2_300_000_000_000);
+        assert!(evaluate_custom_rule(&report, &rule)
+            .expect("rule evaluates")
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_globs_and_unknown_scopes() {
+        let rule = scoped_rule(
+            CustomRuleScope::Files,
+            CustomRuleConditions {
+                path_glob: Some("**/[unclosed".to_string()),
+                ..CustomRuleConditions::default()
+            },
+        );
+        let err = validate_custom_rules(&[rule]).expect_err("invalid glob");
+        assert!(format!("{err:#}").contains("invalid path_glob"), "{err:#}");
+
+        // Scopes are a closed set, so an unknown one fails when the profile is parsed.
+        let temp = TempDir::new().expect("tempdir");
+        let path = temp.path().join("profile.json");
+        fs::write(
+            &path,
+            r#"{"custom_rules": [{"id": "x", "title": "X", "scope": "folders", "when": {}}]}"#,
+        )
+        .expect("write profile");
+        let err = load_policy_profile(&path).expect_err("unknown scope");
+        assert!(
+            format!("{err:#}").contains("unknown variant `folders`"),
+            "{err:#}"
+        );
+    }
+}
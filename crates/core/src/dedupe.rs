@@ -1,21 +1,81 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, Read};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, Metadata};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 
-use crate::model::{DuplicateFile, DuplicateGroup, DuplicateIntent, DuplicateIntentLabel};
+use crate::hash_cache::HashCache;
+use crate::journal::system_time_unix_nanos;
+use crate::model::{
+    DedupeStage, DedupeStageMetrics, DuplicateFile, DuplicateGroup, DuplicateIntent,
+    DuplicateIntentLabel,
+};
 
+/// Bytes hashed from the start of each same-size candidate in the first stage.
+const HEAD_BLOCK_BYTES: u64 = 4 * 1024;
+/// Bytes hashed from each end of a candidate in the head+tail sample stage.
+const SAMPLE_BLOCK_BYTES: u64 = 64 * 1024;
+const DEFAULT_HASH_WORKERS: usize = 4;
+
+/// A scanned file as the dedupe stages see it. Fields may be added over time, so
+/// code outside this crate builds records with `new` or `from_path`.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct FileRecord {
     pub path: PathBuf,
     pub size_bytes: u64,
     pub disk_mount: Option<String>,
     pub modified: Option<String>,
+    pub device_id: Option<u64>,
+    pub inode: Option<u64>,
+    /// Modification and status-change times in nanoseconds since the epoch; the hash
+    /// cache only trusts a digest while both are unchanged.
+    pub modified_unix_nanos: Option<u64>,
+    pub changed_unix_nanos: Option<u64>,
 }
 
 impl FileRecord {
+    /// A record without a file identity or timestamps; add them with `with_identity`
+    /// and `with_timestamps` so hard links are recognized and hashes can be cached.
+    pub fn new(
+        path: PathBuf,
+        size_bytes: u64,
+        disk_mount: Option<String>,
+        modified: Option<String>,
+    ) -> Self {
+        Self {
+            path,
+            size_bytes,
+            disk_mount,
+            modified,
+            device_id: None,
+            inode: None,
+            modified_unix_nanos: None,
+            changed_unix_nanos: None,
+        }
+    }
+
+    pub fn with_identity(mut self, device_id: u64, inode: u64) -> Self {
+        self.device_id = Some(device_id);
+        self.inode = Some(inode);
+        self
+    }
+
+    pub fn with_timestamps(
+        mut self,
+        modified_unix_nanos: Option<u64>,
+        changed_unix_nanos: Option<u64>,
+    ) -> Self {
+        self.modified_unix_nanos = modified_unix_nanos;
+        self.changed_unix_nanos = changed_unix_nanos;
+        self
+    }
+
     pub fn from_path(
         path: PathBuf,
         disk_mount: Option<String>,
@@ -23,61 +83,237 @@ impl FileRecord {
     ) -> Result<Self> {
         let metadata = std::fs::metadata(&path)
             .with_context(|| format!("failed to read metadata for {}", path.display()))?;
+        let identity = file_identity(&metadata);
         Ok(Self {
             path,
             size_bytes: metadata.len(),
             disk_mount,
             modified,
+            device_id: identity.map(|(device, _)| device),
+            inode: identity.map(|(_, inode)| inode),
+            modified_unix_nanos: metadata.modified().ok().and_then(system_time_unix_nanos),
+            changed_unix_nanos: changed_unix_nanos(&metadata),
         })
     }
 }
 
+/// Returns the `(device, inode)` pair identifying the file's data on POSIX systems.
+pub(crate) fn file_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// Inode status-change time (`st_ctime` with its nanoseconds). Unlike the mtime it
+/// cannot be set from user space, so it also moves when a writer restores the mtime.
+pub(crate) fn changed_unix_nanos(metadata: &Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let secs = u64::try_from(metadata.ctime()).ok()?;
+        let nanos = u64::try_from(metadata.ctime_nsec()).ok()?;
+        secs.checked_mul(1_000_000_000)?.checked_add(nanos)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// Number of hard links to the file; always 1 where the platform does not expose it.
+pub(crate) fn link_count(metadata: &Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.nlink()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        1
+    }
+}
+
+/// Bytes actually allocated on disk (`st_blocks * 512`), when the platform exposes it.
+pub(crate) fn allocated_size(metadata: &Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(metadata.blocks().saturating_mul(512))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DedupeOptions {
+    pub min_size_bytes: u64,
+    /// Location of the persistent content-hash cache; `None` hashes every candidate.
+    pub hash_cache_path: Option<PathBuf>,
+    /// Upper bound on files hashed at the same time across all mounts.
+    pub max_workers: usize,
+    /// Per-mount limits keyed by `FileRecord.disk_mount`; other mounts (and records
+    /// without a mount) use `default_mount_limit`.
+    pub mount_limits: HashMap<String, MountHashLimit>,
+    pub default_mount_limit: MountHashLimit,
+    pub cancel_flag: Option<Arc<AtomicBool>>,
+}
+
+impl Default for DedupeOptions {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: 1_048_576,
+            hash_cache_path: None,
+            max_workers: DEFAULT_HASH_WORKERS,
+            mount_limits: HashMap::new(),
+            default_mount_limit: MountHashLimit::default(),
+            cancel_flag: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountHashLimit {
+    /// Files hashed concurrently on this mount; 1 keeps a spinning disk sequential.
+    pub concurrency: usize,
+    /// Read budget shared by every worker hashing on this mount.
+    pub max_bytes_per_second: Option<u64>,
+}
+
+impl Default for MountHashLimit {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_HASH_WORKERS,
+            max_bytes_per_second: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DedupeOutcome {
+    pub groups: Vec<DuplicateGroup>,
+    /// Hash cache hits and misses, counted per hash-stage lookup rather than per file.
+    pub hash_cache_hits: u64,
+    pub hash_cache_misses: u64,
+    pub stages: Vec<DedupeStageMetrics>,
+    /// Set when `cancel_flag` stopped hashing; `groups` is then empty.
+    pub canceled: bool,
+}
+
 pub fn find_duplicates(
     records: &[FileRecord],
     min_size_bytes: u64,
     warnings: &mut Vec<String>,
 ) -> Vec<DuplicateGroup> {
+    let options = DedupeOptions {
+        min_size_bytes,
+        ..DedupeOptions::default()
+    };
+    find_duplicates_with_options(records, &options, warnings).groups
+}
+
+pub fn find_duplicates_with_options(
+    records: &[FileRecord],
+    options: &DedupeOptions,
+    warnings: &mut Vec<String>,
+) -> DedupeOutcome {
+    let mut hash_cache = options
+        .hash_cache_path
+        .as_deref()
+        .map(|path| HashCache::load(path, warnings));
+
     let mut by_size: HashMap<u64, Vec<FileRecord>> = HashMap::new();
+    let mut seen_inodes = HashSet::new();
     for record in records {
-        if record.size_bytes < min_size_bytes {
+        if record.size_bytes < options.min_size_bytes {
             continue;
         }
+        // Hard links share one copy of the data, so only the first path per inode
+        // can take part in a duplicate group.
+        if let (Some(device), Some(inode)) = (record.device_id, record.inode) {
+            if !seen_inodes.insert((device, inode)) {
+                continue;
+            }
+        }
         by_size
             .entry(record.size_bytes)
             .or_default()
             .push(record.clone());
     }
 
-    let mut groups = Vec::new();
+    let mut size_stage = DedupeStageMetrics {
+        stage: DedupeStage::SizeGroup,
+        candidate_files: by_size.values().map(|files| files.len() as u64).sum(),
+        candidate_groups: by_size.len() as u64,
+        eliminated_files: 0,
+        bytes_read: 0,
+    };
     let mut size_keys: Vec<u64> = by_size.keys().copied().collect();
     size_keys.sort_unstable_by(|a, b| b.cmp(a));
-
+    let mut groups = Vec::new();
     for size in size_keys {
-        let candidates = by_size.remove(&size).unwrap_or_default();
-        if candidates.len() < 2 {
+        let files = by_size.remove(&size).unwrap_or_default();
+        if files.len() < 2 {
+            size_stage.eliminated_files += files.len() as u64;
             continue;
         }
+        groups.push(
+            files
+                .into_iter()
+                .map(|record| Candidate {
+                    record,
+                    hash: String::new(),
+                    complete: false,
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
 
-        let mut by_hash: HashMap<String, Vec<FileRecord>> = HashMap::new();
-        for candidate in candidates {
-            match hash_file(&candidate.path) {
-                Ok(hash) => by_hash.entry(hash).or_default().push(candidate),
-                Err(err) => warnings.push(format!(
-                    "dedupe hash skipped for {}: {}",
-                    candidate.path.display(),
-                    err
-                )),
-            }
+    let pool = HashPool::new(options);
+    let mut stages = vec![size_stage];
+    let mut canceled = false;
+    for stage in [
+        DedupeStage::HeadBlock,
+        DedupeStage::HeadTailSample,
+        DedupeStage::FullHash,
+    ] {
+        let (survivors, metrics) = run_hash_stage(stage, groups, &pool, &mut hash_cache, warnings);
+        groups = survivors;
+        stages.push(metrics);
+        if pool.is_cancelled() {
+            canceled = true;
+            groups.clear();
+            warnings.push(
+                "scan canceled during dedupe; duplicate groups were not reported".to_string(),
+            );
+            break;
         }
+    }
 
-        for (hash, mut files) in by_hash {
-            if files.len() < 2 {
-                continue;
-            }
-            files.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut duplicate_groups = groups
+        .into_iter()
+        .map(|mut files| {
+            files.sort_by(|a, b| a.record.path.cmp(&b.record.path));
+            let size = files[0].record.size_bytes;
+            let hash = files[0].hash.clone();
+            let records = files
+                .into_iter()
+                .map(|candidate| candidate.record)
+                .collect::<Vec<_>>();
 
-            let intent = classify_intent(&files);
-            let duplicate_files = files
+            let intent = classify_intent(&records);
+            let duplicate_files = records
                 .iter()
                 .map(|item| DuplicateFile {
                     path: item.path.to_string_lossy().to_string(),
@@ -87,29 +323,399 @@ pub fn find_duplicates(
                 .collect::<Vec<_>>();
 
             let wasted = size.saturating_mul((duplicate_files.len() as u64).saturating_sub(1));
-            groups.push(DuplicateGroup {
+            DuplicateGroup {
                 size_bytes: size,
                 hash,
                 files: duplicate_files,
                 total_wasted_bytes: wasted,
                 intent,
-            });
-        }
-    }
+            }
+        })
+        .collect::<Vec<_>>();
 
-    groups.sort_by(|a, b| {
+    duplicate_groups.sort_by(|a, b| {
         b.total_wasted_bytes
             .cmp(&a.total_wasted_bytes)
             .then_with(|| b.files.len().cmp(&a.files.len()))
+            .then_with(|| a.hash.cmp(&b.hash))
+    });
+
+    let mut outcome = DedupeOutcome {
+        groups: duplicate_groups,
+        stages,
+        canceled,
+        ..DedupeOutcome::default()
+    };
+    if let Some(cache) = hash_cache {
+        outcome.hash_cache_hits = cache.hits;
+        outcome.hash_cache_misses = cache.misses;
+        cache.persist(warnings);
+    }
+    outcome
+}
+
+/// A same-size file still in the running for a duplicate group, with the hash from
+/// the latest stage it went through.
+struct Candidate {
+    record: FileRecord,
+    hash: String,
+    // Set once a stage has read the whole file, making `hash` the full-content hash
+    // and every later stage a no-op for this candidate's group.
+    complete: bool,
+}
+
+/// Splits each group by its stage hash and drops files that no longer match any
+/// other file. Groups whose hashes are already complete pass through untouched.
+fn run_hash_stage(
+    stage: DedupeStage,
+    groups: Vec<Vec<Candidate>>,
+    pool: &HashPool,
+    hash_cache: &mut Option<HashCache>,
+    warnings: &mut Vec<String>,
+) -> (Vec<Vec<Candidate>>, DedupeStageMetrics) {
+    let mut metrics = DedupeStageMetrics {
+        stage,
+        candidate_files: 0,
+        candidate_groups: 0,
+        eliminated_files: 0,
+        bytes_read: 0,
+    };
+    let mut survivors = Vec::new();
+    let mut pending = Vec::new();
+    for group in groups {
+        if group.iter().all(|candidate| candidate.complete) {
+            survivors.push(group);
+        } else {
+            metrics.candidate_groups += 1;
+            metrics.candidate_files += group.len() as u64;
+            pending.push(group);
+        }
+    }
+
+    // Cache lookups stay on this thread; only misses are handed to the pool.
+    let mut hashes = Vec::with_capacity(pending.len());
+    let mut jobs = Vec::new();
+    for (group_index, group) in pending.iter().enumerate() {
+        let mut group_hashes = Vec::with_capacity(group.len());
+        for (candidate_index, candidate) in group.iter().enumerate() {
+            let cached = hash_cache
+                .as_mut()
+                .and_then(|cache| cache.get(&candidate.record, stage));
+            if cached.is_none() {
+                jobs.push((group_index, candidate_index));
+            }
+            group_hashes.push(cached.map(Ok));
+        }
+        hashes.push(group_hashes);
+    }
+
+    let job_mounts = jobs
+        .iter()
+        .map(|&(group_index, candidate_index)| {
+            pending[group_index][candidate_index]
+                .record
+                .disk_mount
+                .as_deref()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let results = pool.run(&job_mounts, |job, io| {
+        let (group_index, candidate_index) = jobs[job];
+        let record = &pending[group_index][candidate_index].record;
+        hash_file_stage(&record.path, record.size_bytes, stage, io)
     });
-    groups
+    for (&(group_index, candidate_index), result) in jobs.iter().zip(results) {
+        let Some(result) = result else {
+            continue;
+        };
+        let hashed = result.map(|(hash, bytes_read)| {
+            metrics.bytes_read = metrics.bytes_read.saturating_add(bytes_read);
+            if let Some(cache) = hash_cache.as_mut() {
+                cache.insert(&pending[group_index][candidate_index].record, stage, &hash);
+            }
+            hash
+        });
+        hashes[group_index][candidate_index] = Some(hashed);
+    }
+    if pool.is_cancelled() {
+        return (Vec::new(), metrics);
+    }
+
+    for (group, group_hashes) in pending.into_iter().zip(hashes) {
+        let mut by_hash: HashMap<String, Vec<Candidate>> = HashMap::new();
+        for (mut candidate, hashed) in group.into_iter().zip(group_hashes) {
+            match hashed {
+                Some(Ok(hash)) => {
+                    candidate.complete = stage_reads_whole_file(stage, candidate.record.size_bytes);
+                    candidate.hash = hash.clone();
+                    by_hash.entry(hash).or_default().push(candidate);
+                }
+                Some(Err(err)) => warnings.push(format!(
+                    "dedupe hash skipped for {}: {}",
+                    candidate.record.path.display(),
+                    err
+                )),
+                None => {}
+            }
+        }
+
+        for (_, files) in by_hash {
+            if files.len() < 2 {
+                metrics.eliminated_files += files.len() as u64;
+            } else {
+                survivors.push(files);
+            }
+        }
+    }
+
+    (survivors, metrics)
+}
+
+/// Bounded worker pool for hashing. Workers pick the next job from any mount that is
+/// below its concurrency limit, and reads on a mount share its byte-rate budget, so
+/// fast disks are hashed in parallel while a spinning disk sees one reader at a time.
+pub(crate) struct HashPool {
+    max_workers: usize,
+    mounts: HashMap<String, MountLane>,
+    default_lane: MountLane,
+    cancel_flag: Option<Arc<AtomicBool>>,
 }
 
-fn hash_file(path: &Path) -> Result<String> {
-    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
-    let mut reader = BufReader::new(file);
+struct MountLane {
+    concurrency: usize,
+    limiter: Option<RateLimiter>,
+}
+
+impl MountLane {
+    fn new(limit: &MountHashLimit) -> Self {
+        Self {
+            concurrency: limit.concurrency.max(1),
+            limiter: limit
+                .max_bytes_per_second
+                .filter(|rate| *rate > 0)
+                .map(RateLimiter::new),
+        }
+    }
+}
+
+/// What a single hashing job may use: its mount's rate limiter and the cancel flag.
+pub(crate) struct HashIo<'a> {
+    limiter: Option<&'a RateLimiter>,
+    cancel_flag: Option<&'a AtomicBool>,
+}
+
+impl HashIo<'_> {
+    fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+}
+
+struct PoolState {
+    // Indices of not-yet-started jobs, queued per mount key.
+    queues: HashMap<String, Vec<usize>>,
+    active: HashMap<String, usize>,
+}
+
+impl HashPool {
+    pub(crate) fn new(options: &DedupeOptions) -> Self {
+        Self {
+            max_workers: options.max_workers.max(1),
+            mounts: options
+                .mount_limits
+                .iter()
+                .map(|(mount, limit)| (mount.clone(), MountLane::new(limit)))
+                .collect(),
+            default_lane: MountLane::new(&options.default_mount_limit),
+            cancel_flag: options.cancel_flag.clone(),
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    fn lane(&self, mount: &str) -> &MountLane {
+        self.mounts.get(mount).unwrap_or(&self.default_lane)
+    }
+
+    /// Runs `work` for every job index, where `job_mounts[index]` names the mount the
+    /// job reads from, and returns results in job order. Jobs that were not started
+    /// because of cancellation yield `None`.
+    pub(crate) fn run<T, F>(&self, job_mounts: &[&str], work: F) -> Vec<Option<Result<T>>>
+    where
+        T: Send,
+        F: Fn(usize, &HashIo) -> Result<T> + Sync,
+    {
+        let mut queues: HashMap<String, Vec<usize>> = HashMap::new();
+        // Reverse so that popping from the back starts jobs in their original order.
+        for (index, mount) in job_mounts.iter().enumerate().rev() {
+            queues.entry(mount.to_string()).or_default().push(index);
+        }
+        let state = Mutex::new(PoolState {
+            queues,
+            active: HashMap::new(),
+        });
+        let wakeup = Condvar::new();
+        let results = Mutex::new((0..job_mounts.len()).map(|_| None).collect::<Vec<_>>());
+        let workers = self.max_workers.min(job_mounts.len());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    while let Some((index, mount)) = self.next_job(&state, &wakeup) {
+                        let lane = self.lane(&mount);
+                        let io = HashIo {
+                            limiter: lane.limiter.as_ref(),
+                            cancel_flag: self.cancel_flag.as_deref(),
+                        };
+                        let result = work(index, &io);
+                        if let Ok(mut results) = results.lock() {
+                            results[index] = Some(result);
+                        }
+                        if let Ok(mut state) = state.lock() {
+                            if let Some(active) = state.active.get_mut(&mount) {
+                                *active = active.saturating_sub(1);
+                            }
+                        }
+                        wakeup.notify_all();
+                    }
+                });
+            }
+        });
+
+        results.into_inner().unwrap_or_default()
+    }
+
+    fn next_job(&self, state: &Mutex<PoolState>, wakeup: &Condvar) -> Option<(usize, String)> {
+        let mut guard = state.lock().ok()?;
+        loop {
+            if self.is_cancelled() {
+                // Drop queued work; in-flight jobs observe the flag while reading.
+                guard.queues.clear();
+                wakeup.notify_all();
+                return None;
+            }
+
+            let state = &mut *guard;
+            let ready = state.queues.iter_mut().find(|(mount, queue)| {
+                !queue.is_empty()
+                    && state.active.get(*mount).copied().unwrap_or(0) < self.lane(mount).concurrency
+            });
+            if let Some((mount, queue)) = ready {
+                let index = queue.pop()?;
+                let mount = mount.clone();
+                *state.active.entry(mount.clone()).or_insert(0) += 1;
+                return Some((index, mount));
+            }
+            if state.queues.values().all(Vec::is_empty) {
+                // Everything left is in flight on other workers.
+                return None;
+            }
+            guard = wakeup
+                .wait_timeout(guard, Duration::from_millis(50))
+                .ok()?
+                .0;
+        }
+    }
+}
+
+/// Token-bucket style limiter: callers report bytes read and sleep until the shared
+/// budget allows them, so the mount's average read rate stays at or below the cap.
+struct RateLimiter {
+    bytes_per_second: u64,
+    state: Mutex<(Instant, u64)>,
+}
+
+impl RateLimiter {
+    fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second,
+            state: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    fn consume(&self, bytes: u64) {
+        let wait = match self.state.lock() {
+            Ok(mut state) => {
+                let now = Instant::now();
+                let budget_end = state.0
+                    + Duration::from_secs_f64(state.1 as f64 / self.bytes_per_second as f64);
+                // An idle limiter must not bank an unbounded burst allowance.
+                if now > budget_end + Duration::from_secs(1) {
+                    *state = (now, 0);
+                }
+                state.1 = state.1.saturating_add(bytes);
+                let due = state.0
+                    + Duration::from_secs_f64(state.1 as f64 / self.bytes_per_second as f64);
+                due.saturating_duration_since(now)
+            }
+            Err(_) => Duration::ZERO,
+        };
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+}
+
+fn stage_reads_whole_file(stage: DedupeStage, size_bytes: u64) -> bool {
+    match stage {
+        DedupeStage::SizeGroup => false,
+        DedupeStage::HeadBlock => size_bytes <= HEAD_BLOCK_BYTES,
+        DedupeStage::HeadTailSample => size_bytes <= SAMPLE_BLOCK_BYTES * 2,
+        DedupeStage::FullHash => true,
+    }
+}
+
+/// Hashes the part of the file a stage looks at and returns the BLAKE3 digest with
+/// the number of bytes read. When the stage covers the whole file the digest equals
+/// the full-content hash.
+pub(crate) fn hash_file_stage(
+    path: &Path,
+    size_bytes: u64,
+    stage: DedupeStage,
+    io: &HashIo,
+) -> Result<(String, u64)> {
+    let mut file =
+        File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
     let mut hasher = blake3::Hasher::new();
+
+    let bytes_read = match stage {
+        DedupeStage::HeadBlock => hash_prefix(&mut file, &mut hasher, HEAD_BLOCK_BYTES, path, io)?,
+        DedupeStage::HeadTailSample if size_bytes > SAMPLE_BLOCK_BYTES * 2 => {
+            let head = hash_prefix(&mut file, &mut hasher, SAMPLE_BLOCK_BYTES, path, io)?;
+            file.seek(SeekFrom::Start(size_bytes - SAMPLE_BLOCK_BYTES))
+                .with_context(|| format!("failed to seek {}", path.display()))?;
+            head + hash_prefix(&mut file, &mut hasher, SAMPLE_BLOCK_BYTES, path, io)?
+        }
+        _ => hash_prefix(&mut file, &mut hasher, u64::MAX, path, io)?,
+    };
+
+    Ok((hasher.finalize().to_hex().to_string(), bytes_read))
+}
+
+fn hash_prefix(
+    file: &mut File,
+    hasher: &mut blake3::Hasher,
+    limit: u64,
+    path: &Path,
+    io: &HashIo,
+) -> Result<u64> {
+    hash_reader(BufReader::new(file.take(limit)), hasher, path, io)
+}
+
+/// Feeds `reader` into `hasher` in chunks, charging each chunk to the mount's rate
+/// limiter and stopping as soon as the cancel flag is set. `path` only labels errors.
+pub(crate) fn hash_reader(
+    mut reader: impl Read,
+    hasher: &mut blake3::Hasher,
+    path: &Path,
+    io: &HashIo,
+) -> Result<u64> {
     let mut buffer = [0_u8; 64 * 1024];
+    let mut total = 0_u64;
 
     loop {
         let bytes_read = reader
@@ -119,12 +725,19 @@ fn hash_file(path: &Path) -> Result<String> {
             break;
         }
         hasher.update(&buffer[..bytes_read]);
+        total += bytes_read as u64;
+        if let Some(limiter) = io.limiter {
+            limiter.consume(bytes_read as u64);
+        }
+        if io.is_cancelled() {
+            return Err(anyhow!("hashing canceled"));
+        }
     }
 
-    Ok(hasher.finalize().to_hex().to_string())
+    Ok(total)
 }
 
-fn classify_intent(files: &[FileRecord]) -> DuplicateIntent {
+pub(crate) fn classify_intent(files: &[FileRecord]) -> DuplicateIntent {
     let backup_keywords = ["backup", "time machine", "history", "mirror", "snapshot"];
     let lowered = files
         .iter()
@@ -186,12 +799,20 @@ fn shared_suffix(files: &[FileRecord], depth: usize) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::fs;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
 
     use tempfile::TempDir;
 
-    use super::{find_duplicates, FileRecord};
-    use crate::model::DuplicateIntentLabel;
+    use super::{
+        find_duplicates, find_duplicates_with_options, DedupeOptions, FileRecord, HashPool,
+        MountHashLimit, RateLimiter, HEAD_BLOCK_BYTES, SAMPLE_BLOCK_BYTES,
+    };
+    use crate::model::{DedupeStage, DuplicateIntentLabel};
 
     #[test]
     fn groups_duplicates_by_size_and_hash() {
@@ -221,4 +842,221 @@ mod tests {
             DuplicateIntentLabel::LikelyRedundant
         );
     }
+
+    #[test]
+    fn staged_hashing_eliminates_mismatches_before_full_reads() {
+        let temp = TempDir::new().expect("tempdir");
+        let size = 200 * 1024;
+        let original = vec![5_u8; size];
+        let mut head_differs = original.clone();
+        head_differs[0] = 1;
+        let mut tail_differs = original.clone();
+        tail_differs[size - 1] = 1;
+        let mut middle_differs = original.clone();
+        middle_differs[size / 2] = 1;
+
+        let mut records = Vec::new();
+        for (name, content) in [
+            ("a.bin", &original),
+            ("b.bin", &original),
+            ("head.bin", &head_differs),
+            ("tail.bin", &tail_differs),
+            ("middle.bin", &middle_differs),
+        ] {
+            let path = temp.path().join(name);
+            fs::write(&path, content).expect("write candidate");
+            records.push(FileRecord::from_path(path, None, None).expect("record"));
+        }
+
+        let options = DedupeOptions {
+            min_size_bytes: 1,
+            ..DedupeOptions::default()
+        };
+        let mut warnings = Vec::new();
+        let outcome = find_duplicates_with_options(&records, &options, &mut warnings);
+
+        assert!(warnings.is_empty());
+        assert_eq!(outcome.groups.len(), 1);
+        assert_eq!(outcome.groups[0].files.len(), 2);
+        assert_eq!(
+            outcome.groups[0].hash,
+            blake3::hash(&original).to_hex().to_string()
+        );
+
+        let summary = outcome
+            .stages
+            .iter()
+            .map(|stage| {
+                (
+                    stage.stage,
+                    stage.candidate_files,
+                    stage.eliminated_files,
+                    stage.bytes_read,
+                )
+            })
+            .collect::<Vec<_>>();
+        let size = size as u64;
+        assert_eq!(
+            summary,
+            vec![
+                (DedupeStage::SizeGroup, 5, 0, 0),
+                (DedupeStage::HeadBlock, 5, 1, 5 * HEAD_BLOCK_BYTES),
+                (
+                    DedupeStage::HeadTailSample,
+                    4,
+                    1,
+                    4 * 2 * SAMPLE_BLOCK_BYTES
+                ),
+                (DedupeStage::FullHash, 3, 1, 3 * size),
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_cache_reuses_unchanged_hashes_and_rehashes_modified_files() {
+        let temp = TempDir::new().expect("tempdir");
+        let a = temp.path().join("a.bin");
+        let b = temp.path().join("b.bin");
+        fs::write(&a, b"same-size-content-1").expect("write a");
+        fs::write(&b, b"same-size-content-1").expect("write b");
+        let options = DedupeOptions {
+            min_size_bytes: 1,
+            hash_cache_path: Some(temp.path().join("cache/hashes.json")),
+            ..DedupeOptions::default()
+        };
+        let records = || {
+            vec![
+                FileRecord::from_path(a.clone(), None, None).expect("record a"),
+                FileRecord::from_path(b.clone(), None, None).expect("record b"),
+            ]
+        };
+
+        let mut warnings = Vec::new();
+        let first = find_duplicates_with_options(&records(), &options, &mut warnings);
+        assert_eq!((first.hash_cache_hits, first.hash_cache_misses), (0, 2));
+        assert_eq!(first.groups.len(), 1);
+
+        let second = find_duplicates_with_options(&records(), &options, &mut warnings);
+        assert_eq!((second.hash_cache_hits, second.hash_cache_misses), (2, 0));
+        assert_eq!(second.groups[0].hash, first.groups[0].hash);
+
+        fs::write(&b, b"same-size-content-2").expect("rewrite b");
+        let third = find_duplicates_with_options(&records(), &options, &mut warnings);
+        assert_eq!((third.hash_cache_hits, third.hash_cache_misses), (1, 1));
+        assert!(third.groups.is_empty());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn hash_pool_limits_concurrency_per_mount() {
+        let options = DedupeOptions {
+            max_workers: 4,
+            mount_limits: HashMap::from([(
+                "hdd".to_string(),
+                MountHashLimit {
+                    concurrency: 1,
+                    max_bytes_per_second: None,
+                },
+            )]),
+            ..DedupeOptions::default()
+        };
+        let pool = HashPool::new(&options);
+        let job_mounts = ["hdd", "ssd", "hdd", "ssd", "hdd", "ssd", "hdd", "ssd"];
+        let active = [AtomicUsize::new(0), AtomicUsize::new(0)];
+        let peak = [AtomicUsize::new(0), AtomicUsize::new(0)];
+
+        let results = pool.run(&job_mounts, |job, _io| {
+            let lane = usize::from(job_mounts[job] == "ssd");
+            let now = active[lane].fetch_add(1, Ordering::SeqCst) + 1;
+            peak[lane].fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(30));
+            active[lane].fetch_sub(1, Ordering::SeqCst);
+            Ok(job * 10)
+        });
+
+        let values = results
+            .into_iter()
+            .map(|result| result.and_then(Result::ok))
+            .collect::<Vec<_>>();
+        assert_eq!(values, (0..8).map(|job| Some(job * 10)).collect::<Vec<_>>());
+        assert_eq!(peak[0].load(Ordering::SeqCst), 1);
+        assert!(peak[1].load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn rate_limiter_delays_reads_beyond_budget() {
+        let limiter = RateLimiter::new(100_000);
+        let started = Instant::now();
+        limiter.consume(10_000);
+        limiter.consume(10_000);
+        assert!(started.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn cancel_flag_stops_dedupe_without_partial_groups() {
+        let temp = TempDir::new().expect("tempdir");
+        let a = temp.path().join("a.bin");
+        let b = temp.path().join("b.bin");
+        fs::write(&a, b"duplicate-content").expect("write a");
+        fs::write(&b, b"duplicate-content").expect("write b");
+        let records = vec![
+            FileRecord::from_path(a, None, None).expect("record a"),
+            FileRecord::from_path(b, None, None).expect("record b"),
+        ];
+        let options = DedupeOptions {
+            min_size_bytes: 1,
+            cancel_flag: Some(Arc::new(AtomicBool::new(true))),
+            ..DedupeOptions::default()
+        };
+
+        let mut warnings = Vec::new();
+        let outcome = find_duplicates_with_options(&records, &options, &mut warnings);
+        assert!(outcome.canceled);
+        assert!(outcome.groups.is_empty());
+        assert!(warnings
+            .iter()
+            .any(|warning| warning.contains("scan canceled during dedupe")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hard_links_are_not_reported_as_duplicates() {
+        let temp = TempDir::new().expect("tempdir");
+        let original = temp.path().join("original.bin");
+        let linked = temp.path().join("linked.bin");
+        fs::write(&original, b"shared-inode-content").expect("write original");
+        fs::hard_link(&original, &linked).expect("hard link");
+
+        let records = vec![
+            FileRecord::from_path(original, None, None).expect("record original"),
+            FileRecord::from_path(linked, None, None).expect("record link"),
+        ];
+
+        let mut warnings = Vec::new();
+        let groups = find_duplicates(&records, 1, &mut warnings);
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn constructed_records_use_the_identity_they_are_given() {
+        let temp = TempDir::new().expect("tempdir");
+        let records = ["a.bin", "b.bin"]
+            .into_iter()
+            .map(|name| {
+                let path = temp.path().join(name);
+                fs::write(&path, b"same-content").expect("write");
+                FileRecord::new(path, 12, None, None)
+            })
+            .collect::<Vec<_>>();
+
+        let mut warnings = Vec::new();
+        assert_eq!(find_duplicates(&records, 1, &mut warnings).len(), 1);
+
+        let linked = records
+            .into_iter()
+            .map(|record| record.with_identity(1, 42))
+            .collect::<Vec<_>>();
+        assert!(find_duplicates(&linked, 1, &mut warnings).is_empty());
+        assert!(warnings.is_empty());
+    }
 }
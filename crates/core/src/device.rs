@@ -1,13 +1,32 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
-use crate::model::{DiskInfo, DiskKind, DiskStorageType, LocalityClass, PerformanceClass};
-use serde::Deserialize;
+use anyhow::{bail, Context, Result};
+use chrono::{SecondsFormat, Utc};
+use sysinfo::{DiskKind as SysDiskKind, Disks};
+
+use crate::dedupe::file_identity;
+use crate::health::{apply_disk_health, collect_disk_health};
+use crate::model::{
+    DiskHealth, DiskInfo, DiskKind, DiskStorageType, LocalityClass, MountKind, PerformanceClass,
+};
+use serde::{Deserialize, Serialize};
 
 #[cfg(windows)]
 use std::env;
 
-#[derive(Debug, Clone)]
+mod space;
+
+pub use space::FilesystemSpace;
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+mod sysfs;
+
+/// Current [`DiskInventory`] format; loading rejects newer inventories.
+pub const DISK_INVENTORY_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiskProbe {
     pub name: String,
     pub mount_point: String,
@@ -18,14 +37,51 @@ pub struct DiskProbe {
     pub is_removable: bool,
 }
 
-#[derive(Debug, Clone, Default)]
-struct PlatformDiskHint {
-    vendor: Option<String>,
-    model: Option<String>,
-    interface: Option<String>,
-    rotational: Option<bool>,
-    confidence: f32,
-    source: String,
+/// Model, vendor, interface and rotation facts a platform provider (sysfs, lsblk,
+/// WMI) reported for one mount.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlatformDiskHint {
+    pub vendor: Option<String>,
+    pub model: Option<String>,
+    pub interface: Option<String>,
+    pub rotational: Option<bool>,
+    pub confidence: f32,
+    pub source: String,
+}
+
+/// One entry of `/proc/self/mountinfo`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MountRecord {
+    /// `major:minor` of the filesystem's superblock.
+    pub device_number: String,
+    /// Path inside the filesystem that is mounted; not `/` for bind mounts and
+    /// btrfs subvolumes.
+    pub root: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub source: String,
+    pub super_options: String,
+}
+
+/// Everything disk enrichment reads from a machine, captured so that machine's
+/// layout can be analyzed elsewhere: the raw probes, platform hints keyed by mount
+/// point, the mount table, filesystem free space keyed by mount point, and drive
+/// health readings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskInventory {
+    pub inventory_version: u32,
+    pub captured_at: String,
+    pub os: String,
+    pub os_mount: Option<String>,
+    pub probes: Vec<DiskProbe>,
+    #[serde(default)]
+    pub platform_hints: BTreeMap<String, PlatformDiskHint>,
+    #[serde(default)]
+    pub mounts: Vec<MountRecord>,
+    #[serde(default)]
+    pub filesystem_space: BTreeMap<String, FilesystemSpace>,
+    #[serde(default)]
+    pub health: Vec<DiskHealth>,
 }
 
 #[cfg(target_os = "linux")]
@@ -50,21 +106,353 @@ pub fn detect_os_mount() -> Option<String> {
     }
 }
 
-pub fn enrich_disks(probes: Vec<DiskProbe>) -> Vec<DiskInfo> {
+/// Disks as sysinfo reports them on this machine, before enrichment.
+pub fn probe_local_disks() -> Vec<DiskProbe> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .map(|disk| {
+            let disk_kind = match disk.kind() {
+                SysDiskKind::HDD => DiskKind::Hdd,
+                SysDiskKind::SSD => DiskKind::Ssd,
+                _ => DiskKind::Unknown,
+            };
+
+            DiskProbe {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                total_space_bytes: disk.total_space(),
+                free_space_bytes: disk.available_space(),
+                disk_kind,
+                file_system: Some(disk.file_system().to_string_lossy().to_string()),
+                is_removable: disk.is_removable(),
+            }
+        })
+        .collect()
+}
+
+/// Enriches probes with this machine's platform hints, mount table and filesystem
+/// free space. Filesystem tools that are missing or fail become warnings.
+pub fn enrich_disks(probes: Vec<DiskProbe>, warnings: &mut Vec<String>) -> Vec<DiskInfo> {
+    let mounts = collect_mount_records(Path::new("/"));
+    enrich_local_disks(probes, &mounts, &space::probe_filesystem_space, warnings)
+}
+
+/// [`enrich_disks`] with the mount table and filesystem space probe supplied by the
+/// caller, so tests do not read the host's mounts.
+pub(crate) fn enrich_local_disks(
+    probes: Vec<DiskProbe>,
+    mounts: &[MountRecord],
+    probe_space: &space::SpaceProbe,
+    warnings: &mut Vec<String>,
+) -> Vec<DiskInfo> {
     let os_mount = detect_os_mount();
     let platform_hints = collect_platform_hints();
+    let mut disks = enrich_probes(probes, os_mount.as_deref(), &platform_hints, mounts, true);
+    space::apply_filesystem_space(&mut disks, mounts, probe_space, warnings);
+    disks
+}
+
+/// [`enrich_local_disks`] with an empty mount table and no filesystem space.
+#[cfg(test)]
+pub(crate) fn enrich_disks_without_space(probes: Vec<DiskProbe>) -> Vec<DiskInfo> {
+    enrich_local_disks(
+        probes,
+        &[],
+        &|_, _, _| FilesystemSpace::default(),
+        &mut Vec::new(),
+    )
+}
+
+/// Captures this machine's probes, platform hints, mount table and filesystem free
+/// space, plus drive health from `smart_dir` and, when `query_smartctl` is set,
+/// from `smartctl`.
+pub fn capture_disk_inventory(
+    smart_dir: Option<&Path>,
+    query_smartctl: bool,
+    warnings: &mut Vec<String>,
+) -> DiskInventory {
+    let probes = probe_local_disks();
+    let mounts = collect_mount_records(Path::new("/"));
+    let filesystem_space = probes
+        .iter()
+        .map(|probe| {
+            let space = space::probe_filesystem_space(&probe.mount_point, &mounts, warnings);
+            (probe.mount_point.clone(), space)
+        })
+        .filter(|(_, space)| !space.is_empty())
+        .collect();
+    DiskInventory {
+        inventory_version: DISK_INVENTORY_VERSION,
+        captured_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        os: std::env::consts::OS.to_string(),
+        os_mount: detect_os_mount(),
+        probes,
+        platform_hints: collect_platform_hints().into_iter().collect(),
+        mounts,
+        filesystem_space,
+        health: collect_disk_health(smart_dir, query_smartctl, warnings),
+    }
+}
+
+pub fn load_disk_inventory(path: &Path) -> Result<DiskInventory> {
+    let payload = fs::read_to_string(path)
+        .with_context(|| format!("failed to read disk inventory {}", path.display()))?;
+    let inventory: DiskInventory = serde_json::from_str(&payload)
+        .with_context(|| format!("failed to parse disk inventory {}", path.display()))?;
+    if inventory.inventory_version > DISK_INVENTORY_VERSION {
+        bail!(
+            "disk inventory {} has version {}; this build reads up to {}",
+            path.display(),
+            inventory.inventory_version,
+            DISK_INVENTORY_VERSION
+        );
+    }
+    Ok(inventory)
+}
+
+/// Enriches a captured inventory as [`enrich_disks`] and drive-health import would
+/// have on the captured machine, without reading anything from this one. Device ids
+/// are left unset because they only identify filesystems on the captured machine.
+pub fn enrich_inventory(inventory: &DiskInventory) -> Vec<DiskInfo> {
+    let platform_hints = inventory
+        .platform_hints
+        .iter()
+        .map(|(mount, hint)| (normalize_mount_for_hint_lookup(mount), hint.clone()))
+        .collect::<HashMap<_, _>>();
+    let mut disks = enrich_probes(
+        inventory.probes.clone(),
+        inventory.os_mount.as_deref(),
+        &platform_hints,
+        &inventory.mounts,
+        false,
+    );
+    for disk in &mut disks {
+        disk.device_id = None;
+        if let Some(space) = inventory.filesystem_space.get(&disk.mount_point) {
+            space::apply_space(disk, space);
+        }
+    }
+    apply_disk_health(&mut disks, &inventory.health);
+    disks
+}
+
+fn enrich_probes(
+    probes: Vec<DiskProbe>,
+    os_mount: Option<&str>,
+    platform_hints: &HashMap<String, PlatformDiskHint>,
+    mounts: &[MountRecord],
+    live: bool,
+) -> Vec<DiskInfo> {
     let mut disks = probes
         .into_iter()
         .map(|probe| {
             let hint_key = normalize_mount_for_hint_lookup(&probe.mount_point);
             let hint = platform_hints.get(&hint_key);
-            enrich_disk(probe, os_mount.as_deref(), hint)
+            let mut disk = enrich_disk(probe, os_mount, hint);
+            if live {
+                disk.device_id = path_device_id(Path::new(&disk.mount_point));
+            }
+            disk
         })
         .collect::<Vec<_>>();
     disks.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    apply_mount_topology(&mut disks, mounts);
     disks
 }
 
+/// `st_dev` of the filesystem holding `path`, where the platform exposes one.
+pub(crate) fn path_device_id(path: &Path) -> Option<u64> {
+    let metadata = fs::metadata(path).ok()?;
+    file_identity(&metadata).map(|(device, _)| device)
+}
+
+/// True when both mounts draw on the same filesystem or pool, so moving data
+/// between them frees nothing.
+pub(crate) fn shares_storage_pool(left: &DiskInfo, right: &DiskInfo) -> bool {
+    left.storage_pool.is_some() && left.storage_pool == right.storage_pool
+}
+
+/// Bytes that can still be written: the filesystem's own estimate (after snapshots,
+/// pool overhead and reserved blocks) when known, otherwise the reported free space.
+pub(crate) fn effective_free_space_bytes(disk: &DiskInfo) -> u64 {
+    disk.effective_free_space_bytes
+        .unwrap_or(disk.free_space_bytes)
+}
+
+/// Share of usable capacity that is still writable. Reserved blocks count neither
+/// as free nor as capacity, matching `df`; `None` for disks without capacity.
+pub(crate) fn effective_free_ratio(disk: &DiskInfo) -> Option<f64> {
+    let capacity = disk
+        .total_space_bytes
+        .saturating_sub(disk.reserved_bytes.unwrap_or(0));
+    if capacity == 0 {
+        return None;
+    }
+    let free = effective_free_space_bytes(disk).min(capacity);
+    Some(free as f64 / capacity as f64)
+}
+
+/// The mount table at `<sysroot>/proc/self/mountinfo`; empty off Linux or when it
+/// cannot be read.
+fn collect_mount_records(sysroot: &Path) -> Vec<MountRecord> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    fs::read_to_string(sysroot.join("proc/self/mountinfo"))
+        .map(|text| parse_mountinfo(&text))
+        .unwrap_or_default()
+}
+
+pub(crate) fn parse_mountinfo(text: &str) -> Vec<MountRecord> {
+    text.lines().filter_map(parse_mountinfo_line).collect()
+}
+
+fn parse_mountinfo_line(line: &str) -> Option<MountRecord> {
+    // `id parent major:minor root mount_point options [optional...] - fstype source super`
+    let (left, right) = line.split_once(" - ")?;
+    let left = left.split_whitespace().collect::<Vec<_>>();
+    let mut right = right.split_whitespace();
+    Some(MountRecord {
+        device_number: left.get(2)?.to_string(),
+        root: unescape_mountinfo(left.get(3)?),
+        mount_point: unescape_mountinfo(left.get(4)?),
+        fs_type: right.next()?.to_string(),
+        source: unescape_mountinfo(right.next()?),
+        super_options: right.next().unwrap_or_default().to_string(),
+    })
+}
+
+// mountinfo escapes space, tab, newline and backslash as three-digit octal.
+fn unescape_mountinfo(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let octal = bytes.get(index + 1..index + 4);
+        if bytes[index] == b'\\'
+            && octal.is_some_and(|digits| digits.iter().all(|b| (b'0'..=b'7').contains(b)))
+        {
+            let code = octal
+                .unwrap_or_default()
+                .iter()
+                .fold(0_u32, |acc, digit| acc * 8 + u32::from(digit - b'0'));
+            out.push(code as u8);
+            index += 4;
+            continue;
+        }
+        out.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Records each disk's mount kind and pool from the mount table, then marks every
+/// pool member except one as a child of that primary member. The primary is the
+/// shortest non-bind mount point, e.g. the pool root of a ZFS pool or the top-level
+/// mount of a btrfs filesystem.
+fn apply_mount_topology(disks: &mut [DiskInfo], mounts: &[MountRecord]) {
+    for disk in disks.iter_mut() {
+        let key = normalize_mount_for_hint_lookup(&disk.mount_point);
+        // With stacked mounts on one path, the last entry is the visible one.
+        let Some(record) = mounts
+            .iter()
+            .rev()
+            .find(|record| normalize_mount_for_hint_lookup(&record.mount_point) == key)
+        else {
+            continue;
+        };
+        let (mount_kind, storage_pool) = classify_mount(record);
+        disk.mount_kind = mount_kind;
+        disk.storage_pool = storage_pool;
+        if disk.device_id.is_none() {
+            disk.device_id = linux_device_id(&record.device_number);
+        }
+    }
+
+    let mut pools: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, disk) in disks.iter().enumerate() {
+        if let Some(pool) = &disk.storage_pool {
+            pools.entry(pool.clone()).or_default().push(index);
+        }
+    }
+    for members in pools.values().filter(|members| members.len() > 1) {
+        let Some(primary) = members.iter().copied().min_by_key(|index| {
+            let disk = &disks[*index];
+            (
+                disk.mount_kind == MountKind::Bind,
+                disk.mount_point.len(),
+                disk.mount_point.clone(),
+            )
+        }) else {
+            continue;
+        };
+        let primary_mount = disks[primary].mount_point.clone();
+        for index in members.iter().copied().filter(|index| *index != primary) {
+            let disk = &mut disks[index];
+            disk.parent_mount = Some(primary_mount.clone());
+            disk.metadata_notes.push(format!(
+                "Shares storage pool {} with {}; free space is counted once there.",
+                disk.storage_pool.as_deref().unwrap_or_default(),
+                primary_mount
+            ));
+        }
+    }
+}
+
+fn classify_mount(record: &MountRecord) -> (MountKind, Option<String>) {
+    let backing = if record.source.starts_with("/dev/") {
+        record.source.as_str()
+    } else {
+        record.device_number.as_str()
+    };
+    match record.fs_type.as_str() {
+        "zfs" => {
+            let pool = record.source.split('/').next().unwrap_or(&record.source);
+            (MountKind::ZfsDataset, Some(format!("zfs:{pool}")))
+        }
+        // Every overlay has its own upper layer, so overlays never share a pool.
+        "overlay" => (MountKind::Overlay, None),
+        "btrfs" => {
+            let subvolume = record
+                .super_options
+                .split(',')
+                .find_map(|option| option.strip_prefix("subvol="))
+                .unwrap_or("/");
+            let kind = if record.root != subvolume {
+                MountKind::Bind
+            } else if subvolume != "/" {
+                MountKind::BtrfsSubvolume
+            } else {
+                MountKind::Filesystem
+            };
+            (kind, Some(format!("btrfs:{backing}")))
+        }
+        fs_type => {
+            let kind = if record.root == "/" {
+                MountKind::Filesystem
+            } else {
+                MountKind::Bind
+            };
+            (kind, Some(format!("{fs_type}:{backing}")))
+        }
+    }
+}
+
+// Linux `makedev` encoding of a `major:minor` pair, as `st_dev` reports it.
+fn linux_device_id(number: &str) -> Option<u64> {
+    let (major, minor) = number.split_once(':')?;
+    let major = major.parse::<u64>().ok()?;
+    let minor = minor.parse::<u64>().ok()?;
+    Some(
+        ((major & 0xffff_f000) << 32)
+            | ((major & 0x0000_0fff) << 8)
+            | ((minor & 0xffff_ff00) << 12)
+            | (minor & 0x0000_00ff),
+    )
+}
+
 fn enrich_disk(
     probe: DiskProbe,
     os_mount: Option<&str>,
@@ -188,6 +576,15 @@ fn enrich_disk(
         metadata_notes,
         role_hint: Default::default(),
         target_role_eligibility: Vec::new(),
+        device_id: None,
+        mount_kind: Default::default(),
+        storage_pool: None,
+        parent_mount: None,
+        reserved_bytes: None,
+        snapshot_bytes: None,
+        logical_used_bytes: None,
+        effective_free_space_bytes: None,
+        health: None,
     }
 }
 
@@ -362,8 +759,19 @@ struct LinuxLsblkNode {
     children: Vec<LinuxLsblkNode>,
 }
 
+/// Sysfs hints come first; lsblk only fills fields sysfs left empty, because its
+/// confidence is lower and its JSON shape varies between util-linux releases.
 #[cfg(target_os = "linux")]
 fn collect_linux_platform_hints() -> HashMap<String, PlatformDiskHint> {
+    let mut hints = sysfs::collect_sysfs_platform_hints(Path::new("/"));
+    for (mount, hint) in collect_lsblk_platform_hints() {
+        upsert_platform_hint(&mut hints, &mount, hint);
+    }
+    hints
+}
+
+#[cfg(target_os = "linux")]
+fn collect_lsblk_platform_hints() -> HashMap<String, PlatformDiskHint> {
     let output = match Command::new("lsblk")
         .args(["-J", "-o", "MOUNTPOINT,MOUNTPOINTS,MODEL,VENDOR,ROTA,TRAN"])
         .output()
@@ -940,9 +1348,15 @@ const KNOWN_VENDORS: &[(&str, &str)] = &[
 
 #[cfg(test)]
 mod tests {
-    use crate::model::{DiskStorageType, LocalityClass};
+    use crate::model::{DiskStorageType, LocalityClass, MountKind};
+
+    use std::fs;
 
-    use super::{detect_os_mount, enrich_disks, DiskProbe};
+    use super::{
+        apply_mount_topology, collect_mount_records, detect_os_mount, enrich_disks_without_space,
+        enrich_inventory, enrich_local_disks, parse_mountinfo, DiskInventory, DiskProbe,
+        FilesystemSpace,
+    };
     use crate::model::DiskKind;
 
     #[test]
@@ -957,7 +1371,7 @@ mod tests {
             is_removable: false,
         };
 
-        let disks = enrich_disks(vec![probe]);
+        let disks = enrich_disks_without_space(vec![probe]);
         let disk = &disks[0];
         assert_eq!(disk.locality_class, LocalityClass::CloudBacked);
         assert_eq!(disk.storage_type, DiskStorageType::CloudBacked);
@@ -975,7 +1389,7 @@ mod tests {
             file_system: Some("ntfs".to_string()),
             is_removable: false,
         };
-        let disks = enrich_disks(vec![probe]);
+        let disks = enrich_disks_without_space(vec![probe]);
         let disk = &disks[0];
         assert_eq!(disk.storage_type, DiskStorageType::Nvme);
         assert_eq!(disk.locality_class, LocalityClass::LocalPhysical);
@@ -1011,10 +1425,173 @@ mod tests {
             is_removable: false,
         };
 
-        let disks = enrich_disks(vec![probe_os, probe_other]);
+        let disks = enrich_disks_without_space(vec![probe_os, probe_other]);
         assert!(disks.iter().any(|disk| disk.is_os_drive));
         assert!(disks
             .iter()
             .any(|disk| disk.is_os_drive && disk.mount_point.eq_ignore_ascii_case(&os_mount)));
     }
+
+    #[test]
+    fn reads_mount_table_under_sysroot() {
+        let sysroot = tempfile::tempdir().expect("sysroot");
+        fs::create_dir_all(sysroot.path().join("proc/self")).expect("proc dir");
+        fs::write(
+            sysroot.path().join("proc/self/mountinfo"),
+            "30 1 0:45 / /tank rw,noatime shared:5 - zfs tank rw,xattr\n\
+             31 30 0:46 / /tank/media rw,noatime shared:6 - zfs tank/media rw,xattr\n",
+        )
+        .expect("mountinfo");
+
+        let mounts = collect_mount_records(sysroot.path());
+        if !cfg!(target_os = "linux") {
+            assert!(mounts.is_empty());
+            return;
+        }
+        assert_eq!(mounts.len(), 2);
+        let probe = |mount: &str| DiskProbe {
+            name: mount.to_string(),
+            mount_point: mount.to_string(),
+            total_space_bytes: 1_000,
+            free_space_bytes: 500,
+            disk_kind: DiskKind::Hdd,
+            file_system: Some("zfs".to_string()),
+            is_removable: false,
+        };
+        let disks = enrich_local_disks(
+            vec![probe("/tank"), probe("/tank/media")],
+            &mounts,
+            &|_, _, _| FilesystemSpace::default(),
+            &mut Vec::new(),
+        );
+        let media = disks
+            .iter()
+            .find(|disk| disk.mount_point == "/tank/media")
+            .expect("media");
+        assert_eq!(media.mount_kind, MountKind::ZfsDataset);
+        assert_eq!(media.parent_mount.as_deref(), Some("/tank"));
+        assert!(collect_mount_records(&sysroot.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn groups_subvolumes_datasets_and_bind_mounts_into_pools() {
+        let mountinfo = "\
+22 1 0:31 /@ / rw,relatime shared:1 - btrfs /dev/nvme0n1p2 rw,ssd,subvol=/@
+23 22 0:31 /@home /home rw,relatime shared:2 - btrfs /dev/nvme0n1p2 rw,ssd,subvol=/@home
+24 22 0:31 /@home/shared /srv/share rw,relatime shared:2 - btrfs /dev/nvme0n1p2 rw,ssd,subvol=/@home
+30 22 0:45 / /tank rw,noatime shared:5 - zfs tank rw,xattr
+31 30 0:46 / /tank/media rw,noatime shared:6 - zfs tank/media rw,xattr
+40 22 8:17 / /mnt/backup\\040disk rw,relatime shared:7 - ext4 /dev/sdb1 rw
+41 22 8:17 / /mnt/backup-again rw,relatime shared:8 - ext4 /dev/sdb1 rw
+50 22 0:60 / /var/lib/docker/overlay2/merged rw - overlay overlay rw,lowerdir=/l,upperdir=/u
+";
+        let probes = [
+            "/",
+            "/home",
+            "/srv/share",
+            "/tank",
+            "/tank/media",
+            "/mnt/backup disk",
+            "/mnt/backup-again",
+            "/var/lib/docker/overlay2/merged",
+        ]
+        .into_iter()
+        .map(|mount| DiskProbe {
+            name: mount.to_string(),
+            mount_point: mount.to_string(),
+            total_space_bytes: 1_000,
+            free_space_bytes: 500,
+            disk_kind: DiskKind::Ssd,
+            file_system: None,
+            is_removable: false,
+        })
+        .collect();
+        let mut disks = enrich_disks_without_space(probes);
+        for disk in &mut disks {
+            disk.device_id = None;
+        }
+        apply_mount_topology(&mut disks, &parse_mountinfo(mountinfo));
+        let disk = |mount: &str| {
+            disks
+                .iter()
+                .find(|disk| disk.mount_point == mount)
+                .expect("disk")
+        };
+
+        assert_eq!(disk("/").mount_kind, MountKind::BtrfsSubvolume);
+        assert_eq!(
+            disk("/").storage_pool.as_deref(),
+            Some("btrfs:/dev/nvme0n1p2")
+        );
+        assert_eq!(disk("/").parent_mount, None);
+        assert_eq!(disk("/home").parent_mount.as_deref(), Some("/"));
+        assert_eq!(disk("/srv/share").mount_kind, MountKind::Bind);
+        assert_eq!(disk("/srv/share").parent_mount.as_deref(), Some("/"));
+
+        assert_eq!(disk("/tank/media").mount_kind, MountKind::ZfsDataset);
+        assert_eq!(
+            disk("/tank/media").storage_pool.as_deref(),
+            Some("zfs:tank")
+        );
+        assert_eq!(disk("/tank/media").parent_mount.as_deref(), Some("/tank"));
+
+        // The same filesystem mounted twice: the shorter path is the primary.
+        assert_eq!(disk("/mnt/backup disk").device_id, Some(8 << 8 | 17));
+        assert_eq!(disk("/mnt/backup disk").parent_mount, None);
+        assert_eq!(
+            disk("/mnt/backup-again").parent_mount.as_deref(),
+            Some("/mnt/backup disk")
+        );
+
+        let overlay = disk("/var/lib/docker/overlay2/merged");
+        assert_eq!(overlay.mount_kind, MountKind::Overlay);
+        assert_eq!(overlay.storage_pool, None);
+    }
+
+    #[test]
+    fn enriches_a_captured_inventory_without_probing_this_machine() {
+        let inventory: DiskInventory = serde_json::from_str(include_str!(
+            "../../../fixtures/disk-inventory-linux-homelab.json"
+        ))
+        .expect("inventory");
+        let round_trip: DiskInventory =
+            serde_json::from_str(&serde_json::to_string(&inventory).expect("serialize"))
+                .expect("deserialize");
+        assert_eq!(round_trip, inventory);
+
+        let disks = enrich_inventory(&inventory);
+        assert_eq!(disks, enrich_inventory(&inventory));
+        let disk = |mount: &str| {
+            disks
+                .iter()
+                .find(|disk| disk.mount_point == mount)
+                .expect("disk")
+        };
+
+        assert_eq!(disks.len(), 5);
+        assert!(disks.iter().all(|disk| disk.device_id.is_none()));
+        assert!(disk("/").is_os_drive);
+        assert_eq!(disk("/").model.as_deref(), Some("Samsung SSD 980 PRO 1TB"));
+        assert_eq!(disk("/").storage_type, DiskStorageType::Nvme);
+        assert_eq!(disk("/srv/media").rotational, Some(true));
+        assert_eq!(disk("/data").mount_kind, MountKind::BtrfsSubvolume);
+        assert_eq!(
+            disk("/data/.snapshots").parent_mount.as_deref(),
+            Some("/data")
+        );
+        assert_eq!(
+            disk("/mnt/onedrive").locality_class,
+            LocalityClass::CloudBacked
+        );
+
+        // Free space and drive health come from the captured host, not this one.
+        assert_eq!(disk("/").reserved_bytes, Some(25_005_393_920));
+        assert_eq!(disk("/").effective_free_space_bytes, Some(16_106_127_360));
+        assert_eq!(disk("/srv/media").effective_free_space_bytes, None);
+        for mount in ["/data", "/data/.snapshots"] {
+            let health = disk(mount).health.as_ref().expect("health");
+            assert_eq!(health.reallocated_sectors, Some(24));
+        }
+        assert!(disk("/").health.is_none());
+    }
 }
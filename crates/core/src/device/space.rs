@@ -0,0 +1,373 @@
+//! Filesystem-specific free space: reserved blocks from `statvfs`, snapshot-held and
+//! logical sizes from `zfs get`, and the free-space estimate and snapshot-exclusive
+//! bytes from `btrfs filesystem usage` and `btrfs qgroup show`. Probes only read;
+//! nothing is mounted or changed, and a missing tool is reported as a warning.
+
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::DiskInfo;
+
+use super::MountRecord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StatvfsSpace {
+    reserved_bytes: u64,
+    available_bytes: u64,
+}
+
+/// What a pooled filesystem's own tooling reports beyond `statvfs`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct PoolSpace {
+    free_bytes: Option<u64>,
+    snapshot_bytes: Option<u64>,
+    logical_used_bytes: Option<u64>,
+}
+
+/// The filesystem free-space fields of one mount, as copied onto `DiskInfo`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilesystemSpace {
+    pub reserved_bytes: Option<u64>,
+    pub effective_free_space_bytes: Option<u64>,
+    pub snapshot_bytes: Option<u64>,
+    pub logical_used_bytes: Option<u64>,
+}
+
+impl FilesystemSpace {
+    fn new(statvfs: Option<StatvfsSpace>, pool: Option<PoolSpace>) -> Self {
+        let pool = pool.unwrap_or_default();
+        Self {
+            reserved_bytes: statvfs.map(|space| space.reserved_bytes),
+            effective_free_space_bytes: pool
+                .free_bytes
+                .or(statvfs.map(|space| space.available_bytes)),
+            snapshot_bytes: pool.snapshot_bytes,
+            logical_used_bytes: pool.logical_used_bytes,
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Measures one mount's filesystem space given the mount table; warnings collect
+/// tools that are missing or fail.
+pub(crate) type SpaceProbe = dyn Fn(&str, &[MountRecord], &mut Vec<String>) -> FilesystemSpace;
+
+/// Fills reserved, snapshot-held, logical and effective free space for every disk
+/// whose filesystem `probe_space` can measure.
+pub(super) fn apply_filesystem_space(
+    disks: &mut [DiskInfo],
+    mounts: &[MountRecord],
+    probe_space: &SpaceProbe,
+    warnings: &mut Vec<String>,
+) {
+    for disk in disks.iter_mut() {
+        let space = probe_space(&disk.mount_point, mounts, warnings);
+        apply_space(disk, &space);
+    }
+}
+
+/// Measures `mount_point` on this machine; the mount table decides which
+/// filesystem tool, if any, is asked on top of `statvfs`.
+pub(super) fn probe_filesystem_space(
+    mount_point: &str,
+    mounts: &[MountRecord],
+    warnings: &mut Vec<String>,
+) -> FilesystemSpace {
+    let statvfs = statvfs_space(Path::new(mount_point));
+    let record = mounts
+        .iter()
+        .rev()
+        .find(|record| record.mount_point == mount_point);
+    let pool = match record.map(|record| record.fs_type.as_str()) {
+        Some("zfs") => record.and_then(|record| zfs_space(&record.source, warnings)),
+        Some("btrfs") => btrfs_space(mount_point, warnings),
+        _ => None,
+    };
+    FilesystemSpace::new(statvfs, pool)
+}
+
+pub(super) fn apply_space(disk: &mut DiskInfo, space: &FilesystemSpace) {
+    if space.effective_free_space_bytes.is_some() {
+        disk.reserved_bytes = space.reserved_bytes;
+        disk.effective_free_space_bytes = space.effective_free_space_bytes;
+    }
+    if space.snapshot_bytes.is_some() || space.logical_used_bytes.is_some() {
+        disk.snapshot_bytes = space.snapshot_bytes;
+        disk.logical_used_bytes = space.logical_used_bytes;
+    }
+    if let Some(snapshot_bytes) = disk.snapshot_bytes.filter(|bytes| *bytes > 0) {
+        disk.metadata_notes.push(format!(
+            "Snapshots hold {:.1} GiB; deleting live files will not free that space.",
+            snapshot_bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+        ));
+    }
+}
+
+#[cfg(unix)]
+fn statvfs_space(path: &Path) -> Option<StatvfsSpace> {
+    let stat = rustix::fs::statvfs(path).ok()?;
+    Some(space_from_statvfs(
+        stat.f_frsize,
+        stat.f_bfree,
+        stat.f_bavail,
+    ))
+}
+
+#[cfg(not(unix))]
+fn statvfs_space(_path: &Path) -> Option<StatvfsSpace> {
+    None
+}
+
+fn space_from_statvfs(fragment_size: u64, free_blocks: u64, available_blocks: u64) -> StatvfsSpace {
+    StatvfsSpace {
+        reserved_bytes: free_blocks
+            .saturating_sub(available_blocks)
+            .saturating_mul(fragment_size),
+        available_bytes: available_blocks.saturating_mul(fragment_size),
+    }
+}
+
+/// Runs a read-only filesystem tool and returns its stdout. A missing tool is warned
+/// about once per scan, a failing command once per mount.
+fn run_tool(tool: &str, args: &[&str], target: &str, warnings: &mut Vec<String>) -> Option<String> {
+    let warning = match Command::new(tool).args(args).output() {
+        Ok(output) if output.status.success() => {
+            return Some(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+        Ok(output) => format!(
+            "`{tool} {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .next()
+                .unwrap_or("no error output")
+                .trim()
+        ),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            format!("{tool} is not installed; {tool} free space falls back to statvfs")
+        }
+        Err(err) => format!("{tool} could not be run for {target}: {err}"),
+    };
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+    None
+}
+
+fn zfs_space(dataset: &str, warnings: &mut Vec<String>) -> Option<PoolSpace> {
+    let stdout = run_tool(
+        "zfs",
+        &[
+            "get",
+            "-Hp",
+            "-o",
+            "property,value",
+            "usedbysnapshots,logicalused",
+            dataset,
+        ],
+        dataset,
+        warnings,
+    )?;
+    Some(parse_zfs_space(&stdout))
+}
+
+// `zfs get -Hp -o property,value` prints one tab-separated `property value` pair
+// per line, with `-` for properties the pool version lacks.
+fn parse_zfs_space(text: &str) -> PoolSpace {
+    let mut space = PoolSpace::default();
+    for line in text.lines() {
+        let Some((property, value)) = line.split_once('\t') else {
+            continue;
+        };
+        let value = value.trim().parse::<u64>().ok();
+        match property.trim() {
+            "usedbysnapshots" => space.snapshot_bytes = value,
+            "logicalused" => space.logical_used_bytes = value,
+            _ => {}
+        }
+    }
+    space
+}
+
+/// The free-space estimate accounts for RAID profiles and unallocated chunks, which
+/// `statvfs` on btrfs does not. Snapshot-held bytes need quotas; without them (or
+/// without privileges) only the estimate is reported.
+fn btrfs_space(mount_point: &str, warnings: &mut Vec<String>) -> Option<PoolSpace> {
+    let usage = run_tool(
+        "btrfs",
+        &["filesystem", "usage", "-b", mount_point],
+        mount_point,
+        warnings,
+    )?;
+    let mut space = PoolSpace {
+        free_bytes: parse_btrfs_free_estimate(&usage),
+        ..PoolSpace::default()
+    };
+    let snapshots = run_tool(
+        "btrfs",
+        &["subvolume", "list", "-s", mount_point],
+        mount_point,
+        warnings,
+    );
+    if let Some(snapshots) = snapshots {
+        let snapshot_ids = parse_btrfs_subvolume_ids(&snapshots);
+        space.snapshot_bytes = if snapshot_ids.is_empty() {
+            Some(0)
+        } else {
+            run_tool(
+                "btrfs",
+                &["qgroup", "show", "--raw", mount_point],
+                mount_point,
+                warnings,
+            )
+            .map(|qgroups| parse_btrfs_exclusive_bytes(&qgroups, &snapshot_ids))
+        };
+    }
+    Some(space)
+}
+
+// `btrfs filesystem usage -b` prints `Free (estimated):  <bytes>  (min: <bytes>)` in
+// its `Overall:` section.
+fn parse_btrfs_free_estimate(text: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("Free (estimated):")?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    })
+}
+
+// `btrfs subvolume list -s` prints `ID <id> gen <gen> cgen <gen> top level <id> ...`.
+fn parse_btrfs_subvolume_ids(text: &str) -> HashSet<u64> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            (fields.next()? == "ID").then_some(())?;
+            fields.next()?.parse().ok()
+        })
+        .collect()
+}
+
+// `btrfs qgroup show --raw` prints `qgroupid rfer excl [path]` rows; level-0 groups
+// `0/<id>` track one subvolume each, and their `excl` bytes are freed only when that
+// subvolume is deleted.
+fn parse_btrfs_exclusive_bytes(text: &str, subvolume_ids: &HashSet<u64>) -> u64 {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let id = fields.next()?.strip_prefix("0/")?.parse::<u64>().ok()?;
+            let exclusive = fields.nth(1)?.parse::<u64>().ok()?;
+            subvolume_ids.contains(&id).then_some(exclusive)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::device::{effective_free_ratio, enrich_local_disks, DiskProbe};
+    use crate::model::DiskKind;
+
+    use super::{
+        parse_btrfs_exclusive_bytes, parse_btrfs_free_estimate, parse_btrfs_subvolume_ids,
+        parse_zfs_space, space_from_statvfs, FilesystemSpace, PoolSpace,
+    };
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn effective_free_space_excludes_reserved_blocks_and_reports_snapshots() {
+        let probe = DiskProbe {
+            name: "tank/home".to_string(),
+            mount_point: "/tank/home".to_string(),
+            total_space_bytes: 100 * GIB,
+            free_space_bytes: 12 * GIB,
+            disk_kind: DiskKind::Hdd,
+            file_system: Some("zfs".to_string()),
+            is_removable: false,
+        };
+        let mut warnings = Vec::new();
+        let unprobed = enrich_local_disks(
+            vec![probe.clone()],
+            &[],
+            &|_, _, _| FilesystemSpace::default(),
+            &mut warnings,
+        );
+        assert_eq!(effective_free_ratio(&unprobed[0]), Some(0.12));
+
+        // 5 GiB of the 12 GiB the kernel calls free is reserved for root.
+        let statvfs = space_from_statvfs(4096, 12 * GIB / 4096, 7 * GIB / 4096);
+        let zfs =
+            parse_zfs_space("usedbysnapshots\t32212254720\nlogicalused\t139586437120\nbogus\t-\n");
+        let probe_space = move |mount_point: &str, _: &_, _: &mut _| {
+            assert_eq!(mount_point, "/tank/home");
+            FilesystemSpace::new(Some(statvfs), Some(zfs))
+        };
+        let disk = enrich_local_disks(vec![probe], &[], &probe_space, &mut warnings).remove(0);
+
+        assert_eq!(disk.reserved_bytes, Some(5 * GIB));
+        assert_eq!(disk.effective_free_space_bytes, Some(7 * GIB));
+        assert_eq!(disk.snapshot_bytes, Some(30 * GIB));
+        assert_eq!(disk.logical_used_bytes, Some(130 * GIB));
+        let ratio = effective_free_ratio(&disk).expect("ratio");
+        assert!((ratio - 7.0 / 95.0).abs() < 1e-9);
+        assert!(disk
+            .metadata_notes
+            .iter()
+            .any(|note| note.starts_with("Snapshots hold")));
+        assert!(warnings.is_empty());
+
+        assert_eq!(parse_zfs_space("usedbysnapshots\t-\n").snapshot_bytes, None);
+    }
+
+    #[test]
+    fn btrfs_free_estimate_and_snapshot_exclusive_bytes_are_parsed() {
+        let usage = "\
+Overall:
+    Device size:\t\t     1000204886016
+    Device allocated:\t\t      322122547200
+    Used:\t\t\t      300647710720
+    Free (estimated):\t\t      690568806400\t(min: 345284403200)
+    Free (statfs, df):\t\t      690568806400
+    Data ratio:\t\t\t              1.00
+";
+        let subvolumes = "\
+ID 261 gen 1042 cgen 1040 top level 5 otime 2024-05-01 10:00:00 path .snapshots/home-1
+ID 262 gen 1100 cgen 1099 top level 5 otime 2024-05-02 10:00:00 path .snapshots/home-2
+";
+        let qgroups = "\
+qgroupid         rfer         excl     path
+--------         ----         ----     ----
+0/5       53687091200  10737418240     <toplevel>
+0/261     42949672960   2147483648     .snapshots/home-1
+0/262     43486543872   1073741824     .snapshots/home-2
+1/100     86436216832   3221225472     <under deletion>
+";
+        let snapshot_ids = parse_btrfs_subvolume_ids(subvolumes);
+        assert_eq!(snapshot_ids, HashSet::from([261, 262]));
+        let pool = PoolSpace {
+            free_bytes: parse_btrfs_free_estimate(usage),
+            snapshot_bytes: Some(parse_btrfs_exclusive_bytes(qgroups, &snapshot_ids)),
+            logical_used_bytes: None,
+        };
+        assert_eq!(pool.free_bytes, Some(690_568_806_400));
+        assert_eq!(pool.snapshot_bytes, Some(3 * GIB));
+
+        // The btrfs estimate wins over `statvfs`, which ignores RAID profiles.
+        let statvfs = space_from_statvfs(4096, 700 * GIB / 4096, 700 * GIB / 4096);
+        let space = FilesystemSpace::new(Some(statvfs), Some(pool));
+        assert_eq!(space.effective_free_space_bytes, Some(690_568_806_400));
+        assert_eq!(space.reserved_bytes, Some(0));
+        assert_eq!(parse_btrfs_free_estimate("Overall:\n"), None);
+    }
+}
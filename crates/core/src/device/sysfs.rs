@@ -0,0 +1,327 @@
+//! Native Linux disk hints read from procfs and sysfs under a configurable sysroot,
+//! so the provider runs against fixture trees in tests and needs no external tools.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use super::{
+    normalize_interface_hint, normalize_optional_field, parse_mountinfo, upsert_platform_hint,
+    PlatformDiskHint,
+};
+
+/// Stacked devices (dm, LVM, md) nest only a few levels deep; the bound guards
+/// against cycles in malformed trees.
+const MAX_STACK_DEPTH: usize = 8;
+
+#[derive(Debug, Clone)]
+struct PhysicalDisk {
+    name: String,
+    vendor: Option<String>,
+    model: Option<String>,
+    interface: Option<String>,
+    rotational: Option<bool>,
+    removable: bool,
+}
+
+/// Hints for every block-backed mount in `<sysroot>/proc/self/mountinfo`. A mount
+/// on a partition, device-mapper/LVM volume or md array is resolved down to its
+/// physical disks; fields are kept only where all of those disks agree, except
+/// `rotational`, which is set when any member spins.
+pub(super) fn collect_sysfs_platform_hints(sysroot: &Path) -> HashMap<String, PlatformDiskHint> {
+    let mut hints = HashMap::new();
+    let Ok(mountinfo) = fs::read_to_string(sysroot.join("proc/self/mountinfo")) else {
+        return hints;
+    };
+    let devices_by_number = block_devices_by_number(sysroot);
+
+    for entry in parse_mountinfo(&mountinfo) {
+        let Some(device) = devices_by_number
+            .get(&entry.device_number)
+            .cloned()
+            .or_else(|| device_from_source(sysroot, &entry.source))
+        else {
+            continue;
+        };
+        let mut disks = Vec::new();
+        collect_physical_disks(sysroot, &device, 0, &mut disks);
+        if disks.is_empty() {
+            continue;
+        }
+        upsert_platform_hint(&mut hints, &entry.mount_point, merge_disks(&device, &disks));
+    }
+    hints
+}
+
+fn block_devices_by_number(sysroot: &Path) -> HashMap<String, String> {
+    let mut devices = HashMap::new();
+    let Ok(entries) = fs::read_dir(sysroot.join("sys/class/block")) else {
+        return devices;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(number) = read_attribute(&entry.path().join("dev")) {
+            devices.insert(number, name);
+        }
+    }
+    devices
+}
+
+// Filesystems such as btrfs report an anonymous device number, so the mount
+// source is the fallback: `/dev/sda1`, `/dev/mapper/<name>` or `/dev/md/<name>`.
+fn device_from_source(sysroot: &Path, source: &str) -> Option<String> {
+    let name = source.strip_prefix("/dev/")?;
+    if let Some(mapped) = name.strip_prefix("mapper/") {
+        return find_block_device(sysroot, |path| {
+            read_attribute(&path.join("dm/name")).as_deref() == Some(mapped)
+        });
+    }
+    if let Some(array) = name.strip_prefix("md/") {
+        return find_block_device(sysroot, |path| {
+            read_attribute(&path.join("md/array_name")).as_deref() == Some(array)
+                || path.file_name().and_then(|file| file.to_str()) == Some(array)
+        });
+    }
+    sysroot
+        .join("sys/class/block")
+        .join(name)
+        .exists()
+        .then(|| name.to_string())
+}
+
+fn find_block_device(sysroot: &Path, matches: impl Fn(&Path) -> bool) -> Option<String> {
+    fs::read_dir(sysroot.join("sys/block"))
+        .ok()?
+        .flatten()
+        .find(|entry| matches(&entry.path()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+}
+
+fn collect_physical_disks(sysroot: &Path, device: &str, depth: usize, out: &mut Vec<PhysicalDisk>) {
+    if depth > MAX_STACK_DEPTH {
+        return;
+    }
+    let Some(disk) = whole_disk_for(sysroot, device) else {
+        return;
+    };
+    let disk_dir = sysroot.join("sys/block").join(&disk);
+    let slaves = list_names(&disk_dir.join("slaves"));
+    if !slaves.is_empty() {
+        for slave in slaves {
+            collect_physical_disks(sysroot, &slave, depth + 1, out);
+        }
+        return;
+    }
+    if out.iter().any(|known| known.name == disk) {
+        return;
+    }
+    out.push(read_physical_disk(sysroot, &disk_dir, &disk));
+}
+
+// Partitions live as `sys/block/<disk>/<partition>`; whole disks, dm and md devices
+// appear directly under `sys/block`.
+fn whole_disk_for(sysroot: &Path, device: &str) -> Option<String> {
+    let block = sysroot.join("sys/block");
+    if block.join(device).is_dir() {
+        return Some(device.to_string());
+    }
+    find_block_device(sysroot, |path| path.join(device).join("partition").exists())
+}
+
+fn read_physical_disk(sysroot: &Path, disk_dir: &Path, name: &str) -> PhysicalDisk {
+    // `sys/block/<disk>` links into the device tree; a USB bridge shows up as a
+    // `usbN` component of the resolved path.
+    let sysroot = fs::canonicalize(sysroot).unwrap_or_else(|_| sysroot.to_path_buf());
+    let device_path = fs::canonicalize(disk_dir).unwrap_or_else(|_| disk_dir.to_path_buf());
+    let via_usb = device_path
+        .strip_prefix(&sysroot)
+        .unwrap_or(&device_path)
+        .components()
+        .any(|component| component.as_os_str().to_string_lossy().starts_with("usb"));
+    let interface = if via_usb {
+        Some("usb")
+    } else if name.starts_with("nvme") {
+        Some("nvme")
+    } else if name.starts_with("vd") {
+        Some("virtio")
+    } else if name.starts_with("sd") || name.starts_with("hd") {
+        Some("sata")
+    } else {
+        None
+    };
+    PhysicalDisk {
+        name: name.to_string(),
+        vendor: read_attribute(&disk_dir.join("device/vendor")),
+        model: read_attribute(&disk_dir.join("device/model")),
+        interface: normalize_interface_hint(interface).map(str::to_string),
+        rotational: read_attribute(&disk_dir.join("queue/rotational")).map(|value| value != "0"),
+        removable: read_attribute(&disk_dir.join("removable")).as_deref() == Some("1"),
+    }
+}
+
+fn merge_disks(device: &str, disks: &[PhysicalDisk]) -> PlatformDiskHint {
+    fn agreed(values: impl Iterator<Item = Option<String>>) -> Option<String> {
+        let values = values.collect::<BTreeSet<_>>();
+        match values.len() {
+            1 => values.into_iter().next().flatten(),
+            _ => None,
+        }
+    }
+
+    let vendor = agreed(disks.iter().map(|disk| disk.vendor.clone()));
+    let model = agreed(disks.iter().map(|disk| disk.model.clone()));
+    let interface = if disks.iter().any(|disk| disk.removable) {
+        Some("usb".to_string())
+    } else {
+        agreed(disks.iter().map(|disk| disk.interface.clone()))
+    };
+    let rotational = if disks.iter().any(|disk| disk.rotational == Some(true)) {
+        Some(true)
+    } else if disks.iter().all(|disk| disk.rotational == Some(false)) {
+        Some(false)
+    } else {
+        None
+    };
+    let known_fields = [
+        vendor.is_some(),
+        model.is_some(),
+        interface.is_some(),
+        rotational.is_some(),
+    ]
+    .iter()
+    .filter(|known| **known)
+    .count();
+    let names = disks
+        .iter()
+        .map(|disk| disk.name.as_str())
+        .collect::<Vec<_>>()
+        .join("+");
+    let source = if disks.len() == 1 && disks[0].name == device {
+        "linux_sysfs".to_string()
+    } else {
+        format!("linux_sysfs {device} -> {names}")
+    };
+
+    PlatformDiskHint {
+        vendor,
+        model,
+        interface,
+        rotational,
+        confidence: (0.85 + known_fields as f32 * 0.025).min(0.95),
+        source,
+    }
+}
+
+fn list_names(dir: &Path) -> Vec<String> {
+    let mut names = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn read_attribute(path: &Path) -> Option<String> {
+    let raw = fs::read_to_string(path).ok()?;
+    normalize_optional_field(Some(&raw)).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::TempDir;
+
+    use super::collect_sysfs_platform_hints;
+
+    fn write(root: &Path, relative: &str, contents: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().expect("parent")).expect("dirs");
+        fs::write(path, contents).expect("write");
+    }
+
+    fn disk(root: &Path, name: &str, model: &str, vendor: &str, rotational: &str) {
+        write(
+            root,
+            &format!("sys/block/{name}/device/model"),
+            &format!("{model}\n"),
+        );
+        write(
+            root,
+            &format!("sys/block/{name}/device/vendor"),
+            &format!("{vendor}\n"),
+        );
+        write(
+            root,
+            &format!("sys/block/{name}/queue/rotational"),
+            rotational,
+        );
+        write(root, &format!("sys/block/{name}/removable"), "0\n");
+    }
+
+    fn block_dev(root: &Path, name: &str, number: &str) {
+        write(
+            root,
+            &format!("sys/class/block/{name}/dev"),
+            &format!("{number}\n"),
+        );
+    }
+
+    #[test]
+    fn resolves_partitions_and_lvm_stacks_to_physical_disks() {
+        let temp = TempDir::new().expect("tempdir");
+        let root = temp.path();
+        write(
+            root,
+            "proc/self/mountinfo",
+            "22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw\n\
+             23 22 0:21 / /proc rw,nosuid shared:2 - proc proc rw\n\
+             24 22 253:0 / /srv/media\\040library rw,relatime shared:3 - xfs /dev/mapper/vg-media rw\n\
+             25 22 0:40 / /data rw,relatime shared:4 - btrfs /dev/sdc1 rw,subvol=/\n",
+        );
+        disk(root, "nvme0n1", "Samsung SSD 980 PRO 1TB", "", "0\n");
+        write(root, "sys/block/nvme0n1/nvme0n1p2/partition", "2\n");
+        block_dev(root, "nvme0n1p2", "259:2");
+
+        // An LVM volume spanning partitions on two spinning disks.
+        disk(root, "sda", "ST4000DM004-2CV1", "ATA", "1\n");
+        disk(root, "sdb", "ST4000DM004-2CV1", "ATA", "1\n");
+        write(root, "sys/block/sda/sda1/partition", "1\n");
+        write(root, "sys/block/sdb/sdb1/partition", "1\n");
+        fs::create_dir_all(root.join("sys/block/dm-0/slaves/sda1")).expect("slave");
+        fs::create_dir_all(root.join("sys/block/dm-0/slaves/sdb1")).expect("slave");
+        write(root, "sys/block/dm-0/dm/name", "vg-media\n");
+        block_dev(root, "dm-0", "253:0");
+
+        // btrfs reports an anonymous device number; the source names the partition.
+        disk(root, "sdc", "WDC WD40EFRX", "ATA", "1\n");
+        write(root, "sys/block/sdc/sdc1/partition", "1\n");
+        block_dev(root, "sdc1", "8:33");
+
+        let hints = collect_sysfs_platform_hints(root);
+        assert_eq!(hints.len(), 3);
+
+        let os = &hints["/"];
+        assert_eq!(os.model.as_deref(), Some("Samsung SSD 980 PRO 1TB"));
+        assert_eq!(os.vendor, None);
+        assert_eq!(os.interface.as_deref(), Some("nvme"));
+        assert_eq!(os.rotational, Some(false));
+        assert!(os.confidence > 0.9);
+
+        let media = &hints["/srv/media library"];
+        assert_eq!(media.model.as_deref(), Some("ST4000DM004-2CV1"));
+        assert_eq!(media.vendor.as_deref(), Some("ATA"));
+        assert_eq!(media.interface.as_deref(), Some("sata"));
+        assert_eq!(media.rotational, Some(true));
+        assert_eq!(media.source, "linux_sysfs dm-0 -> sda+sdb");
+
+        let data = &hints["/data"];
+        assert_eq!(data.model.as_deref(), Some("WDC WD40EFRX"));
+        assert_eq!(data.rotational, Some(true));
+    }
+}
@@ -0,0 +1,452 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::dedupe::{classify_intent, FileRecord};
+use crate::model::{
+    DirectoryMatchKind, DuplicateDirectory, DuplicateDirectoryGroup, DuplicateGroup,
+};
+
+/// Share of the larger directory's bytes that must be duplicated at matching
+/// relative paths for a pair to be reported as near-identical.
+pub const DEFAULT_DIRECTORY_MIN_OVERLAP: f32 = 0.9;
+/// Files copied into more places than this say little about which directories
+/// mirror each other, and pairing every copy would be quadratic.
+const MAX_PAIRED_GROUP_FILES: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct DirectoryDedupeOptions {
+    /// Directories smaller than this are never reported on their own.
+    pub min_size_bytes: u64,
+    pub min_overlap_ratio: f32,
+}
+
+impl Default for DirectoryDedupeOptions {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: 1_048_576,
+            min_overlap_ratio: DEFAULT_DIRECTORY_MIN_OVERLAP,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct DirectoryEntry {
+    disk_mount: Option<String>,
+    size_bytes: u64,
+    file_count: u64,
+    // Direct children only: `(file name, content token)` and subdirectory names.
+    files: Vec<(String, String)>,
+    subdirectories: BTreeSet<String>,
+}
+
+/// Finds duplicated directory trees under `roots` from the scan's file records and
+/// the content hashes already established by file-level dedupe. Files that are in no
+/// `file_groups` entry are treated as unique content, so `file_groups` should be
+/// computed without a size floor for small files to count.
+pub fn find_duplicate_directories(
+    roots: &[PathBuf],
+    records: &[FileRecord],
+    file_groups: &[DuplicateGroup],
+    options: &DirectoryDedupeOptions,
+) -> Vec<DuplicateDirectoryGroup> {
+    let directories = index_directories(roots, records, file_groups);
+    let tree_hashes = merkle_hashes(&directories);
+    let shared_bytes = shared_bytes_by_pair(&directories, file_groups);
+
+    let identical = |a: &Path, b: &Path| {
+        tree_hashes
+            .get(a)
+            .is_some_and(|hash| tree_hashes.get(b) == Some(hash))
+    };
+    let overlap = |a: &Path, b: &Path| -> f32 {
+        let larger = directories[a].size_bytes.max(directories[b].size_bytes);
+        if larger == 0 {
+            return 0.0;
+        }
+        let shared = shared_bytes
+            .get(&pair_key(a, b))
+            .copied()
+            .unwrap_or_default();
+        (shared as f64 / larger as f64) as f32
+    };
+    let parents_match = |a: &Path, b: &Path| -> Option<(PathBuf, PathBuf)> {
+        if a.file_name() != b.file_name() {
+            return None;
+        }
+        let (parent_a, parent_b) = (a.parent()?, b.parent()?);
+        if parent_a == parent_b
+            || !directories.contains_key(parent_a)
+            || !directories.contains_key(parent_b)
+        {
+            return None;
+        }
+        Some((parent_a.to_path_buf(), parent_b.to_path_buf()))
+    };
+    let pair_qualifies =
+        |a: &Path, b: &Path| identical(a, b) || overlap(a, b) >= options.min_overlap_ratio;
+
+    let mut groups = Vec::new();
+
+    let mut by_hash: HashMap<&str, Vec<&Path>> = HashMap::new();
+    for (path, hash) in &tree_hashes {
+        if directories[path].size_bytes >= options.min_size_bytes {
+            by_hash
+                .entry(hash.as_str())
+                .or_default()
+                .push(path.as_path());
+        }
+    }
+    for (hash, mut members) in by_hash {
+        if members.len() < 2 {
+            continue;
+        }
+        members.sort();
+        // A copied tree also duplicates every subdirectory; only its top is reported.
+        let parents = members
+            .iter()
+            .map(|path| {
+                path.parent()
+                    .filter(|parent| directories.contains_key(*parent))
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(parents) = parents {
+            let distinct = parents.iter().collect::<BTreeSet<_>>().len() == parents.len();
+            if distinct && parents.windows(2).all(|pair| identical(pair[0], pair[1])) {
+                continue;
+            }
+        }
+        if let [a, b] = members[..] {
+            if parents_match(a, b).is_some_and(|(pa, pb)| pair_qualifies(&pa, &pb)) {
+                continue;
+            }
+        }
+
+        let size_bytes = directories[members[0]].size_bytes;
+        groups.push(build_group(
+            &directories,
+            &members,
+            DirectoryMatchKind::Identical,
+            Some(hash.to_string()),
+            1.0,
+            size_bytes.saturating_mul(members.len() as u64 - 1),
+        ));
+    }
+
+    for ((a, b), shared) in &shared_bytes {
+        let (a, b) = (a.as_path(), b.as_path());
+        let larger = directories[a].size_bytes.max(directories[b].size_bytes);
+        let ratio = overlap(a, b);
+        if identical(a, b) || larger < options.min_size_bytes || ratio < options.min_overlap_ratio {
+            continue;
+        }
+        if parents_match(a, b).is_some_and(|(pa, pb)| pair_qualifies(&pa, &pb)) {
+            continue;
+        }
+        groups.push(build_group(
+            &directories,
+            &[a, b],
+            DirectoryMatchKind::NearIdentical,
+            None,
+            ratio,
+            *shared,
+        ));
+    }
+
+    groups.sort_by(|a, b| {
+        b.total_wasted_bytes
+            .cmp(&a.total_wasted_bytes)
+            .then_with(|| a.directories[0].path.cmp(&b.directories[0].path))
+    });
+    groups
+}
+
+fn index_directories(
+    roots: &[PathBuf],
+    records: &[FileRecord],
+    file_groups: &[DuplicateGroup],
+) -> HashMap<PathBuf, DirectoryEntry> {
+    let mut hash_by_path = HashMap::new();
+    for group in file_groups {
+        for file in &group.files {
+            hash_by_path.insert(file.path.as_str(), group.hash.as_str());
+        }
+    }
+    // Dedupe keeps one path per inode, so extra hard links inherit its hash.
+    let mut hash_by_identity = HashMap::new();
+    for record in records {
+        if let (Some(identity), Some(hash)) = (
+            record.device_id.zip(record.inode),
+            hash_by_path.get(record.path.to_string_lossy().as_ref()),
+        ) {
+            hash_by_identity.insert(identity, *hash);
+        }
+    }
+
+    let mut directories: HashMap<PathBuf, DirectoryEntry> = HashMap::new();
+    for record in records {
+        let Some(root) = roots
+            .iter()
+            .filter(|root| record.path.starts_with(root))
+            .max_by_key(|root| root.as_os_str().len())
+        else {
+            continue;
+        };
+        let (Some(parent), Some(name)) = (record.path.parent(), record.path.file_name()) else {
+            continue;
+        };
+
+        let path_text = record.path.to_string_lossy();
+        let token = hash_by_path
+            .get(path_text.as_ref())
+            .or_else(|| {
+                record
+                    .device_id
+                    .zip(record.inode)
+                    .and_then(|identity| hash_by_identity.get(&identity))
+            })
+            .map(|hash| hash.to_string())
+            .unwrap_or_else(|| {
+                if record.size_bytes == 0 {
+                    "empty".to_string()
+                } else {
+                    format!("unique:{path_text}")
+                }
+            });
+        directories
+            .entry(parent.to_path_buf())
+            .or_default()
+            .files
+            .push((name.to_string_lossy().to_string(), token));
+
+        let mut child: Option<&Path> = None;
+        let mut current = Some(parent);
+        while let Some(directory) = current {
+            if !directory.starts_with(root) {
+                break;
+            }
+            let entry = directories.entry(directory.to_path_buf()).or_default();
+            entry.size_bytes = entry.size_bytes.saturating_add(record.size_bytes);
+            entry.file_count += 1;
+            if entry.disk_mount.is_none() {
+                entry.disk_mount = record.disk_mount.clone();
+            }
+            if let Some(name) = child.and_then(|child| child.file_name()) {
+                let name = name.to_string_lossy();
+                if !entry.subdirectories.contains(name.as_ref()) {
+                    entry.subdirectories.insert(name.to_string());
+                }
+            }
+            if directory == root.as_path() {
+                break;
+            }
+            child = Some(directory);
+            current = directory.parent();
+        }
+    }
+    directories
+}
+
+/// Hashes every directory bottom-up over its sorted file names + content tokens and
+/// subdirectory names + tree hashes, so equal hashes mean equal trees.
+fn merkle_hashes(directories: &HashMap<PathBuf, DirectoryEntry>) -> HashMap<PathBuf, String> {
+    let mut order = directories.keys().collect::<Vec<_>>();
+    order.sort_by_key(|path| std::cmp::Reverse(path.components().count()));
+
+    let mut hashes: HashMap<PathBuf, String> = HashMap::with_capacity(order.len());
+    for path in order {
+        let entry = &directories[path];
+        let mut files = entry.files.iter().collect::<Vec<_>>();
+        files.sort();
+
+        let mut hasher = blake3::Hasher::new();
+        for (name, token) in files {
+            hasher.update(b"f\0");
+            hasher.update(name.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(token.as_bytes());
+            hasher.update(b"\n");
+        }
+        for name in &entry.subdirectories {
+            let child = hashes
+                .get(&path.join(name))
+                .map(String::as_str)
+                .unwrap_or_default();
+            hasher.update(b"d\0");
+            hasher.update(name.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(child.as_bytes());
+            hasher.update(b"\n");
+        }
+        hashes.insert(path.clone(), hasher.finalize().to_hex().to_string());
+    }
+    hashes
+}
+
+/// Sums, per directory pair, the bytes of duplicate files found at the same relative
+/// path in both. Each duplicate file pair credits its parents and then keeps climbing
+/// while directory names keep matching.
+fn shared_bytes_by_pair(
+    directories: &HashMap<PathBuf, DirectoryEntry>,
+    file_groups: &[DuplicateGroup],
+) -> HashMap<(PathBuf, PathBuf), u64> {
+    let mut shared = HashMap::new();
+    for group in file_groups
+        .iter()
+        .filter(|group| group.files.len() <= MAX_PAIRED_GROUP_FILES)
+    {
+        for (index, first) in group.files.iter().enumerate() {
+            for second in &group.files[index + 1..] {
+                let (a, b) = (Path::new(&first.path), Path::new(&second.path));
+                if a.file_name() != b.file_name() {
+                    continue;
+                }
+                let (mut current_a, mut current_b) = (a.parent(), b.parent());
+                while let (Some(a), Some(b)) = (current_a, current_b) {
+                    if a.starts_with(b)
+                        || b.starts_with(a)
+                        || !directories.contains_key(a)
+                        || !directories.contains_key(b)
+                    {
+                        break;
+                    }
+                    let bytes = shared.entry(pair_key(a, b)).or_insert(0_u64);
+                    *bytes = bytes.saturating_add(group.size_bytes);
+                    if a.file_name() != b.file_name() {
+                        break;
+                    }
+                    current_a = a.parent();
+                    current_b = b.parent();
+                }
+            }
+        }
+    }
+    shared
+}
+
+fn pair_key(a: &Path, b: &Path) -> (PathBuf, PathBuf) {
+    if a <= b {
+        (a.to_path_buf(), b.to_path_buf())
+    } else {
+        (b.to_path_buf(), a.to_path_buf())
+    }
+}
+
+fn build_group(
+    directories: &HashMap<PathBuf, DirectoryEntry>,
+    members: &[&Path],
+    match_kind: DirectoryMatchKind,
+    tree_hash: Option<String>,
+    overlap_ratio: f32,
+    total_wasted_bytes: u64,
+) -> DuplicateDirectoryGroup {
+    let intent_records = members
+        .iter()
+        .map(|path| {
+            FileRecord::new(
+                path.to_path_buf(),
+                directories[*path].size_bytes,
+                directories[*path].disk_mount.clone(),
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    DuplicateDirectoryGroup {
+        match_kind,
+        tree_hash,
+        directories: members
+            .iter()
+            .map(|path| {
+                let entry = &directories[*path];
+                DuplicateDirectory {
+                    path: path.to_string_lossy().to_string(),
+                    disk_mount: entry.disk_mount.clone(),
+                    file_count: entry.file_count,
+                    size_bytes: entry.size_bytes,
+                }
+            })
+            .collect(),
+        overlap_ratio,
+        total_wasted_bytes,
+        intent: classify_intent(&intent_records),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+    use walkdir::WalkDir;
+
+    use super::{find_duplicate_directories, DirectoryDedupeOptions};
+    use crate::dedupe::{find_duplicates, FileRecord};
+    use crate::model::DirectoryMatchKind;
+
+    #[test]
+    fn reports_top_level_copied_trees_and_near_identical_pairs() {
+        let temp = TempDir::new().expect("tempdir");
+        let root = temp.path().to_path_buf();
+        for base in ["proj", "copy/proj"] {
+            fs::create_dir_all(root.join(base).join("src")).expect("mkdir");
+            fs::write(root.join(base).join("README"), b"readme").expect("write");
+            fs::write(root.join(base).join("src/main.rs"), b"fn main() {}").expect("write");
+        }
+        for base in ["photos", "photos-old"] {
+            fs::create_dir_all(root.join(base)).expect("mkdir");
+            for index in 0..10 {
+                fs::write(
+                    root.join(base).join(format!("{index}.raw")),
+                    vec![index as u8; 1_000],
+                )
+                .expect("write");
+            }
+        }
+        fs::write(root.join("photos-old/notes.txt"), vec![b'x'; 500]).expect("write");
+
+        let records = WalkDir::new(&root)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| {
+                FileRecord::from_path(entry.path().to_path_buf(), None, None).expect("record")
+            })
+            .collect::<Vec<_>>();
+        let mut warnings = Vec::new();
+        let file_groups = find_duplicates(&records, 1, &mut warnings);
+
+        let groups = find_duplicate_directories(
+            std::slice::from_ref(&root),
+            &records,
+            &file_groups,
+            &DirectoryDedupeOptions {
+                min_size_bytes: 1,
+                ..DirectoryDedupeOptions::default()
+            },
+        );
+
+        assert_eq!(groups.len(), 2, "{groups:#?}");
+        let near = &groups[0];
+        assert_eq!(near.match_kind, DirectoryMatchKind::NearIdentical);
+        assert_eq!(near.total_wasted_bytes, 10_000);
+        assert!(near.overlap_ratio > 0.95 && near.overlap_ratio < 1.0);
+        assert!(near.directories[0].path.ends_with("photos"));
+
+        let identical = &groups[1];
+        assert_eq!(identical.match_kind, DirectoryMatchKind::Identical);
+        assert!(identical.tree_hash.is_some());
+        let paths = identical
+            .directories
+            .iter()
+            .map(|directory| directory.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                root.join("copy/proj").to_string_lossy().to_string(),
+                root.join("proj").to_string_lossy().to_string(),
+            ]
+        );
+        assert_eq!(identical.directories[0].file_count, 2);
+    }
+}
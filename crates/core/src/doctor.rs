@@ -1,10 +1,9 @@
 use std::env;
 
 use serde::{Deserialize, Serialize};
-use sysinfo::{DiskKind as SysDiskKind, Disks};
 
-use crate::device::{detect_os_mount, enrich_disks, DiskProbe};
-use crate::model::{DiskInfo, DiskKind};
+use crate::device::{detect_os_mount, enrich_disks, probe_local_disks};
+use crate::model::DiskInfo;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DoctorInfo {
@@ -23,12 +22,14 @@ pub fn collect_doctor_info() -> DoctorInfo {
         .map(|path| path.to_string_lossy().to_string());
     let os_mount = detect_os_mount();
 
-    let disks = enumerate_disks();
+    let mut warnings = Vec::new();
+    let disks = enrich_disks(probe_local_disks(), &mut warnings);
     let mut notes = vec![
         "v1 operates in read-only mode; no file mutations are performed.".to_string(),
         "Network access is not used by the runtime scanner.".to_string(),
         "Cloud/network/virtual mounts are excluded as local placement targets.".to_string(),
     ];
+    notes.extend(warnings);
     if disks.is_empty() {
         notes.push("No disks detected by sysinfo; consider passing explicit --paths.".to_string());
     }
@@ -43,29 +44,3 @@ pub fn collect_doctor_info() -> DoctorInfo {
         notes,
     }
 }
-
-fn enumerate_disks() -> Vec<DiskInfo> {
-    let disks = Disks::new_with_refreshed_list();
-    let probes = disks
-        .list()
-        .iter()
-        .map(|disk| {
-            let disk_kind = match disk.kind() {
-                SysDiskKind::HDD => DiskKind::Hdd,
-                SysDiskKind::SSD => DiskKind::Ssd,
-                _ => DiskKind::Unknown,
-            };
-
-            DiskProbe {
-                name: disk.name().to_string_lossy().to_string(),
-                mount_point: disk.mount_point().to_string_lossy().to_string(),
-                total_space_bytes: disk.total_space(),
-                free_space_bytes: disk.available_space(),
-                disk_kind,
-                file_system: Some(disk.file_system().to_string_lossy().to_string()),
-                is_removable: disk.is_removable(),
-            }
-        })
-        .collect::<Vec<_>>();
-    enrich_disks(probes)
-}
@@ -5,8 +5,10 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::device::{enrich_inventory, load_disk_inventory};
 use crate::model::Report;
 use crate::recommend::generate_recommendation_bundle;
+use crate::role::infer_disk_roles;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvaluationSuite {
@@ -17,6 +19,9 @@ pub struct EvaluationSuite {
 pub struct EvaluationCase {
     pub name: String,
     pub report: String,
+    /// Captured disk inventory whose enriched disks replace the report's disks.
+    #[serde(default)]
+    pub disk_inventory: Option<String>,
     #[serde(default)]
     pub expected_top_ids: Vec<String>,
     #[serde(default)]
@@ -28,6 +33,8 @@ pub struct EvaluationResult {
     pub total_cases: usize,
     pub passed_cases: usize,
     pub precision_at_3: f32,
+    #[serde(default)]
+    pub ndcg_at_3: f32,
     pub contradiction_rate: f32,
     pub unsafe_recommendations: u64,
     pub case_results: Vec<EvaluationCaseResult>,
@@ -41,6 +48,8 @@ pub struct EvaluationCaseResult {
     pub expected_top_ids: Vec<String>,
     pub forbidden_hits: Vec<String>,
     pub precision_at_3: f32,
+    #[serde(default)]
+    pub ndcg_at_3: f32,
     pub contradiction_count: u64,
 }
 
@@ -61,6 +70,7 @@ pub fn evaluate_suite(suite_path: &Path, suite: &EvaluationSuite) -> Result<Eval
     let mut case_results = Vec::new();
     let mut passed_cases = 0_usize;
     let mut precision_total = 0.0_f32;
+    let mut ndcg_total = 0.0_f32;
     let mut contradiction_cases = 0_u64;
     let mut unsafe_recommendations = 0_u64;
 
@@ -68,8 +78,13 @@ pub fn evaluate_suite(suite_path: &Path, suite: &EvaluationSuite) -> Result<Eval
         let report_path = suite_dir.join(&case.report);
         let report_text = fs::read_to_string(&report_path)
             .with_context(|| format!("failed to read report fixture {}", report_path.display()))?;
-        let report: Report = serde_json::from_str(&report_text)
+        let mut report: Report = serde_json::from_str(&report_text)
             .with_context(|| format!("failed to parse fixture {}", report_path.display()))?;
+        if let Some(inventory) = &case.disk_inventory {
+            let inventory = load_disk_inventory(&suite_dir.join(inventory))?;
+            report.disks = enrich_inventory(&inventory);
+            infer_disk_roles(&mut report.disks, &report.categories);
+        }
 
         let bundle = generate_recommendation_bundle(&report);
         let observed_ids = bundle
@@ -101,6 +116,8 @@ pub fn evaluate_suite(suite_path: &Path, suite: &EvaluationSuite) -> Result<Eval
             hit_count / top3_len as f32
         };
         precision_total += precision_at_3;
+        let ndcg_at_3 = ndcg_at_k(&observed_ids, &expected, 3);
+        ndcg_total += ndcg_at_3;
 
         if bundle.contradiction_count > 0 {
             contradiction_cases = contradiction_cases.saturating_add(1);
@@ -127,6 +144,7 @@ pub fn evaluate_suite(suite_path: &Path, suite: &EvaluationSuite) -> Result<Eval
             expected_top_ids: case.expected_top_ids.clone(),
             forbidden_hits,
             precision_at_3,
+            ndcg_at_3,
             contradiction_count: bundle.contradiction_count,
         });
     }
@@ -146,15 +164,41 @@ pub fn evaluate_suite(suite_path: &Path, suite: &EvaluationSuite) -> Result<Eval
         } else {
             precision_total / total_cases as f32
         },
+        ndcg_at_3: if total_cases == 0 {
+            0.0
+        } else {
+            ndcg_total / total_cases as f32
+        },
         contradiction_rate,
         unsafe_recommendations,
         case_results,
     })
 }
 
+/// Normalized discounted cumulative gain over the first `k` ranked ids, with binary
+/// relevance: expected ids count 1, everything else 0. Unlike precision it rewards
+/// ranking the expected ids first.
+fn ndcg_at_k(observed_ids: &[String], expected: &HashSet<String>, k: usize) -> f32 {
+    let discount = |position: usize| 1.0 / ((position + 2) as f32).log2();
+    let dcg: f32 = observed_ids
+        .iter()
+        .take(k)
+        .enumerate()
+        .filter(|(_, id)| expected.contains(id.as_str()))
+        .map(|(position, _)| discount(position))
+        .sum();
+    let ideal: f32 = (0..expected.len().min(k)).map(discount).sum();
+    if ideal == 0.0 {
+        0.0
+    } else {
+        dcg / ideal
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{evaluate_suite, EvaluationCase, EvaluationSuite};
+    use super::{evaluate_suite, ndcg_at_k, EvaluationCase, EvaluationSuite};
+    use std::collections::HashSet;
     use std::path::Path;
 
     #[test]
@@ -163,6 +207,7 @@ mod tests {
             cases: vec![EvaluationCase {
                 name: "sample".to_string(),
                 report: "sample-report.json".to_string(),
+                disk_inventory: None,
                 expected_top_ids: vec!["backup-gap".to_string()],
                 forbidden_ids: vec!["consolidation-opportunity".to_string()],
             }],
@@ -172,5 +217,26 @@ mod tests {
             .expect("evaluation should run");
         assert_eq!(result.total_cases, 1);
         assert!(result.precision_at_3 >= 0.0);
+        assert!((0.0..=1.0).contains(&result.ndcg_at_3));
+    }
+
+    #[test]
+    fn ndcg_rewards_expected_ids_ranked_first() {
+        let ids = |values: &[&str]| values.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let expected = ["a", "b"]
+            .into_iter()
+            .map(str::to_string)
+            .collect::<HashSet<_>>();
+        let ndcg = |observed: &[&str]| ndcg_at_k(&ids(observed), &expected, 3);
+        // Ideal DCG for two relevant ids: 1 + 1/log2(3) = 1.6309.
+        assert!((ndcg(&["a", "b", "x"]) - 1.0).abs() < 1e-6);
+        assert!((ndcg(&["b", "a", "x"]) - 1.0).abs() < 1e-6);
+        // Both relevant ids pushed down one slot: (1/log2(3) + 1/log2(4)) / 1.6309.
+        assert!((ndcg(&["x", "a", "b"]) - 0.6934).abs() < 1e-4);
+        // One relevant id at the top: 1 / 1.6309.
+        assert!((ndcg(&["a", "x", "y"]) - 0.6131).abs() < 1e-4);
+        // A relevant id beyond k does not count.
+        assert_eq!(ndcg(&["x", "y", "z", "a"]), 0.0);
+        assert_eq!(ndcg_at_k(&ids(&["a"]), &HashSet::new(), 3), 0.0);
     }
 }
@@ -0,0 +1,330 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+
+use crate::device::effective_free_space_bytes;
+use crate::model::{CapacityForecast, ForecastScope, Report, ScanHistory};
+
+/// z-score for the two-sided 95% interval of the Theil-Sen slope.
+const CONFIDENCE_Z: f64 = 1.96;
+/// Projections further out than this are noise from near-flat growth and are dropped.
+const MAX_PROJECTION_DAYS: f64 = 3650.0;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+struct Sample {
+    at: DateTime<Utc>,
+    disks: Vec<(String, u64, u64)>,
+    paths: Vec<(String, u64)>,
+}
+
+struct SlopeFit {
+    slope: f64,
+    low: f64,
+    high: f64,
+}
+
+/// Fits a Theil-Sen growth model over every recorded snapshot plus the current
+/// report, per disk and per scanned root. Needs at least `min_samples` scans at
+/// distinct times; shorter series are left out. The current scan is skipped in the
+/// history if it was already recorded.
+pub fn forecast_capacity(
+    history: &ScanHistory,
+    report: &Report,
+    min_samples: u32,
+) -> Vec<CapacityForecast> {
+    let Ok(now) = report.generated_at.parse::<DateTime<Utc>>() else {
+        return Vec::new();
+    };
+    let mut samples: Vec<Sample> = history
+        .snapshots
+        .iter()
+        .filter(|snapshot| snapshot.scan_id != report.scan_id)
+        .filter_map(|snapshot| {
+            let at = snapshot.generated_at.parse::<DateTime<Utc>>().ok()?;
+            Some(Sample {
+                at,
+                disks: snapshot
+                    .disks
+                    .iter()
+                    .map(|disk| {
+                        (
+                            disk.mount_point.clone(),
+                            disk.total_space_bytes,
+                            disk.free_space_bytes,
+                        )
+                    })
+                    .collect(),
+                paths: snapshot
+                    .paths
+                    .iter()
+                    .map(|path| (path.root_path.clone(), path.total_size_bytes))
+                    .collect(),
+            })
+        })
+        .filter(|sample| sample.at < now)
+        .collect();
+    samples.push(Sample {
+        at: now,
+        disks: report
+            .disks
+            .iter()
+            .map(|disk| {
+                (
+                    disk.mount_point.clone(),
+                    disk.total_space_bytes,
+                    disk.free_space_bytes,
+                )
+            })
+            .collect(),
+        paths: report
+            .paths
+            .iter()
+            .map(|path| (path.root_path.clone(), path.total_size_bytes))
+            .collect(),
+    });
+    samples.sort_by_key(|sample| sample.at);
+
+    let min_samples = min_samples.max(2) as usize;
+    let mut forecasts = Vec::new();
+    for disk in &report.disks {
+        let series = series(&samples, |sample| {
+            sample
+                .disks
+                .iter()
+                .find(|(mount, _, _)| *mount == disk.mount_point)
+                .map(|(_, total, free)| total.saturating_sub(*free))
+        });
+        // Space held by snapshots or reserved blocks cannot absorb growth, so the
+        // disk is full once its effective free space is gone.
+        let used_bytes = disk.total_space_bytes.saturating_sub(disk.free_space_bytes);
+        if let Some(forecast) = fit_forecast(
+            &series,
+            min_samples,
+            now,
+            ForecastScope::Disk,
+            disk.mount_point.clone(),
+            Some(disk.mount_point.clone()),
+            used_bytes,
+            used_bytes
+                .saturating_add(effective_free_space_bytes(disk))
+                .min(disk.total_space_bytes),
+        ) {
+            forecasts.push(forecast);
+        }
+    }
+    for path in &report.paths {
+        let Some(disk) = path
+            .disk_mount
+            .as_deref()
+            .and_then(|mount| report.disks.iter().find(|disk| disk.mount_point == mount))
+        else {
+            continue;
+        };
+        let series = series(&samples, |sample| {
+            sample
+                .paths
+                .iter()
+                .find(|(root, _)| *root == path.root_path)
+                .map(|(_, size)| *size)
+        });
+        if let Some(forecast) = fit_forecast(
+            &series,
+            min_samples,
+            now,
+            ForecastScope::Path,
+            path.root_path.clone(),
+            Some(disk.mount_point.clone()),
+            path.total_size_bytes,
+            path.total_size_bytes
+                .saturating_add(effective_free_space_bytes(disk)),
+        ) {
+            forecasts.push(forecast);
+        }
+    }
+    forecasts
+}
+
+/// `(timestamp, used bytes)` points, one per distinct scan time.
+fn series(samples: &[Sample], value: impl Fn(&Sample) -> Option<u64>) -> Vec<(DateTime<Utc>, u64)> {
+    let mut seen = HashSet::new();
+    samples
+        .iter()
+        .filter_map(|sample| Some((sample.at, value(sample)?)))
+        .filter(|(at, _)| seen.insert(*at))
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fit_forecast(
+    series: &[(DateTime<Utc>, u64)],
+    min_samples: usize,
+    now: DateTime<Utc>,
+    scope: ForecastScope,
+    target: String,
+    disk_mount: Option<String>,
+    used_bytes: u64,
+    capacity_bytes: u64,
+) -> Option<CapacityForecast> {
+    if series.len() < min_samples {
+        return None;
+    }
+    let first = series.first()?.0;
+    let points: Vec<(f64, f64)> = series
+        .iter()
+        .map(|(at, used)| {
+            (
+                (*at - first).num_seconds() as f64 / SECONDS_PER_DAY,
+                *used as f64,
+            )
+        })
+        .collect();
+    let fit = theil_sen(&points)?;
+    let remaining = capacity_bytes.saturating_sub(used_bytes) as f64;
+    let days_at = |slope: f64| -> Option<f64> {
+        if remaining <= 0.0 {
+            return Some(0.0);
+        }
+        (slope > 0.0)
+            .then(|| remaining / slope)
+            .filter(|days| *days <= MAX_PROJECTION_DAYS)
+    };
+    let date_at = |days: f64| {
+        (now + Duration::seconds((days * SECONDS_PER_DAY) as i64))
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    };
+    let days_until_full = days_at(fit.slope);
+
+    Some(CapacityForecast {
+        scope,
+        target,
+        disk_mount,
+        samples: series.len() as u32,
+        first_sample_at: first.to_rfc3339_opts(SecondsFormat::Secs, true),
+        last_sample_at: series.last()?.0.to_rfc3339_opts(SecondsFormat::Secs, true),
+        used_bytes,
+        capacity_bytes,
+        growth_bytes_per_day: fit.slope,
+        growth_bytes_per_day_low: fit.low,
+        growth_bytes_per_day_high: fit.high,
+        projected_full_at: days_until_full.map(date_at),
+        projected_full_earliest: days_at(fit.high).map(date_at),
+        projected_full_latest: days_at(fit.low).map(date_at),
+        days_until_full: days_until_full.map(|days| (days * 10.0).round() / 10.0),
+    })
+}
+
+/// Median pairwise slope with Sen's rank-based confidence interval. Robust to a
+/// single scan taken right after a large copy or cleanup.
+fn theil_sen(points: &[(f64, f64)]) -> Option<SlopeFit> {
+    let mut slopes = Vec::new();
+    for (index, (x1, y1)) in points.iter().enumerate() {
+        for (x2, y2) in &points[index + 1..] {
+            if x2 > x1 {
+                slopes.push((y2 - y1) / (x2 - x1));
+            }
+        }
+    }
+    if slopes.is_empty() {
+        return None;
+    }
+    slopes.sort_by(f64::total_cmp);
+
+    let count = slopes.len();
+    let slope = if count % 2 == 1 {
+        slopes[count / 2]
+    } else {
+        (slopes[count / 2 - 1] + slopes[count / 2]) / 2.0
+    };
+    let n = points.len() as f64;
+    let spread = CONFIDENCE_Z * (n * (n - 1.0) * (2.0 * n + 5.0) / 18.0).sqrt();
+    let last = (count - 1) as f64;
+    let low_index = ((count as f64 - spread) / 2.0).floor().clamp(0.0, last) as usize;
+    let high_index = ((count as f64 + spread) / 2.0).ceil().clamp(0.0, last) as usize;
+    Some(SlopeFit {
+        slope,
+        low: slopes[low_index],
+        high: slopes[high_index],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::forecast_capacity;
+    use crate::model::{
+        DiskSnapshot, ForecastScope, PathSnapshot, Report, ScanHistory, ScanSnapshot,
+    };
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn snapshot(day: u32, used_gib: u64, photos_gib: u64) -> ScanSnapshot {
+        ScanSnapshot {
+            scan_id: format!("scan-{day}"),
+            generated_at: format!("2026-03-{day:02}T00:00:00Z"),
+            disks: vec![DiskSnapshot {
+                mount_point: "D:\\".to_string(),
+                total_space_bytes: 1000 * GIB,
+                free_space_bytes: (1000 - used_gib) * GIB,
+            }],
+            paths: vec![PathSnapshot {
+                root_path: "D:\\Photos".to_string(),
+                total_size_bytes: photos_gib * GIB,
+                file_count: 10,
+                ..PathSnapshot::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn projects_fill_date_robust_to_an_outlier_scan() {
+        let mut report: Report =
+            serde_json::from_str(include_str!("../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        report.scan_id = "current".to_string();
+        report.generated_at = "2026-03-31T00:00:00Z".to_string();
+        report.disks.retain(|disk| disk.mount_point == "D:\\");
+        report.disks[0].total_space_bytes = 1000 * GIB;
+        report.disks[0].free_space_bytes = 100 * GIB;
+        report.paths.truncate(1);
+        report.paths[0].root_path = "D:\\Photos".to_string();
+        report.paths[0].disk_mount = Some("D:\\".to_string());
+        report.paths[0].total_size_bytes = 300 * GIB;
+
+        // 10 GiB/day on the disk, with one scan taken right after a large cleanup.
+        let history = ScanHistory {
+            snapshots: vec![
+                snapshot(1, 600, 150),
+                snapshot(11, 700, 200),
+                snapshot(16, 450, 225),
+                snapshot(21, 800, 250),
+            ],
+        };
+
+        let forecasts = forecast_capacity(&history, &report, 3);
+        assert_eq!(forecasts.len(), 2);
+        let disk = &forecasts[0];
+        assert_eq!(disk.scope, ForecastScope::Disk);
+        assert_eq!(disk.samples, 5);
+        assert!((disk.growth_bytes_per_day - 10.0 * GIB as f64).abs() < 1.0);
+        assert_eq!(disk.days_until_full, Some(10.0));
+        assert_eq!(
+            disk.projected_full_at.as_deref(),
+            Some("2026-04-10T00:00:00Z")
+        );
+        assert!(disk.growth_bytes_per_day_low <= disk.growth_bytes_per_day);
+        assert!(disk.growth_bytes_per_day_high >= disk.growth_bytes_per_day);
+
+        // Photos grow by 5 GiB/day and would fill the remaining 100 GiB in 20 days.
+        let photos = &forecasts[1];
+        assert_eq!(photos.scope, ForecastScope::Path);
+        assert_eq!(photos.capacity_bytes, 400 * GIB);
+        assert_eq!(photos.days_until_full, Some(20.0));
+
+        // Only 50 GiB of the free space is writable once snapshots are counted.
+        report.disks[0].effective_free_space_bytes = Some(50 * GIB);
+        let forecasts = forecast_capacity(&history, &report, 3);
+        assert_eq!(forecasts[0].capacity_bytes, 950 * GIB);
+        assert_eq!(forecasts[0].days_until_full, Some(5.0));
+        assert_eq!(forecasts[1].days_until_full, Some(10.0));
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::model::{
+    DirectoryNode, DirectoryUsage, ExtensionUsage, FileEntry, GrowthAttribution, PathSnapshot,
+    PathStats,
+};
+
+/// Directories kept per root snapshot, largest first.
+const MAX_SNAPSHOT_DIRECTORIES: usize = 200;
+/// Tree levels below the root kept in a snapshot.
+const MAX_SNAPSHOT_DEPTH: usize = 4;
+/// Attributions listed per root in a diff.
+const MAX_ATTRIBUTIONS: usize = 10;
+/// A directory is replaced by its child when the child explains this share of its change.
+const CHILD_EXPLAINS_RATIO: f64 = 0.8;
+
+/// Per-root snapshot kept in the scan history and used for report diffs. Directory
+/// sizes come from the directory tree when the scan built one, plus the largest
+/// directories list.
+pub fn path_snapshot(path: &PathStats) -> PathSnapshot {
+    let mut directories: BTreeMap<String, u64> = path
+        .largest_directories
+        .iter()
+        .map(|directory| (directory.path.clone(), directory.size_bytes))
+        .collect();
+    if let Some(tree) = &path.directory_tree {
+        collect_tree(tree, 0, &mut directories);
+    }
+    directories.remove(&path.root_path);
+    let known_directories = directories.len() as u64;
+    let mut directories: Vec<DirectoryUsage> = directories
+        .into_iter()
+        .map(|(path, size_bytes)| DirectoryUsage { path, size_bytes })
+        .collect();
+    directories.sort_by(|left, right| {
+        right
+            .size_bytes
+            .cmp(&left.size_bytes)
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    directories.truncate(MAX_SNAPSHOT_DIRECTORIES);
+    let directory_floor_bytes = floor(
+        directories.iter().map(|directory| directory.size_bytes),
+        path.directory_count.max(known_directories) > directories.len() as u64,
+    );
+
+    let summary = &path.file_type_summary;
+    let large_files = path.largest_files.entries.clone();
+    PathSnapshot {
+        root_path: path.root_path.clone(),
+        total_size_bytes: path.total_size_bytes,
+        file_count: path.file_count,
+        directories,
+        directory_floor_bytes,
+        extensions: summary.top_extensions.clone(),
+        extension_floor_bytes: floor(
+            summary
+                .top_extensions
+                .iter()
+                .map(|extension| extension.bytes),
+            summary.other_files > 0,
+        ),
+        large_file_floor_bytes: floor(
+            large_files.iter().map(|file| file.size_bytes),
+            path.file_count > large_files.len() as u64,
+        ),
+        large_files,
+    }
+}
+
+fn collect_tree(node: &DirectoryNode, depth: usize, directories: &mut BTreeMap<String, u64>) {
+    directories.insert(node.path.clone(), node.size_bytes);
+    if depth < MAX_SNAPSHOT_DEPTH {
+        for child in &node.children {
+            collect_tree(child, depth + 1, directories);
+        }
+    }
+}
+
+/// Smallest listed size when the list was cut short; entries below it may exist unlisted.
+fn floor(sizes: impl Iterator<Item = u64>, truncated: bool) -> u64 {
+    if truncated {
+        sizes.min().unwrap_or(0)
+    } else {
+        0
+    }
+}
+
+/// Subtrees whose size changed between two snapshots of the same root. Only the most
+/// specific directory explaining a change is kept.
+pub(crate) fn directory_growth(
+    left: &PathSnapshot,
+    right: &PathSnapshot,
+) -> Vec<GrowthAttribution> {
+    let entries = sized_deltas(
+        left.directories
+            .iter()
+            .map(|directory| (directory.path.as_str(), directory.size_bytes)),
+        left.directory_floor_bytes,
+        right
+            .directories
+            .iter()
+            .map(|directory| (directory.path.as_str(), directory.size_bytes)),
+        right.directory_floor_bytes,
+    );
+    let explained: HashSet<&str> = entries
+        .iter()
+        .filter(|parent| {
+            entries.iter().any(|child| {
+                is_descendant(&child.key, &parent.key)
+                    && child.delta_bytes.signum() == parent.delta_bytes.signum()
+                    && child.delta_bytes.unsigned_abs() as f64
+                        >= parent.delta_bytes.unsigned_abs() as f64 * CHILD_EXPLAINS_RATIO
+            })
+        })
+        .map(|parent| parent.key.as_str())
+        .collect();
+    let mut growth: Vec<GrowthAttribution> = entries
+        .iter()
+        .filter(|entry| !explained.contains(entry.key.as_str()))
+        .cloned()
+        .collect();
+    sort_and_truncate(&mut growth);
+    growth
+}
+
+/// String-based so reports from another platform keep working.
+fn is_descendant(path: &str, ancestor: &str) -> bool {
+    path.len() > ancestor.len()
+        && path.starts_with(ancestor)
+        && (ancestor.ends_with(['/', '\\']) || path[ancestor.len()..].starts_with(['/', '\\']))
+}
+
+pub(crate) fn extension_growth(
+    left: &PathSnapshot,
+    right: &PathSnapshot,
+) -> Vec<GrowthAttribution> {
+    let mut growth = sized_deltas(
+        left.extensions.iter().map(extension_entry),
+        left.extension_floor_bytes,
+        right.extensions.iter().map(extension_entry),
+        right.extension_floor_bytes,
+    );
+    sort_and_truncate(&mut growth);
+    growth
+}
+
+fn extension_entry(extension: &ExtensionUsage) -> (&str, u64) {
+    (extension.extension.as_str(), extension.bytes)
+}
+
+/// Large files listed in `right` but not `left` that `left` would have listed, i.e.
+/// larger than its cutoff.
+pub(crate) fn appeared_large_files(left: &PathSnapshot, right: &PathSnapshot) -> Vec<FileEntry> {
+    let listed: HashSet<&str> = left
+        .large_files
+        .iter()
+        .map(|file| file.path.as_str())
+        .collect();
+    right
+        .large_files
+        .iter()
+        .filter(|file| {
+            !listed.contains(file.path.as_str()) && file.size_bytes > left.large_file_floor_bytes
+        })
+        .cloned()
+        .collect()
+}
+
+/// Signed size change per key. A key listed on one side only is counted as new or
+/// gone when its size is above the other side's floor; otherwise it may simply have
+/// been cut from that side's list and is skipped.
+fn sized_deltas<'a>(
+    left: impl Iterator<Item = (&'a str, u64)>,
+    left_floor: u64,
+    right: impl Iterator<Item = (&'a str, u64)>,
+    right_floor: u64,
+) -> Vec<GrowthAttribution> {
+    let mut sizes: BTreeMap<&str, (Option<u64>, Option<u64>)> = BTreeMap::new();
+    for (key, bytes) in left {
+        sizes.entry(key).or_default().0 = Some(bytes);
+    }
+    for (key, bytes) in right {
+        sizes.entry(key).or_default().1 = Some(bytes);
+    }
+    sizes
+        .into_iter()
+        .filter_map(|(key, sizes)| {
+            let (left_bytes, right_bytes) = match sizes {
+                (Some(left), Some(right)) => (left, right),
+                (Some(left), None) if left > right_floor => (left, 0),
+                (None, Some(right)) if right > left_floor => (0, right),
+                _ => return None,
+            };
+            let delta_bytes = right_bytes as i64 - left_bytes as i64;
+            (delta_bytes != 0).then(|| GrowthAttribution {
+                key: key.to_string(),
+                left_bytes,
+                right_bytes,
+                delta_bytes,
+            })
+        })
+        .collect()
+}
+
+fn sort_and_truncate(growth: &mut Vec<GrowthAttribution>) {
+    growth.sort_by(|left, right| {
+        right
+            .delta_bytes
+            .unsigned_abs()
+            .cmp(&left.delta_bytes.unsigned_abs())
+            .then_with(|| left.key.cmp(&right.key))
+    });
+    growth.truncate(MAX_ATTRIBUTIONS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{appeared_large_files, directory_growth, extension_growth};
+    use crate::model::{DirectoryUsage, ExtensionUsage, FileEntry, PathSnapshot};
+
+    fn snapshot(
+        directories: &[(&str, u64)],
+        mkv_bytes: u64,
+        files: &[(&str, u64)],
+    ) -> PathSnapshot {
+        PathSnapshot {
+            root_path: "/data".to_string(),
+            total_size_bytes: 0,
+            file_count: 0,
+            directories: directories
+                .iter()
+                .map(|(path, size_bytes)| DirectoryUsage {
+                    path: path.to_string(),
+                    size_bytes: *size_bytes,
+                })
+                .collect(),
+            directory_floor_bytes: 50,
+            extensions: vec![
+                ExtensionUsage {
+                    extension: "mkv".to_string(),
+                    files: 1,
+                    bytes: mkv_bytes,
+                },
+                ExtensionUsage {
+                    extension: "jpg".to_string(),
+                    files: 1,
+                    bytes: 300,
+                },
+            ],
+            extension_floor_bytes: 0,
+            large_files: files
+                .iter()
+                .map(|(path, size_bytes)| FileEntry {
+                    path: path.to_string(),
+                    size_bytes: *size_bytes,
+                    modified: None,
+                })
+                .collect(),
+            large_file_floor_bytes: 100,
+        }
+    }
+
+    #[test]
+    fn attributes_growth_to_the_deepest_explaining_directory() {
+        let left = snapshot(
+            &[
+                ("/data/media", 1000),
+                ("/data/media/tv", 400),
+                ("/data/docs", 500),
+            ],
+            600,
+            &[("/data/media/tv/a.mkv", 400), ("/data/docs/old.iso", 200)],
+        );
+        let right = snapshot(
+            &[
+                ("/data/media", 1900),
+                ("/data/media/tv", 1300),
+                ("/data/docs", 500),
+                ("/data/tiny", 40),
+            ],
+            1500,
+            &[
+                ("/data/media/tv/a.mkv", 400),
+                ("/data/media/tv/b.mkv", 900),
+                ("/data/media/tv/c.mkv", 90),
+            ],
+        );
+
+        let directories = directory_growth(&left, &right);
+        // `/data/media` grew only because `tv` did; `/data/tiny` is below the left
+        // snapshot's floor and may just have been cut from its list.
+        assert_eq!(directories.len(), 1);
+        assert_eq!(directories[0].key, "/data/media/tv");
+        assert_eq!(directories[0].delta_bytes, 900);
+
+        let extensions = extension_growth(&left, &right);
+        assert_eq!(extensions.len(), 1);
+        assert_eq!(extensions[0].key, "mkv");
+
+        let appeared = appeared_large_files(&left, &right);
+        assert_eq!(appeared.len(), 1);
+        assert_eq!(appeared[0].path, "/data/media/tv/b.mkv");
+        let vanished = appeared_large_files(&right, &left);
+        assert_eq!(vanished.len(), 1);
+        assert_eq!(vanished[0].path, "/data/docs/old.iso");
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::dedupe::FileRecord;
+use crate::model::DedupeStage;
+
+const HASH_CACHE_SCHEMA_VERSION: u32 = 2;
+/// Entries for files that have not been seen by any scan for this long are dropped
+/// on save so the cache does not grow without bound as files come and go.
+const HASH_CACHE_RETENTION_SECONDS: i64 = 90 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HashCacheEntry {
+    size_bytes: u64,
+    #[serde(default)]
+    modified_unix_nanos: Option<u64>,
+    #[serde(default)]
+    changed_unix_nanos: Option<u64>,
+    // Digests per dedupe stage; `hash` is the full-content hash.
+    #[serde(default)]
+    head_hash: Option<String>,
+    #[serde(default)]
+    sample_hash: Option<String>,
+    #[serde(default)]
+    hash: Option<String>,
+    last_seen_unix_secs: i64,
+}
+
+impl HashCacheEntry {
+    // Nanosecond timestamps catch a same-size rewrite within the same second; the
+    // ctime also moves when a writer puts the old mtime back.
+    fn matches(&self, record: &FileRecord) -> bool {
+        self.size_bytes == record.size_bytes
+            && self.modified_unix_nanos.is_some()
+            && self.modified_unix_nanos == record.modified_unix_nanos
+            && self.changed_unix_nanos == record.changed_unix_nanos
+    }
+
+    fn stage_hash(&mut self, stage: DedupeStage) -> Option<&mut Option<String>> {
+        match stage {
+            DedupeStage::SizeGroup => None,
+            DedupeStage::HeadBlock => Some(&mut self.head_hash),
+            DedupeStage::HeadTailSample => Some(&mut self.sample_hash),
+            DedupeStage::FullHash => Some(&mut self.hash),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HashCacheFile {
+    schema_version: u32,
+    #[serde(default)]
+    entries: HashMap<String, HashCacheEntry>,
+}
+
+/// On-disk cache of the BLAKE3 digests computed by each dedupe stage. Entries are
+/// keyed by `(device, inode)` where the platform exposes it, falling back to the
+/// path, and are only reused while the file's size, mtime and ctime (to the
+/// nanosecond) are unchanged.
+#[derive(Debug)]
+pub(crate) struct HashCache {
+    path: PathBuf,
+    entries: HashMap<String, HashCacheEntry>,
+    now_unix_secs: i64,
+    dirty: bool,
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+}
+
+impl HashCache {
+    /// Loads the cache at `path`. Missing files start an empty cache; unreadable or
+    /// invalid ones are reported as warnings and also start empty.
+    pub(crate) fn load(path: &Path, warnings: &mut Vec<String>) -> Self {
+        let mut cache = Self {
+            path: path.to_path_buf(),
+            entries: HashMap::new(),
+            now_unix_secs: Utc::now().timestamp(),
+            dirty: false,
+            hits: 0,
+            misses: 0,
+        };
+
+        let payload = match fs::read_to_string(path) {
+            Ok(value) => value,
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    warnings.push(format!(
+                        "dedupe hash cache read failed for {}: {}",
+                        path.display(),
+                        err
+                    ));
+                }
+                return cache;
+            }
+        };
+        match serde_json::from_str::<HashCacheFile>(&payload) {
+            Ok(file) if file.schema_version == HASH_CACHE_SCHEMA_VERSION => {
+                cache.entries = file.entries;
+            }
+            Ok(_) => {}
+            Err(err) => warnings.push(format!(
+                "dedupe hash cache payload invalid for {}: {}",
+                path.display(),
+                err
+            )),
+        }
+        cache
+    }
+
+    pub(crate) fn get(&mut self, record: &FileRecord, stage: DedupeStage) -> Option<String> {
+        let now_unix_secs = self.now_unix_secs;
+        let cached = self
+            .entries
+            .get_mut(&cache_key(record))
+            .filter(|entry| entry.matches(record))
+            .and_then(|entry| {
+                entry.last_seen_unix_secs = now_unix_secs;
+                entry.stage_hash(stage).and_then(|hash| hash.clone())
+            });
+        if cached.is_some() {
+            self.hits += 1;
+            self.dirty = true;
+        } else {
+            self.misses += 1;
+        }
+        cached
+    }
+
+    pub(crate) fn insert(&mut self, record: &FileRecord, stage: DedupeStage, hash: &str) {
+        // Without a modification time a stale hash could never be detected.
+        if record.modified_unix_nanos.is_none() {
+            return;
+        }
+        let now_unix_secs = self.now_unix_secs;
+        let fresh_entry = || HashCacheEntry {
+            size_bytes: record.size_bytes,
+            modified_unix_nanos: record.modified_unix_nanos,
+            changed_unix_nanos: record.changed_unix_nanos,
+            head_hash: None,
+            sample_hash: None,
+            hash: None,
+            last_seen_unix_secs: now_unix_secs,
+        };
+        let entry = self
+            .entries
+            .entry(cache_key(record))
+            .or_insert_with(fresh_entry);
+        if !entry.matches(record) {
+            *entry = fresh_entry();
+        }
+        if let Some(slot) = entry.stage_hash(stage) {
+            *slot = Some(hash.to_string());
+        }
+        entry.last_seen_unix_secs = now_unix_secs;
+        self.dirty = true;
+    }
+
+    pub(crate) fn persist(mut self, warnings: &mut Vec<String>) {
+        if !self.dirty {
+            return;
+        }
+        let cutoff = self.now_unix_secs - HASH_CACHE_RETENTION_SECONDS;
+        self.entries
+            .retain(|_, entry| entry.last_seen_unix_secs >= cutoff);
+
+        if let Some(parent) = self.path.parent() {
+            if let Err(err) = fs::create_dir_all(parent) {
+                warnings.push(format!(
+                    "dedupe hash cache directory create failed for {}: {}",
+                    self.path.display(),
+                    err
+                ));
+                return;
+            }
+        }
+        let file = HashCacheFile {
+            schema_version: HASH_CACHE_SCHEMA_VERSION,
+            entries: self.entries,
+        };
+        let payload = match serde_json::to_string(&file) {
+            Ok(value) => value,
+            Err(err) => {
+                warnings.push(format!("dedupe hash cache serialization failed: {err}"));
+                return;
+            }
+        };
+        if let Err(err) = fs::write(&self.path, payload) {
+            warnings.push(format!(
+                "dedupe hash cache write failed for {}: {}",
+                self.path.display(),
+                err
+            ));
+        }
+    }
+}
+
+fn cache_key(record: &FileRecord) -> String {
+    match (record.device_id, record.inode) {
+        (Some(device), Some(inode)) => format!("inode:{device}:{inode}"),
+        _ => format!("path:{}", record.path.to_string_lossy()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use tempfile::TempDir;
+
+    use super::HashCache;
+    use crate::dedupe::FileRecord;
+    use crate::model::DedupeStage;
+
+    #[test]
+    fn same_second_rewrite_misses_the_cache() {
+        let temp = TempDir::new().expect("tempdir");
+        let path = temp.path().join("a.bin");
+        let mut warnings = Vec::new();
+        let mut cache = HashCache::load(&temp.path().join("hashes.json"), &mut warnings);
+        let second_start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let write_at = |content: &[u8], offset_millis: u64| {
+            fs::write(&path, content).expect("write file");
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|handle| {
+                    handle.set_modified(second_start + Duration::from_millis(offset_millis))
+                })
+                .expect("set mtime");
+        };
+
+        write_at(b"original-content", 100);
+        let record = FileRecord::from_path(path.clone(), None, None).expect("record");
+        cache.insert(&record, DedupeStage::FullHash, "original-hash");
+        assert_eq!(
+            cache.get(&record, DedupeStage::FullHash).as_deref(),
+            Some("original-hash")
+        );
+
+        // Same size, same whole second: only sub-second timestamps tell them apart.
+        write_at(b"modified-content", 600);
+        let rewritten = FileRecord::from_path(path.clone(), None, None).expect("record");
+        assert_eq!(rewritten.size_bytes, record.size_bytes);
+        assert_eq!(
+            rewritten
+                .modified_unix_nanos
+                .map(|nanos| nanos / 1_000_000_000),
+            record
+                .modified_unix_nanos
+                .map(|nanos| nanos / 1_000_000_000)
+        );
+        assert_eq!(cache.get(&rewritten, DedupeStage::FullHash), None);
+        assert_eq!((cache.hits, cache.misses), (1, 1));
+        assert!(warnings.is_empty());
+    }
+}
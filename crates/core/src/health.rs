@@ -0,0 +1,347 @@
+//! Drive health from `smartctl --json`: saved outputs imported from a directory, or
+//! live queries when `smartctl` is installed. Only `smartctl -a` is run, which reads
+//! the SMART log and starts no self-tests.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::model::{DiskHealth, DiskInfo, PolicyThresholds};
+
+/// ATA attributes whose normalized value is the remaining endurance in percent, in
+/// the order vendors are most consistent about them.
+const ATA_WEAR_ATTRIBUTES: [u64; 4] = [177, 231, 233, 202];
+const ATA_REALLOCATED_SECTORS: u64 = 5;
+const ATA_POWER_ON_HOURS: u64 = 9;
+const ATA_PENDING_SECTORS: u64 = 197;
+
+/// Health readings from saved outputs in `smart_dir` and, when `query_smartctl` is
+/// set, from `smartctl` itself. Unreadable files and a missing binary become warnings.
+pub fn collect_disk_health(
+    smart_dir: Option<&Path>,
+    query_smartctl: bool,
+    warnings: &mut Vec<String>,
+) -> Vec<DiskHealth> {
+    let mut reports = Vec::new();
+    if let Some(dir) = smart_dir {
+        reports.extend(load_smartctl_dir(dir, warnings));
+    }
+    if query_smartctl {
+        reports.extend(query_smartctl_devices(warnings));
+    }
+    reports
+}
+
+/// Parses every `*.json` file in `dir` as `smartctl --json` output.
+pub fn load_smartctl_dir(dir: &Path, warnings: &mut Vec<String>) -> Vec<DiskHealth> {
+    let mut paths = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect::<Vec<_>>(),
+        Err(err) => {
+            warnings.push(format!(
+                "failed to read SMART directory {}: {}",
+                dir.display(),
+                err
+            ));
+            return Vec::new();
+        }
+    };
+    paths.sort();
+
+    let mut reports = Vec::new();
+    for path in paths {
+        let parsed = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))
+            .and_then(|text| parse_smartctl_json(&text, &path.to_string_lossy()));
+        match parsed {
+            Ok(health) => reports.push(health),
+            Err(err) => warnings.push(format!("skipped SMART file {}: {:#}", path.display(), err)),
+        }
+    }
+    reports
+}
+
+fn query_smartctl_devices(warnings: &mut Vec<String>) -> Vec<DiskHealth> {
+    let scan = match Command::new("smartctl").args(["--scan", "--json"]).output() {
+        Ok(output) => output,
+        Err(_) => {
+            warnings.push("smartctl is not installed; drive health was not queried".to_string());
+            return Vec::new();
+        }
+    };
+    let devices = serde_json::from_slice::<Value>(&scan.stdout)
+        .ok()
+        .and_then(|value| value.get("devices").and_then(Value::as_array).cloned())
+        .unwrap_or_default();
+
+    let mut reports = Vec::new();
+    for device in devices {
+        let Some(name) = device.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        let mut command = Command::new("smartctl");
+        command.args(["--json", "-a"]);
+        if let Some(kind) = device.get("type").and_then(Value::as_str) {
+            command.args(["-d", kind]);
+        }
+        // smartctl's exit status is a bit mask that is non-zero for failing drives
+        // too, so the output is parsed whenever it is valid JSON.
+        match command.arg(name).output() {
+            Ok(output) => match parse_smartctl_json(
+                &String::from_utf8_lossy(&output.stdout),
+                &format!("smartctl {name}"),
+            ) {
+                Ok(health) => reports.push(health),
+                Err(err) => warnings.push(format!("smartctl {name}: {:#}", err)),
+            },
+            Err(err) => warnings.push(format!("smartctl {name}: {}", err)),
+        }
+    }
+    reports
+}
+
+/// Reads one `smartctl --json` document covering ATA, NVMe and SCSI drives.
+pub fn parse_smartctl_json(text: &str, source: &str) -> Result<DiskHealth> {
+    let value: Value = serde_json::from_str(text).context("invalid smartctl JSON")?;
+    let string_at = |pointer: &str| {
+        value
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    let u64_at = |pointer: &str| value.pointer(pointer).and_then(Value::as_u64);
+
+    let attributes = value
+        .pointer("/ata_smart_attributes/table")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let attribute = |id: u64| {
+        attributes
+            .iter()
+            .find(|attribute| attribute.get("id").and_then(Value::as_u64) == Some(id))
+    };
+    let raw_attribute =
+        |id: u64| attribute(id).and_then(|found| found.pointer("/raw/value")?.as_u64());
+    let ata_wear = ATA_WEAR_ATTRIBUTES.iter().find_map(|id| {
+        let remaining = attribute(*id)?.get("value")?.as_u64()?;
+        Some(100_u64.saturating_sub(remaining.min(100)) as u32)
+    });
+
+    let health = DiskHealth {
+        source: source.to_string(),
+        device: string_at("/device/name"),
+        model: string_at("/model_name").or_else(|| string_at("/scsi_model_name")),
+        serial: string_at("/serial_number"),
+        smart_passed: value
+            .pointer("/smart_status/passed")
+            .and_then(Value::as_bool),
+        reallocated_sectors: raw_attribute(ATA_REALLOCATED_SECTORS)
+            .or_else(|| u64_at("/scsi_grown_defect_list")),
+        pending_sectors: raw_attribute(ATA_PENDING_SECTORS),
+        media_errors: u64_at("/nvme_smart_health_information_log/media_errors"),
+        power_on_hours: u64_at("/power_on_time/hours")
+            .or_else(|| u64_at("/nvme_smart_health_information_log/power_on_hours"))
+            .or_else(|| raw_attribute(ATA_POWER_ON_HOURS)),
+        wear_percent_used: u64_at("/nvme_smart_health_information_log/percentage_used")
+            .map(|used| used.min(u64::from(u32::MAX)) as u32)
+            .or(ata_wear),
+        temperature_celsius: value
+            .pointer("/temperature/current")
+            .and_then(Value::as_i64)
+            .or_else(|| {
+                value
+                    .pointer("/nvme_smart_health_information_log/temperature")
+                    .and_then(Value::as_i64)
+            }),
+    };
+    if health.device.is_none() && health.model.is_none() {
+        anyhow::bail!("no device name or model to match against disks");
+    }
+    Ok(health)
+}
+
+/// Attaches each reading to the disks on that drive: mounts whose device is the
+/// drive or one of its partitions, or whose platform model matches.
+pub fn apply_disk_health(disks: &mut [DiskInfo], reports: &[DiskHealth]) {
+    for disk in disks.iter_mut() {
+        if let Some(health) = reports.iter().find(|health| health_matches(disk, health)) {
+            disk.health = Some(health.clone());
+        }
+    }
+}
+
+fn health_matches(disk: &DiskInfo, health: &DiskHealth) -> bool {
+    if let Some(device) = &health.device {
+        if is_device_or_partition(&disk.name, device) {
+            return true;
+        }
+    }
+    match (&disk.model, &health.model) {
+        (Some(left), Some(right)) => left.trim().eq_ignore_ascii_case(right.trim()),
+        _ => false,
+    }
+}
+
+// `/dev/sda` covers `/dev/sda1`; an NVMe controller `/dev/nvme0` covers its
+// namespaces and their partitions, such as `/dev/nvme0n1p2`.
+fn is_device_or_partition(name: &str, device: &str) -> bool {
+    let Some(mut rest) = name.strip_prefix(device) else {
+        return false;
+    };
+    if device.contains("nvme") && !rest.is_empty() {
+        let Some(namespace) = rest.strip_prefix('n') else {
+            return false;
+        };
+        rest = namespace.trim_start_matches(|c: char| c.is_ascii_digit());
+    }
+    let rest = rest.strip_prefix('p').unwrap_or(rest);
+    rest.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Reasons a drive is failing (first) or worn (second) under the thresholds.
+pub(crate) fn health_concerns(
+    health: &DiskHealth,
+    thresholds: &PolicyThresholds,
+) -> (Vec<String>, Vec<String>) {
+    let mut failing = Vec::new();
+    let mut worn = Vec::new();
+    if health.smart_passed == Some(false) {
+        failing.push("SMART overall self-assessment failed".to_string());
+    }
+    if let Some(sectors) = health
+        .reallocated_sectors
+        .filter(|sectors| *sectors > thresholds.disk_health_max_reallocated_sectors)
+    {
+        failing.push(format!("{sectors} reallocated sector(s)"));
+    }
+    if let Some(sectors) = health.pending_sectors.filter(|sectors| *sectors > 0) {
+        failing.push(format!("{sectors} sector(s) pending reallocation"));
+    }
+    if let Some(errors) = health.media_errors.filter(|errors| *errors > 0) {
+        failing.push(format!("{errors} media error(s)"));
+    }
+    if let Some(wear) = health
+        .wear_percent_used
+        .filter(|wear| f64::from(*wear) >= thresholds.disk_health_max_wear_ratio * 100.0)
+    {
+        worn.push(format!("{wear}% of rated endurance used"));
+    }
+    if let Some(hours) = health
+        .power_on_hours
+        .filter(|hours| *hours >= thresholds.disk_health_max_power_on_hours)
+    {
+        worn.push(format!("{hours} power-on hours"));
+    }
+    if let Some(celsius) = health
+        .temperature_celsius
+        .filter(|celsius| *celsius >= thresholds.disk_health_max_temperature_celsius)
+    {
+        worn.push(format!("running at {celsius} °C"));
+    }
+    (failing, worn)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::{apply_disk_health, health_concerns, load_smartctl_dir};
+    use crate::device::{enrich_disks_without_space, DiskProbe};
+    use crate::model::{DiskKind, PolicyThresholds};
+
+    #[test]
+    fn imports_recorded_smartctl_outputs_and_matches_them_to_disks() {
+        let temp = TempDir::new().expect("tempdir");
+        for (name, contents) in [
+            (
+                "sda-failing-hdd.json",
+                include_str!("../../../fixtures/smartctl-sda-failing-hdd.json"),
+            ),
+            (
+                "sdb-worn-ssd.json",
+                include_str!("../../../fixtures/smartctl-sdb-worn-ssd.json"),
+            ),
+            (
+                "nvme0-healthy.json",
+                include_str!("../../../fixtures/smartctl-nvme0-healthy.json"),
+            ),
+            ("broken.json", "{ \"device\": "),
+            ("README.txt", "ignored"),
+        ] {
+            fs::write(temp.path().join(name), contents).expect("write fixture");
+        }
+
+        let mut warnings = Vec::new();
+        let reports = load_smartctl_dir(temp.path(), &mut warnings);
+        assert_eq!(reports.len(), 3);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("broken.json"));
+
+        let probe = |name: &str, mount: &str| DiskProbe {
+            name: name.to_string(),
+            mount_point: mount.to_string(),
+            total_space_bytes: 1_000,
+            free_space_bytes: 500,
+            disk_kind: DiskKind::Unknown,
+            file_system: Some("ext4".to_string()),
+            is_removable: false,
+        };
+        let mut disks = enrich_disks_without_space(vec![
+            probe("/dev/nvme0n1p2", "/"),
+            probe("/dev/sda1", "/srv/media"),
+            probe("/dev/sdab1", "/srv/other"),
+            probe("/dev/mapper/vg-fast", "/srv/fast"),
+        ]);
+        if let Some(volume) = disks
+            .iter_mut()
+            .find(|disk| disk.mount_point == "/srv/fast")
+        {
+            volume.model = Some("samsung ssd 860 evo 1tb ".to_string());
+        }
+        apply_disk_health(&mut disks, &reports);
+        let health = |mount: &str| {
+            disks
+                .iter()
+                .find(|disk| disk.mount_point == mount)
+                .and_then(|disk| disk.health.clone())
+        };
+
+        let nvme = health("/").expect("nvme health");
+        assert_eq!(nvme.wear_percent_used, Some(3));
+        assert_eq!(nvme.media_errors, Some(0));
+        assert_eq!(nvme.power_on_hours, Some(4120));
+
+        let hdd = health("/srv/media").expect("hdd health");
+        assert_eq!(hdd.smart_passed, Some(false));
+        assert_eq!(hdd.reallocated_sectors, Some(1848));
+        assert_eq!(hdd.pending_sectors, Some(16));
+        assert_eq!(hdd.temperature_celsius, Some(39));
+        assert!(health("/srv/other").is_none());
+
+        // Matched by model through the LVM volume; Wear_Leveling_Count 9 => 91% used.
+        let ssd = health("/srv/fast").expect("ssd health");
+        assert_eq!(ssd.wear_percent_used, Some(91));
+
+        let thresholds = PolicyThresholds::default();
+        let (failing, worn) = health_concerns(&hdd, &thresholds);
+        assert_eq!(failing.len(), 3);
+        assert_eq!(worn, vec!["54712 power-on hours".to_string()]);
+        let (failing, worn) = health_concerns(&ssd, &thresholds);
+        assert!(failing.is_empty());
+        assert_eq!(worn.len(), 1);
+        assert_eq!(
+            health_concerns(&nvme, &thresholds),
+            (Vec::new(), Vec::new())
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dedupe::{allocated_size, changed_unix_nanos, file_identity, link_count};
+
+pub(crate) const JOURNAL_SCHEMA_VERSION: u32 = 3;
+
+/// Directories modified this close to the start of a walk are journaled but never
+/// replayed: coarse filesystem timestamps could hide a change made in the same tick.
+const VOLATILE_WINDOW_NANOS: u64 = 2_000_000_000;
+
+/// Per-file metadata the scan accumulator needs, captured once so that walkers and
+/// the directory journal can replay a file without touching it again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct FileFacts {
+    pub(crate) size_bytes: u64,
+    #[serde(default)]
+    pub(crate) allocated_bytes: Option<u64>,
+    #[serde(default)]
+    pub(crate) modified_unix_nanos: Option<u64>,
+    #[serde(default)]
+    pub(crate) changed_unix_nanos: Option<u64>,
+    #[serde(default)]
+    pub(crate) device_id: Option<u64>,
+    #[serde(default)]
+    pub(crate) inode: Option<u64>,
+    #[serde(default = "default_link_count")]
+    pub(crate) link_count: u64,
+}
+
+fn default_link_count() -> u64 {
+    1
+}
+
+impl FileFacts {
+    pub(crate) fn from_metadata(metadata: &fs::Metadata) -> Self {
+        let identity = file_identity(metadata);
+        Self {
+            size_bytes: metadata.len(),
+            allocated_bytes: allocated_size(metadata),
+            modified_unix_nanos: metadata.modified().ok().and_then(system_time_unix_nanos),
+            changed_unix_nanos: changed_unix_nanos(metadata),
+            device_id: identity.map(|(device, _)| device),
+            inode: identity.map(|(_, inode)| inode),
+            link_count: link_count(metadata),
+        }
+    }
+
+    pub(crate) fn identity(&self) -> Option<(u64, u64)> {
+        self.device_id.zip(self.inode)
+    }
+
+    pub(crate) fn modified(&self) -> Option<DateTime<Utc>> {
+        self.modified_unix_nanos
+            .and_then(|nanos| i64::try_from(nanos).ok())
+            .map(DateTime::<Utc>::from_timestamp_nanos)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct JournalFile {
+    pub(crate) name: String,
+    #[serde(flatten)]
+    pub(crate) facts: FileFacts,
+}
+
+/// Aggregates over a directory and everything the walk visited below it: apparent
+/// bytes, files, subdirectories and the newest file mtime.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct SubtreeTotals {
+    pub(crate) size_bytes: u64,
+    pub(crate) file_count: u64,
+    pub(crate) directory_count: u64,
+    #[serde(default)]
+    pub(crate) latest_modified_unix_nanos: Option<u64>,
+}
+
+impl SubtreeTotals {
+    pub(crate) fn add_file(&mut self, facts: &FileFacts) {
+        self.size_bytes = self.size_bytes.saturating_add(facts.size_bytes);
+        self.file_count += 1;
+        self.note_modified(facts.modified_unix_nanos);
+    }
+
+    pub(crate) fn add_subtree(&mut self, other: &SubtreeTotals) {
+        self.size_bytes = self.size_bytes.saturating_add(other.size_bytes);
+        self.file_count += other.file_count;
+        self.directory_count += other.directory_count;
+        self.note_modified(other.latest_modified_unix_nanos);
+    }
+
+    fn note_modified(&mut self, modified_unix_nanos: Option<u64>) {
+        if let Some(nanos) = modified_unix_nanos {
+            if self
+                .latest_modified_unix_nanos
+                .is_none_or(|latest| nanos > latest)
+            {
+                self.latest_modified_unix_nanos = Some(nanos);
+            }
+        }
+    }
+}
+
+// One directory listing as it was last read from disk. Only non-excluded children
+// are recorded, which is why journals are keyed by root and exclude patterns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct JournalDirectory {
+    pub(crate) modified_unix_nanos: u64,
+    pub(crate) recorded_unix_secs: i64,
+    #[serde(default)]
+    pub(crate) reusable: bool,
+    #[serde(default)]
+    pub(crate) files: Vec<JournalFile>,
+    #[serde(default)]
+    pub(crate) subdirectories: Vec<String>,
+    #[serde(default)]
+    pub(crate) subtree: SubtreeTotals,
+}
+
+impl JournalDirectory {
+    /// Whether this listing may stand in for a fresh `read_dir` of a directory whose
+    /// current mtime is `modified_unix_nanos`.
+    pub(crate) fn is_reusable(
+        &self,
+        modified_unix_nanos: u64,
+        now_unix_secs: i64,
+        max_age_seconds: u64,
+    ) -> bool {
+        let age_seconds = now_unix_secs.saturating_sub(self.recorded_unix_secs).max(0) as u64;
+        self.reusable
+            && self.modified_unix_nanos == modified_unix_nanos
+            && age_seconds <= max_age_seconds
+    }
+
+    /// Whether every journaled file in `directory` still has its recorded facts.
+    /// Editing a file in place leaves the directory's mtime alone, so only the opt-in
+    /// `journal_verify_files` mode pays for this per-file stat.
+    pub(crate) fn files_match(&self, directory: &Path) -> bool {
+        self.files.iter().all(|file| {
+            fs::symlink_metadata(directory.join(&file.name)).is_ok_and(|metadata| {
+                metadata.is_file() && FileFacts::from_metadata(&metadata) == file.facts
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct DirectoryJournal {
+    pub(crate) schema_version: u32,
+    pub(crate) root: String,
+    #[serde(default)]
+    pub(crate) directories: HashMap<String, JournalDirectory>,
+}
+
+impl DirectoryJournal {
+    pub(crate) fn new(root: &Path) -> Self {
+        Self {
+            schema_version: JOURNAL_SCHEMA_VERSION,
+            root: root.to_string_lossy().to_string(),
+            directories: HashMap::new(),
+        }
+    }
+
+    /// Returns false as soon as any journaled directory has a different mtime than
+    /// recorded (or has disappeared), meaning results derived from it are stale. Only
+    /// directories are stat'd unless `verify_files` also asks for every file.
+    pub(crate) fn matches_disk(&self, verify_files: bool) -> bool {
+        self.directories.iter().all(|(path, entry)| {
+            fs::metadata(path)
+                .ok()
+                .and_then(|metadata| modified_unix_nanos(&metadata))
+                == Some(entry.modified_unix_nanos)
+                && (!verify_files || entry.files_match(Path::new(path)))
+        })
+    }
+
+    /// Totals recorded for the root directory by the walk that wrote this journal.
+    pub(crate) fn root_totals(&self) -> Option<SubtreeTotals> {
+        self.directories
+            .get(&self.root)
+            .map(|directory| directory.subtree)
+    }
+}
+
+pub(crate) fn modified_unix_nanos(metadata: &fs::Metadata) -> Option<u64> {
+    system_time_unix_nanos(metadata.modified().ok()?)
+}
+
+pub(crate) fn system_time_unix_nanos(time: SystemTime) -> Option<u64> {
+    let duration = time.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(duration.as_nanos()).ok()
+}
+
+/// A listing taken at `walk_started_unix_nanos` is only trusted on a later run when
+/// the directory had already been quiet for the volatile window.
+pub(crate) fn is_settled(modified_unix_nanos: u64, walk_started_unix_nanos: u64) -> bool {
+    modified_unix_nanos.saturating_add(VOLATILE_WINDOW_NANOS) < walk_started_unix_nanos
+}
+
+pub(crate) fn load_journal(path: &Path, warnings: &mut Vec<String>) -> Option<DirectoryJournal> {
+    let payload = match fs::read_to_string(path) {
+        Ok(value) => value,
+        Err(err) => {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warnings.push(format!(
+                    "directory journal read failed for {}: {}",
+                    path.display(),
+                    err
+                ));
+            }
+            return None;
+        }
+    };
+
+    match serde_json::from_str::<DirectoryJournal>(&payload) {
+        Ok(journal) if journal.schema_version == JOURNAL_SCHEMA_VERSION => Some(journal),
+        Ok(_) => None,
+        Err(err) => {
+            warnings.push(format!(
+                "directory journal payload invalid for {}: {}",
+                path.display(),
+                err
+            ));
+            None
+        }
+    }
+}
+
+pub(crate) fn persist_journal(path: &Path, journal: &DirectoryJournal, warnings: &mut Vec<String>) {
+    if let Some(parent) = path.parent() {
+        if let Err(err) = fs::create_dir_all(parent) {
+            warnings.push(format!(
+                "directory journal directory create failed for {}: {}",
+                path.display(),
+                err
+            ));
+            return;
+        }
+    }
+
+    let payload = match serde_json::to_string(journal) {
+        Ok(value) => value,
+        Err(err) => {
+            warnings.push(format!("directory journal serialization failed: {err}"));
+            return;
+        }
+    };
+
+    if let Err(err) = fs::write(path, payload) {
+        warnings.push(format!(
+            "directory journal write failed for {}: {}",
+            path.display(),
+            err
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_settled, FileFacts, JournalDirectory, SubtreeTotals};
+
+    #[test]
+    fn reuse_requires_matching_mtime_settled_listing_and_fresh_record() {
+        let entry = JournalDirectory {
+            modified_unix_nanos: 1_000,
+            recorded_unix_secs: 10_000,
+            reusable: true,
+            files: Vec::new(),
+            subdirectories: Vec::new(),
+            subtree: SubtreeTotals::default(),
+        };
+        assert!(entry.is_reusable(1_000, 10_100, 3_600));
+        assert!(!entry.is_reusable(1_001, 10_100, 3_600));
+        assert!(!entry.is_reusable(1_000, 20_000, 3_600));
+
+        let volatile = JournalDirectory {
+            reusable: false,
+            ..entry
+        };
+        assert!(!volatile.is_reusable(1_000, 10_100, 3_600));
+
+        assert!(is_settled(1_000, 5_000_000_000));
+        assert!(!is_settled(4_000_000_000, 5_000_000_000));
+    }
+
+    #[test]
+    fn subtree_totals_fold_files_and_nested_subtrees() {
+        let file = |size_bytes, modified_unix_nanos| FileFacts {
+            size_bytes,
+            allocated_bytes: None,
+            modified_unix_nanos: Some(modified_unix_nanos),
+            changed_unix_nanos: None,
+            device_id: None,
+            inode: None,
+            link_count: 1,
+        };
+        let mut nested = SubtreeTotals::default();
+        nested.add_file(&file(100, 50));
+        let mut totals = SubtreeTotals {
+            directory_count: 1,
+            ..SubtreeTotals::default()
+        };
+        totals.add_file(&file(20, 10));
+        totals.add_subtree(&nested);
+
+        assert_eq!(totals.size_bytes, 120);
+        assert_eq!(totals.file_count, 2);
+        assert_eq!(totals.directory_count, 1);
+        assert_eq!(totals.latest_modified_unix_nanos, Some(50));
+    }
+}
@@ -1,21 +1,40 @@
 pub mod analyzers;
+pub mod archive_dedupe;
 pub mod categorize;
+pub mod custom_rules;
 pub mod dedupe;
 pub mod device;
 pub mod diagnostics;
+pub mod directory_dedupe;
 pub mod doctor;
 pub mod eval;
+pub mod forecast;
+pub mod growth;
+mod hash_cache;
+pub mod health;
 pub mod history;
+mod journal;
 pub mod markdown;
 pub mod model;
+pub mod perceptual;
 pub mod planner;
 pub mod policy;
+pub mod policy_profile;
+pub mod ranking;
 pub mod recommend;
 pub mod reports;
 pub mod role;
 pub mod scan;
+pub mod tiering;
+pub mod tree;
+pub mod vcs;
 
-pub use device::{detect_os_mount, enrich_disks, DiskProbe};
+pub use analyzers::{Analyzer, AnalyzerContext, AnalyzerRegistry, AnalyzerResult};
+pub use device::{
+    capture_disk_inventory, detect_os_mount, enrich_disks, enrich_inventory, load_disk_inventory,
+    probe_local_disks, DiskInventory, DiskProbe, FilesystemSpace, MountRecord, PlatformDiskHint,
+    DISK_INVENTORY_VERSION,
+};
 pub use diagnostics::{
     build_diagnostics_bundle, write_diagnostics_bundle, DiagnosticsBundle, DiagnosticsEnvironment,
 };
@@ -23,22 +42,31 @@ pub use doctor::{collect_doctor_info, DoctorInfo};
 pub use eval::{
     evaluate_suite, evaluate_suite_file, EvaluationCase, EvaluationResult, EvaluationSuite,
 };
+pub use forecast::forecast_capacity;
+pub use health::{apply_disk_health, collect_disk_health, load_smartctl_dir, parse_smartctl_json};
 pub use markdown::render_markdown_summary;
 pub use model::{
-    BackendParity, Category, CategorySuggestion, DiskDiff, DiskInfo, DiskKind, DiskRole,
-    DiskRoleHint, DiskStorageType, DuplicateGroup, DuplicateIntent, DuplicateIntentLabel,
-    EstimatedImpact, FileEntry, FileTypeSummary, LocalityClass, PathDiff, PathStats,
-    PerformanceClass, PolicyAction, PolicyDecision, Recommendation, RecommendationChange,
+    AppliedPolicyProfile, ArchiveExtraction, ArchiveFormat, BackendParity, CapacityForecast,
+    Category, CategorySuggestion, CustomRule, CustomRuleConditions, CustomRuleScope, DedupeStage,
+    DedupeStageMetrics, DevProject, DirectoryMatchKind, DirectoryNode, DiskDiff, DiskHealth,
+    DiskInfo, DiskKind, DiskRole, DiskRoleHint, DiskStorageType, DuplicateDirectory,
+    DuplicateDirectoryGroup, DuplicateGroup, DuplicateIntent, DuplicateIntentLabel,
+    EstimatedImpact, FileEntry, FileTypeSummary, ForecastScope, GitRepository, GrowthAttribution,
+    LocalityClass, PathDiff, PathStats, PerformanceClass, PolicyAction, PolicyDecision,
+    PolicyProfile, PolicyThresholds, Recommendation, RecommendationChange,
     RecommendationChangeKind, RecommendationEvidence, RecommendationEvidenceKind, Report,
     ReportDiff, ReportImportResult, ReportSummary, RiskLevel, RuleTrace, RuleTraceStatus,
     ScanBackendKind, ScanMetadata, ScanMetrics, ScanPhase, ScanPhaseCount, ScanProgressEvent,
-    ScanProgressSummary, REPORT_VERSION,
+    ScanProgressSummary, SimilarImageFile, SimilarImageGroup, SizeMode, TieringMove,
+    REPORT_VERSION,
 };
 pub use planner::{
     build_scenario_plan, ScenarioPlan, ScenarioProjection, ScenarioRiskMix, ScenarioStrategy,
 };
+pub use policy_profile::{apply_policy_profile, load_policy_profile, policy_profile_hash};
 pub use recommend::{
-    generate_recommendation_bundle, generate_recommendations, RecommendationBundle,
+    generate_recommendation_bundle, generate_recommendation_bundle_with_context,
+    generate_recommendations, RecommendationBundle,
 };
 pub use reports::{
     build_report_diff, compare_reports, default_report_store_dir, get_report, history_file_path,
@@ -49,3 +77,5 @@ pub use scan::{
     compare_backends, run_scan, run_scan_with_callback, run_scan_with_events, ScanOptions,
     ScanRunOutput,
 };
+pub use tiering::plan_cold_data_tiering;
+pub use tree::flatten_directory_tree;
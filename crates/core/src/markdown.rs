@@ -11,6 +11,14 @@ pub fn render_markdown_summary(report: &Report, recommendations: &[Recommendatio
         report.scan_metrics.backend,
         report.scan_metrics.elapsed_ms
     ));
+    if let Some(applied) = &report.policy_profile {
+        out.push_str(&format!(
+            "- Policy profile: `{}` from `{}` (hash `{}`)\n\n",
+            applied.profile.name.as_deref().unwrap_or("unnamed"),
+            applied.source.as_deref().unwrap_or("built-in defaults"),
+            &applied.hash[..applied.hash.len().min(12)]
+        ));
+    }
 
     out.push_str("## Disk Inventory\n\n");
     if report.disks.is_empty() {
@@ -60,6 +68,27 @@ pub fn render_markdown_summary(report: &Report, recommendations: &[Recommendatio
             }
         }
 
+        if let Some(tree) = &path.directory_tree {
+            if !tree.children.is_empty() {
+                out.push_str("- Directory tree (top two levels):\n");
+                for child in &tree.children {
+                    out.push_str(&format!(
+                        "  - `{}` ({}, {} file(s))\n",
+                        child.path,
+                        human_bytes(child.size_bytes),
+                        child.file_count
+                    ));
+                    for grandchild in &child.children {
+                        out.push_str(&format!(
+                            "    - `{}` ({})\n",
+                            grandchild.path,
+                            human_bytes(grandchild.size_bytes)
+                        ));
+                    }
+                }
+            }
+        }
+
         if !path.file_type_summary.top_extensions.is_empty() {
             out.push_str("- Top file types:\n");
             for item in &path.file_type_summary.top_extensions {
@@ -92,6 +121,47 @@ pub fn render_markdown_summary(report: &Report, recommendations: &[Recommendatio
     }
 
     out.push_str("## Duplicate Highlights\n\n");
+    if !report.scan_metrics.dedupe_stages.is_empty() {
+        let stages = report
+            .scan_metrics
+            .dedupe_stages
+            .iter()
+            .map(|stage| {
+                format!(
+                    "`{:?}` {} file(s), read {}",
+                    stage.stage,
+                    stage.candidate_files,
+                    human_bytes(stage.bytes_read)
+                )
+            })
+            .collect::<Vec<_>>();
+        out.push_str(&format!("- Dedupe stages: {}\n", stages.join(" -> ")));
+    }
+    for group in report.duplicate_directories.iter().take(10) {
+        out.push_str(&format!(
+            "- directory tree `{:?}` ({:.0}% overlap): {}, wasted ~{}, label `{}`\n",
+            group.match_kind,
+            group.overlap_ratio * 100.0,
+            group
+                .directories
+                .iter()
+                .map(|directory| format!("`{}`", directory.path))
+                .collect::<Vec<_>>()
+                .join(", "),
+            human_bytes(group.total_wasted_bytes),
+            duplicate_intent_label(&group.intent.label)
+        ));
+    }
+    for extraction in report.archive_extractions.iter().take(10) {
+        out.push_str(&format!(
+            "- archive `{}` ({}) is fully extracted at `{}`: {} member(s), {}\n",
+            extraction.archive_path,
+            human_bytes(extraction.archive_size_bytes),
+            extraction.extracted_path,
+            extraction.member_count,
+            human_bytes(extraction.extracted_bytes)
+        ));
+    }
     if report.duplicates.is_empty() {
         out.push_str("No duplicate groups were detected.\n\n");
     } else {
@@ -107,14 +177,116 @@ pub fn render_markdown_summary(report: &Report, recommendations: &[Recommendatio
         out.push('\n');
     }
 
+    if !report.tiering_plan.is_empty() {
+        out.push_str("## Cold Data Tiering Plan\n\n");
+        for planned in &report.tiering_plan {
+            out.push_str(&format!(
+                "- `{}` ({}) -> `{}`, last modified {}\n",
+                planned.source_path,
+                human_bytes(planned.bytes),
+                planned.target_mount,
+                planned
+                    .last_modified
+                    .as_deref()
+                    .and_then(|text| text.get(..10))
+                    .unwrap_or("unknown")
+            ));
+        }
+        out.push('\n');
+    }
+
+    if !report.git_repositories.is_empty() {
+        out.push_str("## Git Repositories\n\n");
+        let mut repositories: Vec<_> = report.git_repositories.iter().collect();
+        repositories.sort_by_key(|repository| {
+            std::cmp::Reverse(repository.git_dir_bytes + repository.worktree_bytes)
+        });
+        for repository in repositories.into_iter().take(20) {
+            out.push_str(&format!(
+                "- `{}`: {} ({} in packs), last commit {}{}\n",
+                repository.path,
+                human_bytes(repository.git_dir_bytes + repository.worktree_bytes),
+                human_bytes(repository.pack_bytes),
+                repository.last_commit_at.as_deref().unwrap_or("unknown"),
+                if repository.files_newer_than_index > 0 {
+                    format!(
+                        ", {} file(s) newer than the index",
+                        repository.files_newer_than_index
+                    )
+                } else {
+                    String::new()
+                }
+            ));
+        }
+        out.push('\n');
+    }
+
+    if !report.capacity_forecasts.is_empty() {
+        out.push_str("## Capacity Forecast\n\n");
+        let mut forecasts: Vec<_> = report.capacity_forecasts.iter().collect();
+        forecasts.sort_by(|left, right| {
+            left.days_until_full
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&right.days_until_full.unwrap_or(f64::INFINITY))
+        });
+        for forecast in forecasts.into_iter().take(20) {
+            let day = |value: &Option<String>| {
+                value
+                    .as_deref()
+                    .and_then(|text| text.get(..10))
+                    .unwrap_or("never")
+                    .to_string()
+            };
+            out.push_str(&format!(
+                "- `{}`: {} of {}, {}/day over {} scans, full {} (95% interval {} to {})\n",
+                forecast.target,
+                human_bytes(forecast.used_bytes),
+                human_bytes(forecast.capacity_bytes),
+                human_bytes(forecast.growth_bytes_per_day.max(0.0) as u64),
+                forecast.samples,
+                day(&forecast.projected_full_at),
+                day(&forecast.projected_full_earliest),
+                day(&forecast.projected_full_latest)
+            ));
+        }
+        out.push('\n');
+    }
+
+    if report.scan.similar_images {
+        out.push_str("## Similar Images\n\n");
+        if report.similar_images.is_empty() {
+            out.push_str("No visually similar image groups were detected.\n\n");
+        } else {
+            for group in report.similar_images.iter().take(20) {
+                let reference = &group.files[0];
+                out.push_str(&format!(
+                    "- {} image(s) like `{}` ({}x{}), max distance {}, lower-resolution copies ~{}\n",
+                    group.files.len(),
+                    reference.path,
+                    reference.width,
+                    reference.height,
+                    group.max_distance,
+                    human_bytes(group.lower_resolution_bytes)
+                ));
+            }
+            out.push('\n');
+        }
+    }
+
     out.push_str("## Recommendations\n\n");
     if recommendations.is_empty() {
         out.push_str("No recommendations generated.\n");
     } else {
         for recommendation in recommendations {
+            let heading = if recommendation.rank > 0 {
+                format!("{}. {}", recommendation.rank, recommendation.title)
+            } else {
+                recommendation.title.clone()
+            };
             out.push_str(&format!(
-                "### {}\n\n- Risk: `{:?}`\n- Confidence: `{:.2}`\n- Policy safe: `{}`\n- Rationale: {}\n",
-                recommendation.title,
+                "### {}\n\n- Priority score: `{:.3}`\n- Risk: `{:?}`\n- Confidence: `{:.2}`\n- Policy safe: `{}`\n- Rationale: {}\n",
+                heading,
+                recommendation.priority_score,
                 recommendation.risk_level,
                 recommendation.confidence,
                 recommendation.policy_safe,
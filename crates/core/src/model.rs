@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 pub const REPORT_VERSION: &str = "1.3.0";
@@ -19,6 +21,24 @@ pub struct Report {
     pub paths: Vec<PathStats>,
     pub categories: Vec<CategorySuggestion>,
     pub duplicates: Vec<DuplicateGroup>,
+    #[serde(default)]
+    pub duplicate_directories: Vec<DuplicateDirectoryGroup>,
+    #[serde(default)]
+    pub archive_extractions: Vec<ArchiveExtraction>,
+    #[serde(default)]
+    pub similar_images: Vec<SimilarImageGroup>,
+    #[serde(default)]
+    pub git_repositories: Vec<GitRepository>,
+    /// Projects owning artifact-named directories; `None` in reports written before
+    /// the scan recorded them.
+    #[serde(default)]
+    pub dev_projects: Option<Vec<DevProject>>,
+    #[serde(default)]
+    pub capacity_forecasts: Vec<CapacityForecast>,
+    #[serde(default)]
+    pub tiering_plan: Vec<TieringMove>,
+    #[serde(default)]
+    pub policy_profile: Option<AppliedPolicyProfile>,
     pub recommendations: Vec<Recommendation>,
     #[serde(default)]
     pub policy_decisions: Vec<PolicyDecision>,
@@ -49,6 +69,25 @@ pub struct ScanMetadata {
     pub emit_progress_events: bool,
     #[serde(default = "default_progress_interval_ms")]
     pub progress_interval_ms: u64,
+    #[serde(default)]
+    pub directory_tree: bool,
+    #[serde(default)]
+    pub directory_tree_min_bytes: u64,
+    #[serde(default)]
+    pub size_mode: SizeMode,
+    #[serde(default)]
+    pub similar_images: bool,
+    #[serde(default)]
+    pub similar_image_max_distance: u32,
+    #[serde(default)]
+    pub dedupe_directories: bool,
+    #[serde(default)]
+    pub dedupe_directory_min_overlap: f32,
+    #[serde(default)]
+    pub dedupe_archives: bool,
+    /// Inventory file the disks were read from instead of probing this machine.
+    #[serde(default)]
+    pub disk_inventory: Option<String>,
 }
 
 fn default_progress_interval_ms() -> u64 {
@@ -62,6 +101,17 @@ pub enum ScanBackendKind {
     Native,
     #[serde(alias = "pdu")]
     PduLibrary,
+    Parallel,
+}
+
+// How file sizes are summed into path totals. Dedupe always compares apparent sizes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeMode {
+    #[default]
+    Apparent,
+    Allocated,
+    HardlinkDeduplicated,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -82,6 +132,39 @@ pub struct ScanMetrics {
     pub permission_denied_warnings: u64,
     #[serde(default)]
     pub contradiction_count: u64,
+    // Directories replayed from / re-read despite the incremental directory journal.
+    #[serde(default)]
+    pub journal_reused_directories: u64,
+    #[serde(default)]
+    pub journal_rescanned_directories: u64,
+    // Persistent dedupe cache lookups, counted per hash-stage lookup: a file that goes
+    // through the head, sample and full-hash stages is looked up up to three times.
+    #[serde(default)]
+    pub dedupe_hash_cache_hits: u64,
+    #[serde(default)]
+    pub dedupe_hash_cache_misses: u64,
+    #[serde(default)]
+    pub dedupe_stages: Vec<DedupeStageMetrics>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupeStage {
+    SizeGroup,
+    HeadBlock,
+    HeadTailSample,
+    FullHash,
+}
+
+// Work done by one stage of the duplicate pipeline. Files whose stage hash matches
+// no other file are eliminated and never reach the later, more expensive stages.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DedupeStageMetrics {
+    pub stage: DedupeStage,
+    pub candidate_files: u64,
+    pub candidate_groups: u64,
+    pub eliminated_files: u64,
+    pub bytes_read: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -135,6 +218,12 @@ pub struct BackendParity {
     #[serde(default)]
     pub scanned_bytes_delta: i64,
     #[serde(default)]
+    pub parallel_elapsed_ms: u64,
+    #[serde(default)]
+    pub parallel_scanned_files_delta: i64,
+    #[serde(default)]
+    pub parallel_scanned_bytes_delta: i64,
+    #[serde(default)]
     pub tolerance_ratio: f32,
     #[serde(default)]
     pub within_tolerance: bool,
@@ -186,6 +275,71 @@ pub struct DiskInfo {
     pub role_hint: DiskRoleHint,
     #[serde(default)]
     pub target_role_eligibility: Vec<String>,
+    /// `st_dev` of the mount point; scans attribute files to the disk with their device id.
+    #[serde(default)]
+    pub device_id: Option<u64>,
+    #[serde(default)]
+    pub mount_kind: MountKind,
+    /// Filesystem or pool this mount belongs to, such as `zfs:tank` or `btrfs:/dev/sdb1`.
+    /// Mounts sharing a pool share its capacity and free space.
+    #[serde(default)]
+    pub storage_pool: Option<String>,
+    /// The pool member whose free space stands for the whole pool; `None` for that
+    /// member itself and for standalone mounts.
+    #[serde(default)]
+    pub parent_mount: Option<String>,
+    /// Blocks only the superuser may write, such as ext4 reserved blocks or the btrfs
+    /// global reserve (`statvfs` free minus available).
+    #[serde(default)]
+    pub reserved_bytes: Option<u64>,
+    /// Space pinned by snapshots; deleting live files does not return it.
+    #[serde(default)]
+    pub snapshot_bytes: Option<u64>,
+    /// Uncompressed size of the stored data on filesystems that compress transparently.
+    /// Compare with used space for the compression ratio.
+    #[serde(default)]
+    pub logical_used_bytes: Option<u64>,
+    /// Space an unprivileged writer can still use. Rules prefer it over
+    /// `free_space_bytes` when a filesystem probe filled it.
+    #[serde(default)]
+    pub effective_free_space_bytes: Option<u64>,
+    /// SMART health of the drive behind this mount, when a report was imported or
+    /// `smartctl` was queried.
+    #[serde(default)]
+    pub health: Option<DiskHealth>,
+}
+
+/// Drive health read from `smartctl --json` output. Counters the drive does not
+/// report stay `None`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DiskHealth {
+    /// Where the data came from: the imported file or `smartctl <device>`.
+    pub source: String,
+    pub device: Option<String>,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    /// Overall SMART self-assessment; `false` means the drive predicts its own failure.
+    pub smart_passed: Option<bool>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    /// NVMe media and data integrity errors.
+    pub media_errors: Option<u64>,
+    pub power_on_hours: Option<u64>,
+    /// Share of rated endurance used, in percent; NVMe drives may report over 100.
+    pub wear_percent_used: Option<u32>,
+    pub temperature_celsius: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MountKind {
+    Filesystem,
+    Bind,
+    BtrfsSubvolume,
+    ZfsDataset,
+    Overlay,
+    #[default]
+    Unknown,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -275,6 +429,12 @@ pub struct PathStats {
     pub largest_directories: Vec<DirectoryUsage>,
     pub file_type_summary: FileTypeSummary,
     pub activity: ActivitySignals,
+    #[serde(default)]
+    pub directory_tree: Option<DirectoryNode>,
+    #[serde(default)]
+    pub hardlinked_files: u64,
+    #[serde(default)]
+    pub hardlink_savings_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -295,6 +455,19 @@ pub struct DirectoryUsage {
     pub size_bytes: u64,
 }
 
+// Hierarchical directory usage. Children below the scan's pruning threshold are
+// dropped, but their bytes and files still count towards every ancestor.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DirectoryNode {
+    pub path: String,
+    pub size_bytes: u64,
+    pub file_count: u64,
+    #[serde(default)]
+    pub latest_modified: Option<String>,
+    #[serde(default)]
+    pub children: Vec<DirectoryNode>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FileTypeSummary {
     pub top_extensions: Vec<ExtensionUsage>,
@@ -347,6 +520,162 @@ pub enum DuplicateIntentLabel {
     LikelyRedundant,
 }
 
+// Directory trees whose files duplicate each other. `identical` trees share a
+// Merkle hash over child names and content hashes; `near_identical` pairs share at
+// least the configured fraction of their bytes at matching relative paths.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateDirectoryGroup {
+    pub match_kind: DirectoryMatchKind,
+    #[serde(default)]
+    pub tree_hash: Option<String>,
+    pub directories: Vec<DuplicateDirectory>,
+    pub overlap_ratio: f32,
+    pub total_wasted_bytes: u64,
+    pub intent: DuplicateIntent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateDirectory {
+    pub path: String,
+    pub disk_mount: Option<String>,
+    pub file_count: u64,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectoryMatchKind {
+    Identical,
+    NearIdentical,
+}
+
+// An archive whose every member exists with identical content at the same relative
+// path under `extracted_path`, so the archive itself is a redundant copy.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchiveExtraction {
+    pub archive_path: String,
+    pub disk_mount: Option<String>,
+    pub format: ArchiveFormat,
+    pub archive_size_bytes: u64,
+    pub extracted_path: String,
+    pub member_count: u64,
+    pub extracted_bytes: u64,
+}
+
+// A git working copy found during the scan. Everything is read from `.git` without
+// running git: reflog timestamps, the index mtime and scanned file sizes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitRepository {
+    pub path: String,
+    pub disk_mount: Option<String>,
+    /// Latest commit, merge or rebase recorded in `logs/HEAD` or `logs/refs/heads`.
+    pub last_commit_at: Option<String>,
+    /// Latest reflog entry of any kind, including checkouts and clones.
+    pub last_ref_update_at: Option<String>,
+    pub latest_worktree_modified_at: Option<String>,
+    pub index_modified_at: Option<String>,
+    /// Tracked-looking files modified after the index was last written, a cheap
+    /// proxy for uncommitted changes. Paths ignored by the root `.gitignore` are skipped.
+    pub files_newer_than_index: u64,
+    pub pack_bytes: u64,
+    pub git_dir_bytes: u64,
+    pub worktree_bytes: u64,
+}
+
+// A directory holding project markers (`Cargo.toml`, `package.json`, `*.tf`, ...)
+// above an artifact-named directory. Recorded during the scan so the dev-artifact
+// analyzer works from the report alone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DevProject {
+    pub path: String,
+    /// Marker patterns present, as listed in the artifact catalog.
+    pub markers: Vec<String>,
+    /// Latest mtime of the directory and its non-artifact top-level entries.
+    pub last_activity_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastScope {
+    Disk,
+    Path,
+}
+
+// Projected time until a disk fills, fitted over the scan history plus the current
+// scan. A path forecast asks when that root's growth alone would use up the free
+// space left on its disk.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CapacityForecast {
+    pub scope: ForecastScope,
+    /// Mount point for disk forecasts, root path for path forecasts.
+    pub target: String,
+    pub disk_mount: Option<String>,
+    pub samples: u32,
+    pub first_sample_at: String,
+    pub last_sample_at: String,
+    pub used_bytes: u64,
+    /// Used bytes plus the effective free space, i.e. the point at which writes fail.
+    pub capacity_bytes: u64,
+    /// Theil-Sen slope: the median of all pairwise growth rates.
+    pub growth_bytes_per_day: f64,
+    /// 95% confidence interval of the slope.
+    pub growth_bytes_per_day_low: f64,
+    pub growth_bytes_per_day_high: f64,
+    /// `None` when usage is flat or shrinking.
+    pub projected_full_at: Option<String>,
+    /// Projection at the upper growth bound.
+    pub projected_full_earliest: Option<String>,
+    /// Projection at the lower growth bound; `None` when that bound is not growth.
+    pub projected_full_latest: Option<String>,
+    pub days_until_full: Option<f64>,
+}
+
+// One step of the advisory cold-data tiering plan: a directory untouched for longer
+// than `tiering_cold_days` and the slower local disk it fits on. Only moves whose
+// recommendation passed policy enforcement are kept.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TieringMove {
+    pub recommendation_id: String,
+    pub source_path: String,
+    pub source_mount: String,
+    pub bytes: u64,
+    pub target_mount: String,
+    /// Newest modification time under the directory; `None` when only root-level
+    /// activity signals were available.
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+// Images whose perceptual hashes lie within the configured Hamming distance of each
+// other. Files are ordered by resolution, so the first entry is the reference copy.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimilarImageGroup {
+    pub files: Vec<SimilarImageFile>,
+    pub max_distance: u32,
+    pub lower_resolution_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimilarImageFile {
+    pub path: String,
+    pub disk_mount: Option<String>,
+    pub size_bytes: u64,
+    pub width: u32,
+    pub height: u32,
+    pub perceptual_hash: String,
+    // Hamming distance from the reference copy's perceptual hash.
+    pub distance: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CategorySuggestion {
     pub target: String,
@@ -388,6 +717,12 @@ pub struct Recommendation {
     pub next_steps: Vec<String>,
     pub estimated_impact: EstimatedImpact,
     pub risk_level: RiskLevel,
+    /// Ranking score from the stage after policy enforcement; higher ranks first.
+    #[serde(default)]
+    pub priority_score: f32,
+    /// 1-based position in the ranked list, or 0 for unranked recommendations.
+    #[serde(default)]
+    pub rank: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -410,6 +745,9 @@ pub enum RecommendationEvidenceKind {
     Disk,
     Directory,
     DuplicateGroup,
+    DuplicateDirectoryGroup,
+    ArchiveExtraction,
+    SimilarImageGroup,
     HistoryDelta,
     Warning,
     #[default]
@@ -447,6 +785,157 @@ pub struct PolicyDecision {
     pub rationale: String,
 }
 
+// User-tunable recommendation policy: rule thresholds, disabled rules, and risk-level
+// overrides. Every field is optional in the profile file and falls back to the
+// built-in defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyProfile {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub thresholds: PolicyThresholds,
+    // Rule ids (as in rule traces) or recommendation ids to suppress.
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+    // Recommendation id (or id prefix such as `risky-disk`) to the risk level to report.
+    #[serde(default)]
+    pub risk_levels: BTreeMap<String, RiskLevel>,
+    // Site-specific declarative rules evaluated after the built-in rules.
+    #[serde(default)]
+    pub custom_rules: Vec<CustomRule>,
+}
+
+// A declarative rule: every report item in `scope` that satisfies all `when`
+// conditions counts as a match, and enough matches emit one recommendation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    pub id: String,
+    pub title: String,
+    // Supports `{count}`, `{total_size}`, `{largest_path}` and `{largest_size}`.
+    #[serde(default)]
+    pub rationale: Option<String>,
+    pub scope: CustomRuleScope,
+    #[serde(default)]
+    pub when: CustomRuleConditions,
+    #[serde(default = "default_custom_rule_risk_level")]
+    pub risk_level: RiskLevel,
+    #[serde(default = "default_custom_rule_confidence")]
+    pub confidence: f32,
+    #[serde(default)]
+    pub target_mount: Option<String>,
+    #[serde(default)]
+    pub next_steps: Vec<String>,
+}
+
+fn default_custom_rule_risk_level() -> RiskLevel {
+    RiskLevel::Medium
+}
+
+fn default_custom_rule_confidence() -> f32 {
+    0.6
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomRuleScope {
+    Files,
+    Directories,
+    Disks,
+    Categories,
+    DuplicateGroups,
+}
+
+// Conditions are combined with AND; unset conditions always pass. Disk conditions
+// apply to the disk holding the matched item.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomRuleConditions {
+    pub path_glob: Option<String>,
+    pub min_size_bytes: Option<u64>,
+    pub max_size_bytes: Option<u64>,
+    pub min_total_bytes: Option<u64>,
+    pub min_matches: Option<usize>,
+    pub disk_mount: Option<String>,
+    pub on_os_drive: Option<bool>,
+    pub locality: Option<LocalityClass>,
+    pub storage_type: Option<DiskStorageType>,
+    pub max_free_ratio: Option<f64>,
+    pub category: Option<Category>,
+    pub min_confidence: Option<f32>,
+    pub duplicate_intent: Option<DuplicateIntentLabel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyThresholds {
+    pub os_headroom_min_free_ratio: f64,
+    pub risky_disk_max_free_ratio: f64,
+    pub min_source_scan_coverage_ratio: f64,
+    pub consolidation_min_used_bytes: u64,
+    pub duplicate_min_group_wasted_bytes: u64,
+    pub duplicate_cleanup_min_total_bytes: u64,
+    pub similar_image_min_reclaimable_bytes: u64,
+    pub node_modules_min_size_bytes: u64,
+    pub target_dir_min_size_bytes: u64,
+    pub dev_artifact_min_size_bytes: u64,
+    pub dev_artifact_stale_days: u32,
+    pub stale_repo_months: u32,
+    pub cache_min_size_bytes: u64,
+    pub trend_min_change_ratio: f64,
+    pub trend_min_change_bytes: u64,
+    pub forecast_horizon_days: u32,
+    pub forecast_min_samples: u32,
+    pub tiering_cold_days: u32,
+    pub tiering_min_directory_bytes: u64,
+    pub tiering_target_min_free_ratio: f64,
+    pub disk_health_max_reallocated_sectors: u64,
+    pub disk_health_max_wear_ratio: f64,
+    pub disk_health_max_temperature_celsius: i64,
+    pub disk_health_max_power_on_hours: u64,
+}
+
+impl Default for PolicyThresholds {
+    fn default() -> Self {
+        Self {
+            os_headroom_min_free_ratio: 0.15,
+            risky_disk_max_free_ratio: 0.12,
+            min_source_scan_coverage_ratio: 0.35,
+            consolidation_min_used_bytes: 50 * 1024 * 1024 * 1024,
+            duplicate_min_group_wasted_bytes: 64 * 1024 * 1024,
+            duplicate_cleanup_min_total_bytes: 256 * 1024 * 1024,
+            similar_image_min_reclaimable_bytes: 16 * 1024 * 1024,
+            node_modules_min_size_bytes: 1_000_000_000,
+            target_dir_min_size_bytes: 2_000_000_000,
+            dev_artifact_min_size_bytes: 500_000_000,
+            dev_artifact_stale_days: 90,
+            stale_repo_months: 12,
+            cache_min_size_bytes: 500_000_000,
+            trend_min_change_ratio: 0.10,
+            trend_min_change_bytes: 1_073_741_824,
+            forecast_horizon_days: 90,
+            forecast_min_samples: 3,
+            tiering_cold_days: 180,
+            tiering_min_directory_bytes: 1_073_741_824,
+            tiering_target_min_free_ratio: 0.20,
+            disk_health_max_reallocated_sectors: 0,
+            disk_health_max_wear_ratio: 0.80,
+            disk_health_max_temperature_celsius: 60,
+            disk_health_max_power_on_hours: 50_000,
+        }
+    }
+}
+
+// The profile a report's recommendations were generated with. `source` is the
+// profile file, or `None` for the built-in defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppliedPolicyProfile {
+    pub source: Option<String>,
+    pub hash: String,
+    pub profile: PolicyProfile,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum PolicyAction {
@@ -496,12 +985,26 @@ pub struct DiskSnapshot {
     pub free_space_bytes: u64,
 }
 
-// A snapshot of a scanned path's state.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+// A snapshot of a scanned path's state. The size lists are bounded; each floor is
+// the size below which an entry may be missing from its list (0 when complete), so
+// a later diff can tell truncation from growth.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct PathSnapshot {
     pub root_path: String,
     pub total_size_bytes: u64,
     pub file_count: u64,
+    #[serde(default)]
+    pub directories: Vec<DirectoryUsage>,
+    #[serde(default)]
+    pub directory_floor_bytes: u64,
+    #[serde(default)]
+    pub extensions: Vec<ExtensionUsage>,
+    #[serde(default)]
+    pub extension_floor_bytes: u64,
+    #[serde(default)]
+    pub large_files: Vec<FileEntry>,
+    #[serde(default)]
+    pub large_file_floor_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -570,6 +1073,27 @@ pub struct PathDiff {
     #[serde(default)]
     pub right_file_count: Option<u64>,
     pub file_count_delta: i64,
+    /// Subtrees that explain the size change, largest change first. A directory is
+    /// left out when one of its children accounts for most of its change.
+    #[serde(default)]
+    pub directory_growth: Vec<GrowthAttribution>,
+    #[serde(default)]
+    pub extension_growth: Vec<GrowthAttribution>,
+    /// Large files listed only in the right report; they were added or grew past the
+    /// left report's large-file cutoff.
+    #[serde(default)]
+    pub new_large_files: Vec<FileEntry>,
+    #[serde(default)]
+    pub vanished_large_files: Vec<FileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GrowthAttribution {
+    /// Directory path or file extension.
+    pub key: String,
+    pub left_bytes: u64,
+    pub right_bytes: u64,
+    pub delta_bytes: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -0,0 +1,381 @@
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use image::ImageReader;
+use rayon::prelude::*;
+
+use crate::dedupe::FileRecord;
+use crate::model::{SimilarImageFile, SimilarImageGroup};
+
+/// Hamming distance (out of 64 bits) at or below which two images are grouped.
+pub const DEFAULT_SIMILAR_IMAGE_MAX_DISTANCE: u32 = 6;
+/// Icons and UI sprites below this size are not worth decoding.
+const MIN_IMAGE_BYTES: u64 = 4 * 1024;
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png"];
+
+#[derive(Debug, Clone)]
+pub struct SimilarImageOptions {
+    pub max_distance: u32,
+    pub cancel_flag: Option<Arc<AtomicBool>>,
+}
+
+impl Default for SimilarImageOptions {
+    fn default() -> Self {
+        Self {
+            max_distance: DEFAULT_SIMILAR_IMAGE_MAX_DISTANCE,
+            cancel_flag: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageFingerprint {
+    pub hash: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageFingerprint {
+    fn pixels(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+}
+
+/// Computes a 64-bit difference hash (dHash): the image is reduced to a 9x8
+/// grayscale thumbnail and each bit records whether a pixel is brighter than its
+/// right-hand neighbour. Re-encoding and resizing barely move the hash.
+pub fn fingerprint_image(path: &Path) -> Result<ImageFingerprint> {
+    let image = ImageReader::open(path)
+        .with_context(|| format!("failed to open image {}", path.display()))?
+        .with_guessed_format()
+        .with_context(|| format!("failed to detect image format for {}", path.display()))?
+        .decode()
+        .with_context(|| format!("failed to decode image {}", path.display()))?;
+    let thumbnail = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0_u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if thumbnail.get_pixel(x, y)[0] > thumbnail.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+
+    Ok(ImageFingerprint {
+        hash,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Groups jpeg/png records whose perceptual hashes are within `max_distance` bits of
+/// the group's reference, its highest-resolution member. Images are only read, never
+/// modified; undecodable files are summarized in a single warning.
+pub fn find_similar_images(
+    records: &[FileRecord],
+    options: &SimilarImageOptions,
+    warnings: &mut Vec<String>,
+) -> Vec<SimilarImageGroup> {
+    let mut seen_identities = HashSet::new();
+    let candidates = records
+        .iter()
+        .filter(|record| record.size_bytes >= MIN_IMAGE_BYTES && is_image_path(&record.path))
+        // Hard links share pixels by definition; only fingerprint one of them.
+        .filter(|record| match record.device_id.zip(record.inode) {
+            Some(identity) => seen_identities.insert(identity),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+
+    let is_cancelled = || {
+        options
+            .cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    };
+    let results = candidates
+        .par_iter()
+        .map(|record| {
+            if is_cancelled() {
+                return None;
+            }
+            Some(fingerprint_image(&record.path))
+        })
+        .collect::<Vec<_>>();
+    if is_cancelled() {
+        warnings.push(
+            "scan canceled during perceptual image hashing; similar images were not reported"
+                .to_string(),
+        );
+        return Vec::new();
+    }
+
+    let mut fingerprinted = Vec::new();
+    let mut undecodable = 0_u64;
+    for (record, result) in candidates.into_iter().zip(results) {
+        match result {
+            Some(Ok(fingerprint)) => fingerprinted.push((record, fingerprint)),
+            Some(Err(_)) => undecodable += 1,
+            None => {}
+        }
+    }
+    if undecodable > 0 {
+        warnings.push(format!(
+            "perceptual image hashing skipped {undecodable} file(s) that could not be decoded"
+        ));
+    }
+
+    fingerprinted.sort_by(reference_order);
+    let hashes = fingerprinted
+        .iter()
+        .map(|(_, fingerprint)| fingerprint.hash)
+        .collect::<Vec<_>>();
+    let mut groups = cluster_by_distance(&hashes, options.max_distance)
+        .into_iter()
+        .map(|members| {
+            build_group(
+                members
+                    .into_iter()
+                    .map(|index| fingerprinted[index])
+                    .collect(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    groups.sort_by(|a, b| {
+        b.lower_resolution_bytes
+            .cmp(&a.lower_resolution_bytes)
+            .then_with(|| a.files[0].path.cmp(&b.files[0].path))
+    });
+    groups
+}
+
+fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Highest resolution first, then the larger file, then by path: the first member of
+/// a group is its reference.
+fn reference_order(
+    (a_record, a): &(&FileRecord, ImageFingerprint),
+    (b_record, b): &(&FileRecord, ImageFingerprint),
+) -> CmpOrdering {
+    b.pixels()
+        .cmp(&a.pixels())
+        .then_with(|| b_record.size_bytes.cmp(&a_record.size_bytes))
+        .then_with(|| a_record.path.cmp(&b_record.path))
+}
+
+fn build_group(mut members: Vec<(&FileRecord, ImageFingerprint)>) -> SimilarImageGroup {
+    members.sort_by(reference_order);
+    let reference = members[0].1;
+
+    let files = members
+        .iter()
+        .map(|(record, fingerprint)| SimilarImageFile {
+            path: record.path.to_string_lossy().to_string(),
+            disk_mount: record.disk_mount.clone(),
+            size_bytes: record.size_bytes,
+            width: fingerprint.width,
+            height: fingerprint.height,
+            perceptual_hash: format!("{:016x}", fingerprint.hash),
+            distance: hamming_distance(reference.hash, fingerprint.hash),
+        })
+        .collect::<Vec<_>>();
+    let lower_resolution_bytes = members
+        .iter()
+        .filter(|(_, fingerprint)| fingerprint.pixels() < reference.pixels())
+        .map(|(record, _)| record.size_bytes)
+        .sum();
+
+    SimilarImageGroup {
+        max_distance: files.iter().map(|file| file.distance).max().unwrap_or(0),
+        files,
+        lower_resolution_bytes,
+    }
+}
+
+/// Reference clustering of `hashes`, which must be sorted with the preferred group
+/// reference first. Each hash not yet grouped claims every other ungrouped hash
+/// within `max_distance` of it, so no member drifts further than the threshold from
+/// its reference through a chain of near neighbours. Returns index groups with two
+/// or more members; neighbours are found through a BK-tree so large photo libraries
+/// avoid a full pairwise comparison.
+fn cluster_by_distance(hashes: &[u64], max_distance: u32) -> Vec<Vec<usize>> {
+    let mut tree = BkTree::default();
+    for (index, hash) in hashes.iter().enumerate() {
+        tree.insert(*hash, index);
+    }
+
+    let mut grouped = vec![false; hashes.len()];
+    let mut clusters = Vec::new();
+    for (reference, hash) in hashes.iter().enumerate() {
+        if grouped[reference] {
+            continue;
+        }
+        let mut members = tree
+            .find_within(*hash, max_distance)
+            .into_iter()
+            .filter(|index| !grouped[*index])
+            .collect::<Vec<_>>();
+        for index in &members {
+            grouped[*index] = true;
+        }
+        // A lone reference stays out of later groups: joining one with a lower
+        // resolution reference would make it that group's reference.
+        grouped[reference] = true;
+        if members.len() > 1 {
+            members.sort_unstable();
+            clusters.push(members);
+        }
+    }
+    clusters.sort();
+    clusters
+}
+
+#[derive(Debug, Default)]
+struct BkTree {
+    nodes: Vec<BkNode>,
+}
+
+#[derive(Debug)]
+struct BkNode {
+    hash: u64,
+    index: usize,
+    children: Vec<(u32, usize)>,
+}
+
+impl BkTree {
+    fn insert(&mut self, hash: u64, index: usize) {
+        let new_node = self.nodes.len();
+        self.nodes.push(BkNode {
+            hash,
+            index,
+            children: Vec::new(),
+        });
+        if new_node == 0 {
+            return;
+        }
+
+        let mut current = 0;
+        loop {
+            let distance = hamming_distance(self.nodes[current].hash, hash);
+            match self.nodes[current]
+                .children
+                .iter()
+                .find(|(edge, _)| *edge == distance)
+            {
+                Some((_, child)) => current = *child,
+                None => {
+                    self.nodes[current].children.push((distance, new_node));
+                    return;
+                }
+            }
+        }
+    }
+
+    fn find_within(&self, hash: u64, max_distance: u32) -> Vec<usize> {
+        let mut found = Vec::new();
+        if self.nodes.is_empty() {
+            return found;
+        }
+        let mut stack = vec![0];
+        while let Some(current) = stack.pop() {
+            let node = &self.nodes[current];
+            let distance = hamming_distance(node.hash, hash);
+            if distance <= max_distance {
+                found.push(node.index);
+            }
+            for (edge, child) in &node.children {
+                if edge.abs_diff(distance) <= max_distance {
+                    stack.push(*child);
+                }
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cluster_by_distance, find_similar_images, SimilarImageOptions};
+    use crate::dedupe::FileRecord;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::path::Path;
+
+    fn gradient(width: u32, height: u32, inverted: bool) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            // Pixel-level noise keeps the encoded files above the size floor and is
+            // averaged away by the 9x8 reduction.
+            let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104_729)) % 24;
+            let mut value = (x.abs_diff(width / 3) * 180 / width + y * 50 / height + noise) as u8;
+            if inverted {
+                value = 255 - value;
+            }
+            Rgb([value, value, value])
+        })
+    }
+
+    fn record(path: &Path) -> FileRecord {
+        FileRecord::from_path(path.to_path_buf(), Some("/".to_string()), None)
+            .expect("image metadata")
+    }
+
+    #[test]
+    fn groups_resized_copies_and_orders_highest_resolution_first() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let original = dir.path().join("original.png");
+        let thumbnail = dir.path().join("thumbnail.jpg");
+        let unrelated = dir.path().join("unrelated.png");
+        let image = gradient(640, 480, false);
+        image
+            .save_with_format(&original, ImageFormat::Png)
+            .expect("write original");
+        gradient(320, 240, false)
+            .save_with_format(&thumbnail, ImageFormat::Jpeg)
+            .expect("write thumbnail");
+        gradient(640, 480, true)
+            .save_with_format(&unrelated, ImageFormat::Png)
+            .expect("write unrelated");
+
+        let records = vec![record(&thumbnail), record(&unrelated), record(&original)];
+        let mut warnings = Vec::new();
+        let groups = find_similar_images(&records, &SimilarImageOptions::default(), &mut warnings);
+
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.files.len(), 2);
+        assert!(group.files[0].path.ends_with("original.png"));
+        assert_eq!((group.files[0].width, group.files[0].height), (640, 480));
+        assert!(group.files[1].path.ends_with("thumbnail.jpg"));
+        assert_eq!(group.lower_resolution_bytes, group.files[1].size_bytes);
+        assert!(group.max_distance <= SimilarImageOptions::default().max_distance);
+    }
+
+    #[test]
+    fn clustering_keeps_members_within_threshold_of_the_reference() {
+        // 0b1111 is 2 bits from 0b0011 but 4 from the reference, so the chain stops.
+        let hashes = [0b0000_u64, 0b0011, 0b1111, u64::MAX];
+        assert_eq!(cluster_by_distance(&hashes, 2), vec![vec![0, 1]]);
+        assert_eq!(cluster_by_distance(&hashes, 4), vec![vec![0, 1, 2]]);
+        assert!(cluster_by_distance(&hashes, 1).is_empty());
+    }
+}
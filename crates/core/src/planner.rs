@@ -248,6 +248,8 @@ mod tests {
                 risk_notes: None,
             },
             risk_level,
+            priority_score: 0.0,
+            rank: 0,
         }
     }
 }
@@ -193,7 +193,7 @@ mod tests {
     use super::enforce_recommendation_policies;
     use crate::model::{
         DiskInfo, DiskKind, DiskStorageType, EstimatedImpact, LocalityClass, PerformanceClass,
-        Recommendation, Report, RiskLevel, ScanBackendKind, ScanMetadata, ScanMetrics,
+        Recommendation, Report, RiskLevel, ScanBackendKind, ScanMetadata, ScanMetrics, SizeMode,
     };
 
     #[test]
@@ -224,6 +224,15 @@ mod tests {
             metadata_notes: Vec::new(),
             role_hint: Default::default(),
             target_role_eligibility: Vec::new(),
+            device_id: None,
+            mount_kind: Default::default(),
+            storage_pool: None,
+            parent_mount: None,
+            reserved_bytes: None,
+            snapshot_bytes: None,
+            logical_used_bytes: None,
+            effective_free_space_bytes: None,
+            health: None,
         };
 
         let report = Report {
@@ -242,6 +251,15 @@ mod tests {
                 min_ratio: None,
                 emit_progress_events: false,
                 progress_interval_ms: 250,
+                directory_tree: false,
+                directory_tree_min_bytes: 0,
+                size_mode: SizeMode::Apparent,
+                similar_images: false,
+                similar_image_max_distance: 0,
+                dedupe_directories: false,
+                dedupe_directory_min_overlap: 0.0,
+                dedupe_archives: false,
+                disk_inventory: None,
             },
             scan_metrics: ScanMetrics::default(),
             scan_progress_summary: crate::model::ScanProgressSummary::default(),
@@ -250,6 +268,14 @@ mod tests {
             paths: Vec::new(),
             categories: Vec::new(),
             duplicates: Vec::new(),
+            duplicate_directories: Vec::new(),
+            archive_extractions: Vec::new(),
+            similar_images: Vec::new(),
+            git_repositories: Vec::new(),
+            dev_projects: None,
+            capacity_forecasts: Vec::new(),
+            tiering_plan: Vec::new(),
+            policy_profile: None,
             recommendations: Vec::new(),
             policy_decisions: Vec::new(),
             rule_traces: Vec::new(),
@@ -273,6 +299,8 @@ mod tests {
                 risk_notes: None,
             },
             risk_level: RiskLevel::Low,
+            priority_score: 0.0,
+            rank: 0,
         };
 
         let outcome = enforce_recommendation_policies(&report, vec![candidate]);
@@ -312,6 +340,15 @@ mod tests {
                 evidence: vec!["photos".to_string()],
             },
             target_role_eligibility: vec!["media_library".to_string()],
+            device_id: None,
+            mount_kind: Default::default(),
+            storage_pool: None,
+            parent_mount: None,
+            reserved_bytes: None,
+            snapshot_bytes: None,
+            logical_used_bytes: None,
+            effective_free_space_bytes: None,
+            health: None,
         };
 
         let report = Report {
@@ -330,6 +367,15 @@ mod tests {
                 min_ratio: None,
                 emit_progress_events: false,
                 progress_interval_ms: 250,
+                directory_tree: false,
+                directory_tree_min_bytes: 0,
+                size_mode: SizeMode::Apparent,
+                similar_images: false,
+                similar_image_max_distance: 0,
+                dedupe_directories: false,
+                dedupe_directory_min_overlap: 0.0,
+                dedupe_archives: false,
+                disk_inventory: None,
             },
             scan_metrics: ScanMetrics::default(),
             scan_progress_summary: crate::model::ScanProgressSummary::default(),
@@ -338,6 +384,14 @@ mod tests {
             paths: Vec::new(),
             categories: Vec::new(),
             duplicates: Vec::new(),
+            duplicate_directories: Vec::new(),
+            archive_extractions: Vec::new(),
+            similar_images: Vec::new(),
+            git_repositories: Vec::new(),
+            dev_projects: None,
+            capacity_forecasts: Vec::new(),
+            tiering_plan: Vec::new(),
+            policy_profile: None,
             recommendations: Vec::new(),
             policy_decisions: Vec::new(),
             rule_traces: Vec::new(),
@@ -361,6 +415,8 @@ mod tests {
                 risk_notes: None,
             },
             risk_level: RiskLevel::Low,
+            priority_score: 0.0,
+            rank: 0,
         };
 
         let outcome = enforce_recommendation_policies(&report, vec![candidate]);
@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::custom_rules::validate_custom_rules;
+use crate::model::{
+    AppliedPolicyProfile, PolicyProfile, Recommendation, RiskLevel, RuleTrace, RuleTraceStatus,
+};
+
+/// Loads a policy profile from a `.toml` or `.json` file. Unknown keys are rejected
+/// so a misspelled threshold does not silently fall back to its default.
+pub fn load_policy_profile(path: &Path) -> Result<PolicyProfile> {
+    let payload = fs::read_to_string(path)
+        .with_context(|| format!("failed to read policy profile {}", path.display()))?;
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    let profile: PolicyProfile = match extension.as_deref() {
+        Some("toml") => toml::from_str(&payload)
+            .with_context(|| format!("failed to parse policy profile {}", path.display()))?,
+        Some("json") => serde_json::from_str(&payload)
+            .with_context(|| format!("failed to parse policy profile {}", path.display()))?,
+        _ => bail!(
+            "unsupported policy profile {}; expected a .toml or .json file",
+            path.display()
+        ),
+    };
+    validate_policy_profile(&profile)?;
+    Ok(profile)
+}
+
+pub fn validate_policy_profile(profile: &PolicyProfile) -> Result<()> {
+    let thresholds = &profile.thresholds;
+    for (name, value) in [
+        (
+            "os_headroom_min_free_ratio",
+            thresholds.os_headroom_min_free_ratio,
+        ),
+        (
+            "risky_disk_max_free_ratio",
+            thresholds.risky_disk_max_free_ratio,
+        ),
+        (
+            "min_source_scan_coverage_ratio",
+            thresholds.min_source_scan_coverage_ratio,
+        ),
+        ("trend_min_change_ratio", thresholds.trend_min_change_ratio),
+        (
+            "tiering_target_min_free_ratio",
+            thresholds.tiering_target_min_free_ratio,
+        ),
+        (
+            "disk_health_max_wear_ratio",
+            thresholds.disk_health_max_wear_ratio,
+        ),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            bail!("policy threshold {name} must be between 0 and 1");
+        }
+    }
+    if thresholds.forecast_min_samples < 2 {
+        bail!("policy threshold forecast_min_samples must be at least 2");
+    }
+    if profile.disabled_rules.iter().any(|id| id.trim().is_empty())
+        || profile.risk_levels.keys().any(|id| id.trim().is_empty())
+    {
+        bail!("policy profile rule ids must not be empty");
+    }
+    validate_custom_rules(&profile.custom_rules)
+}
+
+/// Stable BLAKE3 digest of the profile's effective settings, so two reports can be
+/// checked for having been produced under the same policy.
+pub fn policy_profile_hash(profile: &PolicyProfile) -> String {
+    let payload = serde_json::to_vec(profile).unwrap_or_default();
+    blake3::hash(&payload).to_hex().to_string()
+}
+
+pub fn apply_policy_profile(source: Option<&Path>, profile: PolicyProfile) -> AppliedPolicyProfile {
+    AppliedPolicyProfile {
+        source: source.map(|path| path.to_string_lossy().to_string()),
+        hash: policy_profile_hash(&profile),
+        profile,
+    }
+}
+
+impl PolicyProfile {
+    pub fn is_rule_disabled(&self, rule_id: &str) -> bool {
+        self.disabled_rules
+            .iter()
+            .any(|disabled| disabled == rule_id)
+    }
+
+    pub fn is_recommendation_disabled(&self, recommendation_id: &str) -> bool {
+        self.disabled_rules
+            .iter()
+            .any(|disabled| matches_recommendation_id(disabled, recommendation_id))
+    }
+
+    /// Risk level override for a recommendation; the most specific matching key wins.
+    pub fn risk_level_for(&self, recommendation_id: &str) -> Option<RiskLevel> {
+        self.risk_levels
+            .iter()
+            .filter(|(key, _)| matches_recommendation_id(key, recommendation_id))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, level)| level.clone())
+    }
+}
+
+/// `key` names either the exact recommendation id or a family of ids such as
+/// `risky-disk` for `risky-disk-d`.
+fn matches_recommendation_id(key: &str, recommendation_id: &str) -> bool {
+    recommendation_id
+        .strip_prefix(key)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+pub(crate) fn disabled_rule_trace(rule_id: &str) -> RuleTrace {
+    RuleTrace {
+        rule_id: rule_id.to_string(),
+        status: RuleTraceStatus::Skipped,
+        detail: "Rule disabled by policy profile.".to_string(),
+        recommendation_id: None,
+        confidence: None,
+    }
+}
+
+/// Drops recommendations disabled by id and applies risk-level overrides, tracing
+/// every change so the report shows why a recommendation differs from the defaults.
+pub(crate) fn apply_profile_overrides(
+    profile: &PolicyProfile,
+    candidates: Vec<Recommendation>,
+    traces: &mut Vec<RuleTrace>,
+) -> Vec<Recommendation> {
+    let mut output = Vec::with_capacity(candidates.len());
+    for mut recommendation in candidates {
+        if profile.is_recommendation_disabled(&recommendation.id) {
+            traces.push(RuleTrace {
+                rule_id: "policy_profile".to_string(),
+                status: RuleTraceStatus::Skipped,
+                detail: "Recommendation disabled by policy profile.".to_string(),
+                recommendation_id: Some(recommendation.id),
+                confidence: None,
+            });
+            continue;
+        }
+        if let Some(level) = profile.risk_level_for(&recommendation.id) {
+            if level != recommendation.risk_level {
+                traces.push(RuleTrace {
+                    rule_id: "policy_profile".to_string(),
+                    status: RuleTraceStatus::Emitted,
+                    detail: format!(
+                        "Risk level changed from {:?} to {:?} by policy profile.",
+                        recommendation.risk_level, level
+                    ),
+                    recommendation_id: Some(recommendation.id.clone()),
+                    confidence: Some(recommendation.confidence),
+                });
+                recommendation.risk_level = level;
+            }
+        }
+        output.push(recommendation);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::{load_policy_profile, policy_profile_hash};
+    use crate::model::{PolicyProfile, RiskLevel};
+
+    #[test]
+    fn loads_toml_profile_over_defaults_and_rejects_unknown_keys() {
+        let temp = TempDir::new().expect("tempdir");
+        let path = temp.path().join("strict.toml");
+        fs::write(
+            &path,
+            r#"
+name = "strict"
+disabled_rules = ["cloud_exclusion_notice"]
+
+[thresholds]
+os_headroom_min_free_ratio = 0.25
+
+[risk_levels]
+risky-disk = "medium"
+"#,
+        )
+        .expect("write profile");
+
+        let profile = load_policy_profile(&path).expect("profile");
+        assert_eq!(profile.name.as_deref(), Some("strict"));
+        assert_eq!(profile.thresholds.os_headroom_min_free_ratio, 0.25);
+        assert_eq!(profile.thresholds.risky_disk_max_free_ratio, 0.12);
+        assert!(profile.is_rule_disabled("cloud_exclusion_notice"));
+        assert_eq!(
+            profile.risk_level_for("risky-disk-d"),
+            Some(RiskLevel::Medium)
+        );
+        assert_eq!(profile.risk_level_for("risky-diskette"), None);
+        assert_ne!(
+            policy_profile_hash(&profile),
+            policy_profile_hash(&PolicyProfile::default())
+        );
+
+        let typo = temp.path().join("typo.json");
+        fs::write(&typo, r#"{"thresholds": {"os_headroom_ratio": 0.2}}"#).expect("write");
+        assert!(load_policy_profile(&typo).is_err());
+    }
+}
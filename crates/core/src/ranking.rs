@@ -0,0 +1,200 @@
+use std::path::Path;
+
+use crate::device::effective_free_ratio;
+use crate::model::{
+    DiskInfo, ForecastScope, PolicyThresholds, Recommendation, RecommendationEvidenceKind, Report,
+    RiskLevel,
+};
+use crate::recommend::AFFECTED_DISK_LABEL;
+
+/// Share of the score driven by how full the affected disk is.
+const URGENCY_WEIGHT: f32 = 0.4;
+/// Share of the score driven by reclaimable bytes.
+const RECLAIM_WEIGHT: f32 = 0.3;
+/// Floor so advisory recommendations without bytes or a disk still rank by
+/// confidence and risk.
+const BASE_WEIGHT: f32 = 0.3;
+/// Disks below this used ratio contribute no urgency; urgency is 1 at `FULL_USED_RATIO`.
+const URGENT_USED_RATIO: f64 = 0.70;
+const FULL_USED_RATIO: f64 = 0.95;
+/// Reclaimable bytes are scored on a log scale between these bounds.
+const MIN_RECLAIM_BYTES: f64 = 64.0 * 1024.0 * 1024.0;
+const MAX_RECLAIM_BYTES: f64 = 1024.0 * 1024.0 * 1024.0 * 1024.0;
+
+/// Orders recommendations by `priority_score`, highest first, and assigns 1-based
+/// ranks. The score is `confidence * risk_factor * (base + urgency + reclaim)`:
+/// confident, low-risk actions that free space on nearly full disks come first.
+/// A disk projected to fill within `forecast_horizon_days` is treated as urgent in
+/// proportion to how soon it fills. Ties keep rule order.
+pub fn rank_recommendations(
+    report: &Report,
+    thresholds: &PolicyThresholds,
+    recommendations: &mut [Recommendation],
+) {
+    for recommendation in recommendations.iter_mut() {
+        recommendation.priority_score = priority_score(report, thresholds, recommendation);
+    }
+    recommendations.sort_by(|left, right| right.priority_score.total_cmp(&left.priority_score));
+    for (index, recommendation) in recommendations.iter_mut().enumerate() {
+        recommendation.rank = index as u32 + 1;
+    }
+}
+
+fn priority_score(
+    report: &Report,
+    thresholds: &PolicyThresholds,
+    recommendation: &Recommendation,
+) -> f32 {
+    let benefit = BASE_WEIGHT
+        + URGENCY_WEIGHT * urgency(report, thresholds, recommendation)
+        + RECLAIM_WEIGHT * reclaim_score(recommendation.estimated_impact.space_saving_bytes);
+    let score = recommendation.confidence.clamp(0.0, 1.0)
+        * risk_factor(&recommendation.risk_level)
+        * benefit;
+    (score * 1000.0).round() / 1000.0
+}
+
+/// Cost of acting on the recommendation: riskier actions need more benefit to rank high.
+fn risk_factor(risk_level: &RiskLevel) -> f32 {
+    match risk_level {
+        RiskLevel::Low => 1.0,
+        RiskLevel::Medium => 0.85,
+        RiskLevel::High => 0.7,
+    }
+}
+
+fn reclaim_score(space_saving_bytes: Option<u64>) -> f32 {
+    let Some(bytes) = space_saving_bytes.filter(|bytes| *bytes > 0) else {
+        return 0.0;
+    };
+    let scaled =
+        (bytes as f64 / MIN_RECLAIM_BYTES).log2() / (MAX_RECLAIM_BYTES / MIN_RECLAIM_BYTES).log2();
+    scaled.clamp(0.0, 1.0) as f32
+}
+
+/// Fullness, or projected fill date, of the most pressed disk the recommendation acts
+/// on. Destination and excluded-target disks do not count, only disks holding the
+/// affected data.
+fn urgency(report: &Report, thresholds: &PolicyThresholds, recommendation: &Recommendation) -> f32 {
+    recommendation
+        .evidence
+        .iter()
+        .filter(|evidence| {
+            evidence.kind != RecommendationEvidenceKind::Disk
+                || evidence.label == AFFECTED_DISK_LABEL
+        })
+        .filter_map(|evidence| {
+            evidence
+                .mount_point
+                .as_deref()
+                .and_then(|mount| report.disks.iter().find(|disk| disk.mount_point == mount))
+                .or_else(|| {
+                    evidence
+                        .path
+                        .as_deref()
+                        .and_then(|path| disk_for_path(&report.disks, path))
+                })
+        })
+        .filter(|disk| recommendation.target_mount.as_deref() != Some(disk.mount_point.as_str()))
+        .map(|disk| disk_urgency(disk).max(forecast_urgency(report, thresholds, disk)))
+        .fold(0.0, f32::max)
+}
+
+fn disk_for_path<'a>(disks: &'a [DiskInfo], path: &str) -> Option<&'a DiskInfo> {
+    disks
+        .iter()
+        .filter(|disk| {
+            // Reports from another platform keep their separators, so `C:\` is
+            // also matched as a plain prefix.
+            Path::new(path).starts_with(Path::new(&disk.mount_point))
+                || (disk.mount_point.ends_with(['\\', '/']) && path.starts_with(&disk.mount_point))
+        })
+        .max_by_key(|disk| disk.mount_point.len())
+}
+
+// Snapshot-held space and reserved blocks count as used, as in the headroom rules.
+fn disk_urgency(disk: &DiskInfo) -> f32 {
+    let Some(free_ratio) = effective_free_ratio(disk) else {
+        return 0.0;
+    };
+    let used_ratio = 1.0 - free_ratio;
+    ((used_ratio - URGENT_USED_RATIO) / (FULL_USED_RATIO - URGENT_USED_RATIO)).clamp(0.0, 1.0)
+        as f32
+}
+
+fn forecast_urgency(report: &Report, thresholds: &PolicyThresholds, disk: &DiskInfo) -> f32 {
+    let horizon = f64::from(thresholds.forecast_horizon_days);
+    if horizon <= 0.0 {
+        return 0.0;
+    }
+    report
+        .capacity_forecasts
+        .iter()
+        .find(|forecast| {
+            forecast.scope == ForecastScope::Disk && forecast.target == disk.mount_point
+        })
+        .and_then(|forecast| forecast.days_until_full)
+        .map_or(0.0, |days| (1.0 - days / horizon).clamp(0.0, 1.0) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::Report;
+    use crate::recommend::generate_recommendation_bundle;
+
+    #[test]
+    fn ranks_full_disk_warnings_above_advisories() {
+        let report: Report = serde_json::from_str(include_str!(
+            "../../../fixtures/os-headroom-cloud-report.json"
+        ))
+        .expect("fixture");
+        let bundle = generate_recommendation_bundle(&report);
+
+        let ranked: Vec<(&str, u32)> = bundle
+            .recommendations
+            .iter()
+            .map(|recommendation| (recommendation.id.as_str(), recommendation.rank))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![("os-headroom", 1), ("cloud-backed-target-exclusion", 2)]
+        );
+        let scores: Vec<f32> = bundle
+            .recommendations
+            .iter()
+            .map(|recommendation| recommendation.priority_score)
+            .collect();
+        assert!(scores[0] > scores[1] && scores[1] > 0.0);
+    }
+
+    #[test]
+    fn urgency_counts_snapshot_held_space_as_used() {
+        let mut report: Report = serde_json::from_str(include_str!(
+            "../../../fixtures/os-headroom-cloud-report.json"
+        ))
+        .expect("fixture");
+        let headroom = |report: &Report| {
+            generate_recommendation_bundle(report)
+                .recommendations
+                .into_iter()
+                .find(|recommendation| recommendation.id == "os-headroom")
+                .expect("os-headroom")
+        };
+        let raw = headroom(&report);
+
+        // 40 GiB is reported free, but snapshots leave only 2 GiB writable.
+        let os_disk = report
+            .disks
+            .iter_mut()
+            .find(|disk| disk.is_os_drive)
+            .expect("os disk");
+        os_disk.effective_free_space_bytes = Some(2 * 1024 * 1024 * 1024);
+        let effective = headroom(&report);
+
+        assert!(effective.priority_score > raw.priority_score);
+        assert!(effective
+            .evidence
+            .iter()
+            .any(|evidence| evidence.detail.contains("| 2147483648 free of")));
+    }
+}
@@ -3,16 +3,23 @@ use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
-use crate::analyzers::{run_analyzers, AnalyzerContext};
+use crate::analyzers::{AnalyzerContext, AnalyzerRegistry};
+use crate::custom_rules::run_custom_rules;
+use crate::device::{effective_free_ratio, effective_free_space_bytes, shares_storage_pool};
+use crate::health::health_concerns;
 use crate::model::{
     Category, DiskInfo, DiskStorageType, DuplicateIntentLabel, EstimatedImpact, LocalityClass,
-    PerformanceClass, Recommendation, RecommendationEvidence, RecommendationEvidenceKind, Report,
-    RiskLevel, RuleTrace, RuleTraceStatus,
+    PerformanceClass, PolicyProfile, PolicyThresholds, Recommendation, RecommendationEvidence,
+    RecommendationEvidenceKind, Report, RiskLevel, RuleTrace, RuleTraceStatus, TieringMove,
 };
 use crate::policy::enforce_recommendation_policies;
+use crate::policy_profile::{apply_profile_overrides, disabled_rule_trace};
+use crate::ranking::rank_recommendations;
+use crate::tiering::plan_cold_data_tiering;
 
-const OS_HEADROOM_MIN_RATIO: f64 = 0.15;
-const MIN_SOURCE_SCAN_COVERAGE_RATIO: f64 = 0.35;
+/// Evidence label for the disk whose free space a recommendation is about, as opposed
+/// to a destination or excluded disk. Ranking reads it to score urgency.
+pub(crate) const AFFECTED_DISK_LABEL: &str = "Affected disk";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RecommendationBundle {
@@ -20,81 +27,144 @@ pub struct RecommendationBundle {
     pub rule_traces: Vec<RuleTrace>,
     pub policy_decisions: Vec<crate::model::PolicyDecision>,
     pub contradiction_count: u64,
+    /// Cold-data moves whose `cold-data-tiering-*` recommendation survived policy.
+    #[serde(default)]
+    pub tiering_plan: Vec<TieringMove>,
 }
 
 pub fn generate_recommendations(report: &Report) -> Vec<Recommendation> {
     generate_recommendation_bundle(report).recommendations
 }
 
+/// Generates recommendations under the policy profile recorded in `report`, or the
+/// built-in defaults when the report has none.
 pub fn generate_recommendation_bundle(report: &Report) -> RecommendationBundle {
-    generate_recommendation_bundle_with_context(report, &AnalyzerContext::default())
+    let context = AnalyzerContext {
+        policy_profile: report
+            .policy_profile
+            .as_ref()
+            .map(|applied| applied.profile.clone())
+            .unwrap_or_default(),
+        ..AnalyzerContext::default()
+    };
+    generate_recommendation_bundle_with_context(report, &context, &AnalyzerRegistry::default())
 }
 
+/// Generates recommendations with an explicit analyzer context and registry, so
+/// embedders can add, replace or remove analyzers and pass them configuration.
 pub fn generate_recommendation_bundle_with_context(
     report: &Report,
     analyzer_context: &AnalyzerContext,
+    registry: &AnalyzerRegistry,
 ) -> RecommendationBundle {
+    let profile = &analyzer_context.policy_profile;
+    let thresholds = &profile.thresholds;
     let disk_scores = category_scores_by_disk(report);
     let mut candidates = Vec::new();
     let mut traces = Vec::new();
 
     emit_optional(
         "active_workload_placement",
-        active_workload_placement_rule(report, &disk_scores),
+        profile,
+        || active_workload_placement_rule(report, &disk_scores),
         &mut candidates,
         &mut traces,
     );
     emit_optional(
         "consolidation_opportunity",
-        consolidation_rule(report),
+        profile,
+        || consolidation_rule(report, thresholds),
+        &mut candidates,
+        &mut traces,
+    );
+    let tiering_moves = plan_cold_data_tiering(report, thresholds);
+    emit_many(
+        "cold_data_tiering",
+        profile,
+        || cold_data_tiering_rule(report, &tiering_moves),
         &mut candidates,
         &mut traces,
     );
     emit_many(
         "risky_disk",
-        risky_disk_rule(report, &disk_scores),
+        profile,
+        || risky_disk_rule(report, &disk_scores, thresholds),
+        &mut candidates,
+        &mut traces,
+    );
+    emit_many(
+        "disk_health",
+        profile,
+        || disk_health_rule(report, &disk_scores, thresholds),
         &mut candidates,
         &mut traces,
     );
     emit_optional(
         "backup_gap",
-        backup_gap_rule(report, &disk_scores),
+        profile,
+        || backup_gap_rule(report, &disk_scores),
         &mut candidates,
         &mut traces,
     );
     emit_optional(
         "duplicate_cleanup",
-        duplicate_cleanup_rule(report),
+        profile,
+        || duplicate_cleanup_rule(report, thresholds),
+        &mut candidates,
+        &mut traces,
+    );
+    emit_optional(
+        "similar_image_cleanup",
+        profile,
+        || similar_image_cleanup_rule(report, thresholds),
         &mut candidates,
         &mut traces,
     );
     emit_optional(
         "os_headroom",
-        os_headroom_rule(report, &disk_scores),
+        profile,
+        || os_headroom_rule(report, &disk_scores, thresholds),
         &mut candidates,
         &mut traces,
     );
     emit_optional(
         "cloud_exclusion_notice",
-        cloud_exclusion_notice_rule(report),
+        profile,
+        || cloud_exclusion_notice_rule(report),
         &mut candidates,
         &mut traces,
     );
-    for result in run_analyzers(report, analyzer_context) {
+    for result in registry.run(report, analyzer_context) {
         candidates.extend(result.recommendations);
         traces.extend(result.traces);
     }
+    let (custom_recommendations, custom_traces) = run_custom_rules(report, profile);
+    candidates.extend(custom_recommendations);
+    traces.extend(custom_traces);
+    let candidates = apply_profile_overrides(profile, candidates, &mut traces);
 
     let policy_outcome = enforce_recommendation_policies(report, candidates);
     traces.extend(policy_outcome.rejection_traces);
     let mut recommendations = policy_outcome.recommendations;
     enrich_recommendations(report, &mut recommendations);
+    rank_recommendations(report, thresholds, &mut recommendations);
+
+    // The plan only keeps moves whose destination passed every policy check.
+    let surviving_ids = recommendations
+        .iter()
+        .map(|recommendation| recommendation.id.as_str())
+        .collect::<HashSet<_>>();
+    let tiering_plan = tiering_moves
+        .into_iter()
+        .filter(|planned| surviving_ids.contains(planned.recommendation_id.as_str()))
+        .collect();
 
     RecommendationBundle {
         recommendations,
         rule_traces: traces,
         policy_decisions: policy_outcome.decisions,
         contradiction_count: policy_outcome.contradiction_count,
+        tiering_plan,
     }
 }
 
@@ -115,6 +185,29 @@ fn gather_recommendation_evidence(
 ) -> Vec<RecommendationEvidence> {
     let mut evidence = Vec::new();
 
+    let affected_disk = if recommendation.id == "os-headroom" {
+        report.disks.iter().find(|disk| disk.is_os_drive)
+    } else {
+        report.disks.iter().find(|disk| {
+            recommendation.id == format!("risky-disk-{}", sanitize_id(&disk.mount_point))
+        })
+    };
+    if let Some(disk) = affected_disk {
+        evidence.push(RecommendationEvidence {
+            kind: RecommendationEvidenceKind::Disk,
+            label: AFFECTED_DISK_LABEL.to_string(),
+            detail: format!(
+                "{} | {} free of {} bytes",
+                disk.name,
+                effective_free_space_bytes(disk),
+                disk.total_space_bytes
+            ),
+            path: None,
+            mount_point: Some(disk.mount_point.clone()),
+            duplicate_hash: None,
+        });
+    }
+
     if let Some(target_mount) = &recommendation.target_mount {
         if let Some(disk) = report
             .disks
@@ -136,6 +229,53 @@ fn gather_recommendation_evidence(
     }
 
     if recommendation.id.contains("duplicate") {
+        if let Some(group) = report
+            .duplicate_directories
+            .iter()
+            .filter(|group| group.intent.label == DuplicateIntentLabel::LikelyRedundant)
+            .max_by_key(|group| group.total_wasted_bytes)
+        {
+            evidence.push(RecommendationEvidence {
+                kind: RecommendationEvidenceKind::DuplicateDirectoryGroup,
+                label: "Largest duplicated directory tree".to_string(),
+                detail: format!(
+                    "{:?} match across {} director(ies), {:.0}% overlap, wasted {} bytes",
+                    group.match_kind,
+                    group.directories.len(),
+                    group.overlap_ratio * 100.0,
+                    group.total_wasted_bytes
+                ),
+                path: group
+                    .directories
+                    .first()
+                    .map(|directory| directory.path.clone()),
+                mount_point: group
+                    .directories
+                    .first()
+                    .and_then(|directory| directory.disk_mount.clone()),
+                duplicate_hash: group.tree_hash.clone(),
+            });
+        }
+        if let Some(extraction) = report
+            .archive_extractions
+            .iter()
+            .max_by_key(|extraction| extraction.archive_size_bytes)
+        {
+            evidence.push(RecommendationEvidence {
+                kind: RecommendationEvidenceKind::ArchiveExtraction,
+                label: "Largest fully extracted archive".to_string(),
+                detail: format!(
+                    "{:?} archive of {} bytes, {} member(s) extracted unchanged at {}",
+                    extraction.format,
+                    extraction.archive_size_bytes,
+                    extraction.member_count,
+                    extraction.extracted_path
+                ),
+                path: Some(extraction.archive_path.clone()),
+                mount_point: extraction.disk_mount.clone(),
+                duplicate_hash: None,
+            });
+        }
         if let Some(group) = report
             .duplicates
             .iter()
@@ -157,6 +297,31 @@ fn gather_recommendation_evidence(
         }
     }
 
+    if recommendation.id.starts_with("similar-image-") {
+        if let Some(group) = report
+            .similar_images
+            .iter()
+            .max_by_key(|group| group.lower_resolution_bytes)
+        {
+            let reference = &group.files[0];
+            evidence.push(RecommendationEvidence {
+                kind: RecommendationEvidenceKind::SimilarImageGroup,
+                label: "Largest similar image group".to_string(),
+                detail: format!(
+                    "{} image(s) within distance {}, reference {}x{}, lower-resolution copies {} bytes",
+                    group.files.len(),
+                    group.max_distance,
+                    reference.width,
+                    reference.height,
+                    group.lower_resolution_bytes
+                ),
+                path: Some(reference.path.clone()),
+                mount_point: reference.disk_mount.clone(),
+                duplicate_hash: Some(reference.perceptual_hash.clone()),
+            });
+        }
+    }
+
     if recommendation.id.starts_with("cleanup-") {
         if let Some(directory) = report
             .paths
@@ -272,6 +437,15 @@ fn recommendation_next_steps(recommendation: &Recommendation) -> Vec<String> {
         ];
     }
 
+    if recommendation.id.starts_with("similar-image-") {
+        return vec![
+            "Compare each lower-resolution copy against the reference image before cleanup."
+                .to_string(),
+            "Keep crops and edits you still need; perceptual matches ignore small changes."
+                .to_string(),
+        ];
+    }
+
     if recommendation.id.starts_with("cleanup-") {
         return vec![
             "Inspect the evidence paths and estimate the reclaimable space before cleanup."
@@ -290,11 +464,16 @@ fn recommendation_next_steps(recommendation: &Recommendation) -> Vec<String> {
 
 fn emit_optional(
     rule_id: &str,
-    recommendation: Option<Recommendation>,
+    profile: &PolicyProfile,
+    rule: impl FnOnce() -> Option<Recommendation>,
     out: &mut Vec<Recommendation>,
     traces: &mut Vec<RuleTrace>,
 ) {
-    if let Some(rec) = recommendation {
+    if profile.is_rule_disabled(rule_id) {
+        traces.push(disabled_rule_trace(rule_id));
+        return;
+    }
+    if let Some(rec) = rule() {
         traces.push(RuleTrace {
             rule_id: rule_id.to_string(),
             status: RuleTraceStatus::Emitted,
@@ -316,10 +495,16 @@ fn emit_optional(
 
 fn emit_many(
     rule_id: &str,
-    recommendations: Vec<Recommendation>,
+    profile: &PolicyProfile,
+    rule: impl FnOnce() -> Vec<Recommendation>,
     out: &mut Vec<Recommendation>,
     traces: &mut Vec<RuleTrace>,
 ) {
+    if profile.is_rule_disabled(rule_id) {
+        traces.push(disabled_rule_trace(rule_id));
+        return;
+    }
+    let recommendations = rule();
     if recommendations.is_empty() {
         traces.push(RuleTrace {
             rule_id: rule_id.to_string(),
@@ -361,7 +546,7 @@ fn active_workload_placement_rule(
 
     let mut candidate: Option<(&DiskInfo, f32)> = None;
     for source in eligible_non_os_local_targets(report) {
-        if source.mount_point == target.mount_point {
+        if source.mount_point == target.mount_point || shares_storage_pool(source, target) {
             continue;
         }
         let source_rank = performance_rank(source);
@@ -410,11 +595,18 @@ fn active_workload_placement_rule(
             ),
         },
         risk_level: RiskLevel::Low,
+        priority_score: 0.0,
+        rank: 0,
     })
 }
 
-fn consolidation_rule(report: &Report) -> Option<Recommendation> {
-    let eligible_targets = eligible_non_os_local_targets(report);
+fn consolidation_rule(report: &Report, thresholds: &PolicyThresholds) -> Option<Recommendation> {
+    // Pool children report their primary's used and free space, so only primaries
+    // take part; otherwise one pool could be consolidated onto itself.
+    let eligible_targets = eligible_non_os_local_targets(report)
+        .into_iter()
+        .filter(|disk| disk.parent_mount.is_none())
+        .collect::<Vec<_>>();
     if eligible_targets.len() < 2 {
         return None;
     }
@@ -424,10 +616,14 @@ fn consolidation_rule(report: &Report) -> Option<Recommendation> {
     let mut best_candidate: Option<(&DiskInfo, &DiskInfo, u64)> = None;
     for source in &eligible_targets {
         let source_used = used_space(source);
-        if source_used < 50 * 1024 * 1024 * 1024 {
+        if source_used < thresholds.consolidation_min_used_bytes {
             continue;
         }
-        if !has_sufficient_scan_coverage(source, observed_bytes.get(&source.mount_point).copied()) {
+        if !has_sufficient_scan_coverage(
+            source,
+            observed_bytes.get(&source.mount_point).copied(),
+            thresholds,
+        ) {
             continue;
         }
 
@@ -445,6 +641,7 @@ fn consolidation_rule(report: &Report) -> Option<Recommendation> {
             if !has_sufficient_scan_coverage(
                 target,
                 observed_bytes.get(&target.mount_point).copied(),
+                thresholds,
             ) {
                 continue;
             }
@@ -467,7 +664,7 @@ fn consolidation_rule(report: &Report) -> Option<Recommendation> {
                 continue;
             }
 
-            if target.free_space_bytes > (source_used as f64 * 1.25_f64) as u64 {
+            if effective_free_space_bytes(target) > (source_used as f64 * 1.25_f64) as u64 {
                 let score = source_used;
                 match best_candidate {
                     Some((_, _, best_score)) if best_score >= score => {}
@@ -502,12 +699,129 @@ fn consolidation_rule(report: &Report) -> Option<Recommendation> {
             ),
         },
         risk_level: RiskLevel::Medium,
+        priority_score: 0.0,
+        rank: 0,
     })
 }
 
+fn cold_data_tiering_rule(report: &Report, moves: &[TieringMove]) -> Vec<Recommendation> {
+    let mut by_target: Vec<(&str, Vec<&TieringMove>)> = Vec::new();
+    for planned in moves {
+        match by_target
+            .iter_mut()
+            .find(|(target, _)| *target == planned.target_mount)
+        {
+            Some((_, group)) => group.push(planned),
+            None => by_target.push((planned.target_mount.as_str(), vec![planned])),
+        }
+    }
+
+    by_target
+        .into_iter()
+        .map(|(target, group)| {
+            let total_bytes = group.iter().map(|planned| planned.bytes).sum::<u64>();
+            let mut source_mounts = group
+                .iter()
+                .map(|planned| planned.source_mount.as_str())
+                .collect::<Vec<_>>();
+            source_mounts.sort_unstable();
+            source_mounts.dedup();
+            let all_dated = group.iter().all(|planned| planned.last_modified.is_some());
+
+            let mut evidence = Vec::new();
+            for mount in &source_mounts {
+                if let Some(disk) = report.disks.iter().find(|disk| disk.mount_point == *mount) {
+                    evidence.push(RecommendationEvidence {
+                        kind: RecommendationEvidenceKind::Disk,
+                        label: AFFECTED_DISK_LABEL.to_string(),
+                        detail: format!(
+                            "{} | {} free of {} bytes | perf {:?}",
+                            disk.name,
+                            effective_free_space_bytes(disk),
+                            disk.total_space_bytes,
+                            disk.performance_class
+                        ),
+                        path: None,
+                        mount_point: Some(disk.mount_point.clone()),
+                        duplicate_hash: None,
+                    });
+                }
+            }
+            if let Some(disk) = report.disks.iter().find(|disk| disk.mount_point == target) {
+                evidence.push(RecommendationEvidence {
+                    kind: RecommendationEvidenceKind::Disk,
+                    label: "Target disk".to_string(),
+                    detail: format!(
+                        "{} | role {:?} | perf {:?} | {} free of {} bytes",
+                        disk.name,
+                        disk.role_hint.role,
+                        disk.performance_class,
+                        effective_free_space_bytes(disk),
+                        disk.total_space_bytes
+                    ),
+                    path: None,
+                    mount_point: Some(disk.mount_point.clone()),
+                    duplicate_hash: None,
+                });
+            }
+            for planned in &group {
+                evidence.push(RecommendationEvidence {
+                    kind: RecommendationEvidenceKind::Directory,
+                    label: "Cold directory".to_string(),
+                    detail: planned.rationale.clone(),
+                    path: Some(planned.source_path.clone()),
+                    mount_point: Some(planned.source_mount.clone()),
+                    duplicate_hash: None,
+                });
+            }
+
+            Recommendation {
+                id: group[0].recommendation_id.clone(),
+                title: format!("Tier cold directories onto {}", target),
+                rationale: format!(
+                    "{} cold director(ies) totalling {} on {} fit on slower eligible local disk {} with headroom to spare. The tiering plan lists each directory, its size and why it was picked.",
+                    group.len(),
+                    human_bytes(total_bytes),
+                    source_mounts.join(", "),
+                    target
+                ),
+                confidence: if all_dated { 0.72 } else { 0.6 },
+                target_mount: Some(target.to_string()),
+                policy_safe: true,
+                policy_rules_applied: vec!["safe_target_policy".to_string()],
+                policy_rules_blocked: Vec::new(),
+                evidence,
+                next_steps: vec![
+                    "Confirm nothing still reads from each listed directory before moving it."
+                        .to_string(),
+                    "Copy one directory at a time, verify the copy, then remove the original."
+                        .to_string(),
+                    "Update shortcuts, library paths and backup jobs that pointed at the old location."
+                        .to_string(),
+                ],
+                estimated_impact: EstimatedImpact {
+                    space_saving_bytes: Some(total_bytes),
+                    performance: Some(
+                        "Frees faster storage for active data; cold data reads slower after the move."
+                            .to_string(),
+                    ),
+                    risk_notes: Some(
+                        "Advisory plan only; verify backups before any manual migration."
+                            .to_string(),
+                    ),
+                },
+                risk_level: RiskLevel::Medium,
+                priority_score: 0.0,
+                rank: 0,
+            }
+        })
+        .collect()
+}
+
 fn risky_disk_rule(
     report: &Report,
     disk_scores: &HashMap<String, HashMap<Category, f32>>,
+    thresholds: &PolicyThresholds,
 ) -> Vec<Recommendation> {
     let mut output = Vec::new();
 
@@ -517,15 +831,19 @@ fn risky_disk_rule(
             LocalityClass::LocalPhysical | LocalityClass::Unknown
         )
     }) {
-        if disk.total_space_bytes == 0 {
+        // A pool's free space is reported once, on its primary mount.
+        if disk.parent_mount.is_some() {
             continue;
         }
-        let free_ratio = disk.free_space_bytes as f64 / disk.total_space_bytes as f64;
-        if free_ratio > 0.12 {
+        let Some(free_ratio) = effective_free_ratio(disk) else {
+            continue;
+        };
+        if free_ratio > thresholds.risky_disk_max_free_ratio {
             continue;
         }
 
-        let scores = disk_scores.get(&disk.mount_point);
+        let pool_scores = pool_category_scores(report, disk, disk_scores);
+        let scores = Some(&pool_scores);
         let important = score_sum(scores, &[Category::Work, Category::Games, Category::Media]);
         let has_backup = score_sum(scores, &[Category::Backup]) >= 0.6;
         if important < 0.8 || has_backup {
@@ -536,9 +854,10 @@ fn risky_disk_rule(
             id: format!("risky-disk-{}", sanitize_id(&disk.mount_point)),
             title: format!("Review low-free-space risk on {}", disk.mount_point),
             rationale: format!(
-                "Disk {} is low on free space ({:.1}% free) and appears to contain important active categories without clear backup indicators. Verify backup coverage and growth headroom.",
+                "Disk {} is low on free space ({:.1}% free) and appears to contain important active categories without clear backup indicators. Verify backup coverage and growth headroom.{}",
                 disk.mount_point,
-                free_ratio * 100.0
+                free_ratio * 100.0,
+                free_space_caveats(disk)
             ),
             confidence: 0.82,
             target_mount: None,
@@ -558,12 +877,145 @@ fn risky_disk_rule(
                 ),
             },
             risk_level: RiskLevel::High,
+            priority_score: 0.0,
+            rank: 0,
         });
     }
 
     output
 }
 
+/// Flags drives whose SMART data shows failure or heavy wear while they hold work or
+/// media data and no backup indicators.
+fn disk_health_rule(
+    report: &Report,
+    disk_scores: &HashMap<String, HashMap<Category, f32>>,
+    thresholds: &PolicyThresholds,
+) -> Vec<Recommendation> {
+    let mut output = Vec::new();
+
+    for disk in report
+        .disks
+        .iter()
+        .filter(|disk| disk.parent_mount.is_none())
+    {
+        let Some(health) = &disk.health else {
+            continue;
+        };
+        let (failing, worn) = health_concerns(health, thresholds);
+        if failing.is_empty() && worn.is_empty() {
+            continue;
+        }
+
+        let pool_scores = pool_category_scores(report, disk, disk_scores);
+        let scores = Some(&pool_scores);
+        let important = score_sum(scores, &[Category::Work, Category::Media]);
+        let has_backup = score_sum(scores, &[Category::Backup]) >= 0.6;
+        if important < 0.5 || has_backup {
+            continue;
+        }
+
+        let is_failing = !failing.is_empty();
+        let concerns = failing
+            .iter()
+            .chain(worn.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        let drive = health
+            .model
+            .as_deref()
+            .or(health.device.as_deref())
+            .unwrap_or(&disk.name);
+        output.push(Recommendation {
+            id: format!("disk-health-{}", sanitize_id(&disk.mount_point)),
+            title: if is_failing {
+                format!("Back up {} before its drive fails", disk.mount_point)
+            } else {
+                format!("Plan replacement of the worn drive behind {}", disk.mount_point)
+            },
+            rationale: format!(
+                "Drive {} behind {} reports {}. It holds work or media data without clear backup indicators, so a failure could lose the only copy.",
+                drive,
+                disk.mount_point,
+                concerns.join(", ")
+            ),
+            confidence: if is_failing { 0.9 } else { 0.75 },
+            target_mount: None,
+            policy_safe: true,
+            policy_rules_applied: vec!["safe_target_policy".to_string()],
+            policy_rules_blocked: Vec::new(),
+            evidence: vec![
+                RecommendationEvidence {
+                    kind: RecommendationEvidenceKind::Disk,
+                    label: AFFECTED_DISK_LABEL.to_string(),
+                    detail: format!(
+                        "{} | {} free of {} bytes",
+                        disk.name,
+                        effective_free_space_bytes(disk),
+                        disk.total_space_bytes
+                    ),
+                    path: None,
+                    mount_point: Some(disk.mount_point.clone()),
+                    duplicate_hash: None,
+                },
+                RecommendationEvidence {
+                    kind: RecommendationEvidenceKind::Disk,
+                    label: "Drive health".to_string(),
+                    detail: format!("{} | {}", health.source, concerns.join(" | ")),
+                    path: None,
+                    mount_point: Some(disk.mount_point.clone()),
+                    duplicate_hash: None,
+                },
+            ],
+            next_steps: vec![
+                "Copy irreplaceable work and media to another disk before anything else."
+                    .to_string(),
+                "Run a long SMART self-test and compare the counters with the next reading."
+                    .to_string(),
+                "Plan a replacement drive and avoid moving more data onto this one."
+                    .to_string(),
+            ],
+            estimated_impact: EstimatedImpact {
+                space_saving_bytes: None,
+                performance: None,
+                risk_notes: Some(
+                    "Heavy reads can push a failing drive over the edge; copy the most important data first."
+                        .to_string(),
+                ),
+            },
+            risk_level: if is_failing {
+                RiskLevel::High
+            } else {
+                RiskLevel::Medium
+            },
+            priority_score: 0.0,
+            rank: 0,
+        });
+    }
+
+    output
+}
+
+/// Category scores of a disk together with the pool members that share its free
+/// space, taking the strongest signal per category.
+fn pool_category_scores(
+    report: &Report,
+    disk: &DiskInfo,
+    disk_scores: &HashMap<String, HashMap<Category, f32>>,
+) -> HashMap<Category, f32> {
+    let mut merged = HashMap::new();
+    for member in report.disks.iter().filter(|member| {
+        member.mount_point == disk.mount_point
+            || member.parent_mount.as_deref() == Some(disk.mount_point.as_str())
+    }) {
+        for (category, score) in disk_scores.get(&member.mount_point).into_iter().flatten() {
+            let entry = merged.entry(category.clone()).or_insert(0.0_f32);
+            *entry = entry.max(*score);
+        }
+    }
+    merged
+}
+
 fn backup_gap_rule(
     report: &Report,
     disk_scores: &HashMap<String, HashMap<Category, f32>>,
@@ -616,43 +1068,126 @@ fn backup_gap_rule(
                 ),
             },
             risk_level: RiskLevel::High,
+            priority_score: 0.0,
+            rank: 0,
         });
     }
 
     None
 }
 
-fn duplicate_cleanup_rule(report: &Report) -> Option<Recommendation> {
+fn duplicate_cleanup_rule(
+    report: &Report,
+    thresholds: &PolicyThresholds,
+) -> Option<Recommendation> {
+    // A copied folder is one decision rather than one per file, so file groups that
+    // sit entirely inside a redundant duplicated tree are folded into that finding.
+    let redundant_trees = report
+        .duplicate_directories
+        .iter()
+        .filter(|group| {
+            group.intent.label == DuplicateIntentLabel::LikelyRedundant
+                && group.total_wasted_bytes >= thresholds.duplicate_min_group_wasted_bytes
+        })
+        .collect::<Vec<_>>();
+    let tree_paths = redundant_trees
+        .iter()
+        .flat_map(|group| group.directories.iter())
+        .map(|directory| Path::new(&directory.path))
+        .collect::<Vec<_>>();
     let redundant_groups = report
         .duplicates
         .iter()
         .filter(|group| {
             group.intent.label == DuplicateIntentLabel::LikelyRedundant
-                && group.total_wasted_bytes >= 64 * 1024 * 1024
+                && group.total_wasted_bytes >= thresholds.duplicate_min_group_wasted_bytes
+        })
+        .filter(|group| {
+            !group.files.iter().all(|file| {
+                tree_paths
+                    .iter()
+                    .any(|tree| Path::new(&file.path).starts_with(tree))
+            })
+        })
+        .collect::<Vec<_>>();
+    // An archive whose members are all present unchanged in an extracted folder is
+    // a redundant copy of that folder.
+    let extracted_archives = report
+        .archive_extractions
+        .iter()
+        .filter(|extraction| {
+            extraction.archive_size_bytes >= thresholds.duplicate_min_group_wasted_bytes
         })
         .collect::<Vec<_>>();
 
-    if redundant_groups.is_empty() {
+    if redundant_trees.is_empty() && extracted_archives.is_empty() && redundant_groups.is_empty() {
         return None;
     }
 
-    let total_wasted = redundant_groups
+    let tree_wasted = redundant_trees
         .iter()
         .map(|group| group.total_wasted_bytes)
         .sum::<u64>();
+    let file_wasted = redundant_groups
+        .iter()
+        .map(|group| group.total_wasted_bytes)
+        .sum::<u64>();
+    let archive_wasted = extracted_archives
+        .iter()
+        .map(|extraction| extraction.archive_size_bytes)
+        .sum::<u64>();
+    let total_wasted = tree_wasted
+        .saturating_add(archive_wasted)
+        .saturating_add(file_wasted);
 
-    if total_wasted < 256 * 1024 * 1024 {
+    if total_wasted < thresholds.duplicate_cleanup_min_total_bytes {
         return None;
     }
 
-    Some(Recommendation {
-        id: "duplicate-cleanup-candidate".to_string(),
-        title: "Review duplicate cleanup candidates".to_string(),
-        rationale: format!(
+    let mut findings = Vec::new();
+    if let Some(largest) = redundant_trees.first() {
+        findings.push(format!(
+            "{} duplicated directory tree(s) account for about {} (largest at {})",
+            redundant_trees.len(),
+            human_bytes(tree_wasted),
+            largest.directories[0].path
+        ));
+    }
+    if let Some(largest) = extracted_archives.first() {
+        findings.push(format!(
+            "{} archive(s) already fully extracted in the scan account for about {} (largest {} at {})",
+            extracted_archives.len(),
+            human_bytes(archive_wasted),
+            largest.archive_path,
+            largest.extracted_path
+        ));
+    }
+    let rationale = if findings.is_empty() {
+        format!(
             "{} redundant duplicate group(s) account for about {} of potential reclaimable space. Review each set before manual cleanup.",
             redundant_groups.len(),
             human_bytes(total_wasted)
-        ),
+        )
+    } else {
+        let (scope, subject) = match (redundant_trees.is_empty(), extracted_archives.is_empty()) {
+            (false, true) => ("outside those trees ", "tree"),
+            (true, false) => ("", "archive"),
+            _ => ("outside those trees ", "tree and archive"),
+        };
+        format!(
+            "{}, and {} redundant duplicate group(s) {}add about {}. Review each {} as a whole before manual cleanup.",
+            findings.join(", "),
+            redundant_groups.len(),
+            scope,
+            human_bytes(file_wasted),
+            subject
+        )
+    };
+
+    Some(Recommendation {
+        id: "duplicate-cleanup-candidate".to_string(),
+        title: "Review duplicate cleanup candidates".to_string(),
+        rationale,
         confidence: 0.7,
         target_mount: None,
         policy_safe: true,
@@ -663,22 +1198,84 @@ fn duplicate_cleanup_rule(report: &Report) -> Option<Recommendation> {
         estimated_impact: EstimatedImpact {
             space_saving_bytes: Some(total_wasted),
             performance: Some("Potential capacity relief and reduced indexing load.".to_string()),
-            risk_notes: Some("Validate ownership and backup expectations before removal.".to_string()),
+            risk_notes: Some(
+                "Validate ownership and backup expectations before removal.".to_string(),
+            ),
+        },
+        risk_level: RiskLevel::Medium,
+        priority_score: 0.0,
+        rank: 0,
+    })
+}
+
+fn similar_image_cleanup_rule(
+    report: &Report,
+    thresholds: &PolicyThresholds,
+) -> Option<Recommendation> {
+    let groups = report
+        .similar_images
+        .iter()
+        .filter(|group| group.lower_resolution_bytes > 0)
+        .collect::<Vec<_>>();
+    let reclaimable = groups
+        .iter()
+        .map(|group| group.lower_resolution_bytes)
+        .sum::<u64>();
+    if reclaimable < thresholds.similar_image_min_reclaimable_bytes {
+        return None;
+    }
+
+    let copies = groups
+        .iter()
+        .map(|group| {
+            let reference_pixels =
+                u64::from(group.files[0].width) * u64::from(group.files[0].height);
+            group
+                .files
+                .iter()
+                .filter(|file| u64::from(file.width) * u64::from(file.height) < reference_pixels)
+                .count()
+        })
+        .sum::<usize>();
+
+    Some(Recommendation {
+        id: "similar-image-cleanup-candidate".to_string(),
+        title: "Review lower-resolution copies of similar images".to_string(),
+        rationale: format!(
+            "{} visually similar image group(s) contain {} lower-resolution copy(ies) using about {}. A higher-resolution version of each is kept elsewhere in the scan; review before manual cleanup.",
+            groups.len(),
+            copies,
+            human_bytes(reclaimable)
+        ),
+        confidence: 0.62,
+        target_mount: None,
+        policy_safe: true,
+        policy_rules_applied: vec!["safe_target_policy".to_string()],
+        policy_rules_blocked: Vec::new(),
+        evidence: Vec::new(),
+        next_steps: Vec::new(),
+        estimated_impact: EstimatedImpact {
+            space_saving_bytes: Some(reclaimable),
+            performance: None,
+            risk_notes: Some(
+                "Perceptual matches are approximate; resized copies may be intentional exports."
+                    .to_string(),
+            ),
         },
         risk_level: RiskLevel::Medium,
+        priority_score: 0.0,
+        rank: 0,
     })
 }
 
 fn os_headroom_rule(
     report: &Report,
     disk_scores: &HashMap<String, HashMap<Category, f32>>,
+    thresholds: &PolicyThresholds,
 ) -> Option<Recommendation> {
     let os_disk = report.disks.iter().find(|disk| disk.is_os_drive)?;
-    if os_disk.total_space_bytes == 0 {
-        return None;
-    }
-    let free_ratio = os_disk.free_space_bytes as f64 / os_disk.total_space_bytes as f64;
-    if free_ratio >= OS_HEADROOM_MIN_RATIO {
+    let free_ratio = effective_free_ratio(os_disk)?;
+    if free_ratio >= thresholds.os_headroom_min_free_ratio {
         return None;
     }
 
@@ -689,10 +1286,11 @@ fn os_headroom_rule(
         id: "os-headroom".to_string(),
         title: "Protect OS drive free-space headroom".to_string(),
         rationale: format!(
-            "OS drive {} is at {:.1}% free, below the {:.0}% safety threshold. Review cold data placement and preserve headroom for updates, paging, and recovery workflows.",
+            "OS drive {} is at {:.1}% free, below the {:.0}% safety threshold. Review cold data placement and preserve headroom for updates, paging, and recovery workflows.{}",
             os_disk.mount_point,
             free_ratio * 100.0,
-            OS_HEADROOM_MIN_RATIO * 100.0
+            thresholds.os_headroom_min_free_ratio * 100.0,
+            free_space_caveats(os_disk)
         ),
         confidence: if cold_score > 0.6 { 0.86 } else { 0.72 },
         target_mount: None,
@@ -712,9 +1310,32 @@ fn os_headroom_rule(
             ),
         },
         risk_level: RiskLevel::High,
+        priority_score: 0.0,
+        rank: 0,
     })
 }
 
+/// Filesystem details that change what freeing space involves, appended to
+/// low-space rationales.
+fn free_space_caveats(disk: &DiskInfo) -> String {
+    let mut caveats = String::new();
+    if let Some(snapshot_bytes) = disk.snapshot_bytes.filter(|bytes| *bytes > 0) {
+        caveats.push_str(&format!(
+            " Snapshots hold {}; deleting files frees nothing until they are pruned.",
+            human_bytes(snapshot_bytes)
+        ));
+    }
+    let used = used_space(disk);
+    if let Some(logical) = disk.logical_used_bytes.filter(|bytes| *bytes > used) {
+        caveats.push_str(&format!(
+            " Data is stored compressed ({} logical in {}); moving it to an uncompressed disk needs the logical size.",
+            human_bytes(logical),
+            human_bytes(used)
+        ));
+    }
+    caveats
+}
+
 fn cloud_exclusion_notice_rule(report: &Report) -> Option<Recommendation> {
     let cloud_disks = report
         .disks
@@ -753,6 +1374,8 @@ fn cloud_exclusion_notice_rule(report: &Report) -> Option<Recommendation> {
             ),
         },
         risk_level: RiskLevel::Low,
+        priority_score: 0.0,
+        rank: 0,
     })
 }
 
@@ -806,10 +1429,11 @@ fn eligible_non_os_local_targets(report: &Report) -> Vec<&DiskInfo> {
 fn fastest_eligible_disk(report: &Report) -> Option<&DiskInfo> {
     eligible_non_os_local_targets(report)
         .into_iter()
+        .filter(|disk| disk.parent_mount.is_none())
         .max_by(|a, b| performance_rank(a).total_cmp(&performance_rank(b)))
 }
 
-fn performance_rank(disk: &DiskInfo) -> f32 {
+pub(crate) fn performance_rank(disk: &DiskInfo) -> f32 {
     let base = match disk.performance_class {
         PerformanceClass::Fast => 3.0,
         PerformanceClass::Balanced => 2.0,
@@ -869,7 +1493,11 @@ fn observed_bytes_by_disk(report: &Report) -> HashMap<String, u64> {
     totals
 }
 
-fn has_sufficient_scan_coverage(disk: &DiskInfo, observed_bytes: Option<u64>) -> bool {
+fn has_sufficient_scan_coverage(
+    disk: &DiskInfo,
+    observed_bytes: Option<u64>,
+    thresholds: &PolicyThresholds,
+) -> bool {
     let Some(observed_bytes) = observed_bytes else {
         return false;
     };
@@ -877,10 +1505,10 @@ fn has_sufficient_scan_coverage(disk: &DiskInfo, observed_bytes: Option<u64>) ->
     if used == 0 {
         return false;
     }
-    (observed_bytes as f64 / used as f64) >= MIN_SOURCE_SCAN_COVERAGE_RATIO
+    (observed_bytes as f64 / used as f64) >= thresholds.min_source_scan_coverage_ratio
 }
 
-fn sanitize_id(value: &str) -> String {
+pub(crate) fn sanitize_id(value: &str) -> String {
     value
         .chars()
         .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
@@ -891,9 +1519,13 @@ fn sanitize_id(value: &str) -> String {
 mod tests {
     use super::{generate_recommendation_bundle, generate_recommendations};
     use crate::model::{
-        CategorySuggestion, DiskInfo, DiskKind, DiskStorageType, LocalityClass, PerformanceClass,
-        Report, ScanBackendKind, ScanMetrics,
+        ArchiveExtraction, ArchiveFormat, CategorySuggestion, DirectoryMatchKind, DiskInfo,
+        DiskKind, DiskStorageType, DuplicateDirectory, DuplicateDirectoryGroup, DuplicateFile,
+        DuplicateGroup, DuplicateIntent, DuplicateIntentLabel, LocalityClass, PerformanceClass,
+        PolicyProfile, RecommendationEvidenceKind, Report, RiskLevel, ScanBackendKind, ScanMetrics,
+        SimilarImageFile, SimilarImageGroup, SizeMode,
     };
+    use crate::policy_profile::apply_policy_profile;
 
     #[test]
     fn fixture_triggers_expected_recommendation_ids() {
@@ -981,6 +1613,15 @@ mod tests {
                 min_ratio: None,
                 emit_progress_events: false,
                 progress_interval_ms: 250,
+                directory_tree: false,
+                directory_tree_min_bytes: 0,
+                size_mode: SizeMode::Apparent,
+                similar_images: false,
+                similar_image_max_distance: 0,
+                dedupe_directories: false,
+                dedupe_directory_min_overlap: 0.0,
+                dedupe_archives: false,
+                disk_inventory: None,
             },
             scan_metrics: ScanMetrics::default(),
             scan_progress_summary: crate::model::ScanProgressSummary::default(),
@@ -1009,6 +1650,9 @@ mod tests {
                         stale_files: 0,
                         unknown_modified_files: 0,
                     },
+                    directory_tree: None,
+                    hardlinked_files: 0,
+                    hardlink_savings_bytes: 0,
                 },
                 crate::model::PathStats {
                     root_path: "G:\\".to_string(),
@@ -1032,6 +1676,9 @@ mod tests {
                         stale_files: 0,
                         unknown_modified_files: 0,
                     },
+                    directory_tree: None,
+                    hardlinked_files: 0,
+                    hardlink_savings_bytes: 0,
                 },
             ],
             categories: vec![
@@ -1053,6 +1700,14 @@ mod tests {
                 },
             ],
             duplicates: Vec::new(),
+            duplicate_directories: Vec::new(),
+            archive_extractions: Vec::new(),
+            similar_images: Vec::new(),
+            git_repositories: Vec::new(),
+            dev_projects: None,
+            capacity_forecasts: Vec::new(),
+            tiering_plan: Vec::new(),
+            policy_profile: None,
             recommendations: Vec::new(),
             policy_decisions: Vec::new(),
             rule_traces: Vec::new(),
@@ -1066,6 +1721,174 @@ mod tests {
         assert!(!ids.iter().any(|id| id == "consolidation-opportunity"));
     }
 
+    #[test]
+    fn consolidation_sizes_targets_by_effective_free_space() {
+        let source = disk(
+            "Data",
+            "D:\\",
+            DiskStorageType::Ssd,
+            LocalityClass::LocalPhysical,
+            false,
+            true,
+            1_000_000_000_000,
+            900_000_000_000,
+        );
+        let target = disk(
+            "Bulk",
+            "E:\\",
+            DiskStorageType::Hdd,
+            LocalityClass::LocalPhysical,
+            false,
+            true,
+            1_000_000_000_000,
+            200_000_000_000,
+        );
+        let template: Report =
+            serde_json::from_str(include_str!("../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        let mut report = minimal_report(vec![source, target]);
+        report.paths = [("D:\\", 100_000_000_000), ("E:\\", 800_000_000_000)]
+            .into_iter()
+            .map(|(mount, total_size_bytes)| crate::model::PathStats {
+                root_path: mount.to_string(),
+                disk_mount: Some(mount.to_string()),
+                total_size_bytes,
+                ..template.paths[0].clone()
+            })
+            .collect();
+        let consolidates = |report: &Report| {
+            generate_recommendations(report)
+                .iter()
+                .any(|item| item.id == "consolidation-opportunity")
+        };
+        assert!(consolidates(&report));
+
+        // Snapshots pin most of the target's free space, leaving less than the 100 GB to move.
+        report.disks[1].effective_free_space_bytes = Some(100_000_000_000);
+        assert!(!consolidates(&report));
+    }
+
+    #[test]
+    fn os_headroom_uses_effective_free_space() {
+        let mut os = disk(
+            "System",
+            "/",
+            DiskStorageType::Nvme,
+            LocalityClass::LocalPhysical,
+            true,
+            false,
+            100_000_000_000,
+            18_000_000_000,
+        );
+        let ids = |report: &Report| {
+            generate_recommendations(report)
+                .into_iter()
+                .map(|item| (item.id, item.rationale))
+                .collect::<Vec<_>>()
+        };
+        assert!(!ids(&minimal_report(vec![os.clone()]))
+            .iter()
+            .any(|(id, _)| id == "os-headroom"));
+
+        // ext4 keeps 5% for root, and only 9 GB of the 95 GB left is writable.
+        os.reserved_bytes = Some(5_000_000_000);
+        os.effective_free_space_bytes = Some(9_000_000_000);
+        os.snapshot_bytes = Some(20_000_000_000);
+        let recommendations = ids(&minimal_report(vec![os]));
+        let (_, rationale) = recommendations
+            .iter()
+            .find(|(id, _)| id == "os-headroom")
+            .expect("os-headroom");
+        assert!(rationale.contains("9.5% free"));
+        assert!(rationale.contains("Snapshots hold"));
+    }
+
+    #[test]
+    fn disk_health_flags_failing_and_worn_drives_holding_unbacked_data() {
+        let health = |fixture: &str, source: &str| {
+            Some(crate::health::parse_smartctl_json(fixture, source).expect("fixture"))
+        };
+        let mut failing = disk(
+            "Media",
+            "D:\\",
+            DiskStorageType::Hdd,
+            LocalityClass::LocalPhysical,
+            false,
+            true,
+            4_000_000_000_000,
+            2_000_000_000_000,
+        );
+        failing.health = health(
+            include_str!("../../../fixtures/smartctl-sda-failing-hdd.json"),
+            "sda-failing-hdd.json",
+        );
+        let mut worn = disk(
+            "Work",
+            "E:\\",
+            DiskStorageType::Ssd,
+            LocalityClass::LocalPhysical,
+            false,
+            true,
+            1_000_000_000_000,
+            500_000_000_000,
+        );
+        worn.health = health(
+            include_str!("../../../fixtures/smartctl-sdb-worn-ssd.json"),
+            "sdb-worn-ssd.json",
+        );
+        let mut backed_up = worn.clone();
+        backed_up.mount_point = "F:\\".to_string();
+        let mut healthy = worn.clone();
+        healthy.mount_point = "G:\\".to_string();
+        healthy.health = health(
+            include_str!("../../../fixtures/smartctl-nvme0-healthy.json"),
+            "nvme0-healthy.json",
+        );
+
+        let mut report = minimal_report(vec![failing, worn, backed_up, healthy]);
+        let suggestion = |mount: &str, category: crate::model::Category| CategorySuggestion {
+            target: mount.to_string(),
+            disk_mount: Some(mount.to_string()),
+            category,
+            confidence: 0.9,
+            rationale: "test".to_string(),
+            evidence: Vec::new(),
+        };
+        report.categories = vec![
+            suggestion("D:\\", crate::model::Category::Media),
+            suggestion("E:\\", crate::model::Category::Work),
+            suggestion("F:\\", crate::model::Category::Work),
+            suggestion("F:\\", crate::model::Category::Backup),
+            suggestion("G:\\", crate::model::Category::Work),
+        ];
+
+        let health_recommendations = generate_recommendations(&report)
+            .into_iter()
+            .filter(|item| item.id.starts_with("disk-health-"))
+            .collect::<Vec<_>>();
+        let ids = health_recommendations
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids.len(), 2, "{ids:?}");
+        let failing = health_recommendations
+            .iter()
+            .find(|item| item.id == "disk-health-D--")
+            .expect("failing drive flagged");
+        assert_eq!(failing.risk_level, RiskLevel::High);
+        assert!(failing.rationale.contains("1848 reallocated sector(s)"));
+        assert!(failing
+            .evidence
+            .iter()
+            .any(|evidence| evidence.detail.starts_with("sda-failing-hdd.json")));
+        let worn = health_recommendations
+            .iter()
+            .find(|item| item.id == "disk-health-E--")
+            .expect("worn drive flagged");
+        assert_eq!(worn.risk_level, RiskLevel::Medium);
+        assert!(worn.rationale.contains("91% of rated endurance used"));
+    }
+
     #[test]
     fn bundle_contains_rule_traces() {
         let fixture = include_str!("../../../fixtures/sample-report.json");
@@ -1075,6 +1898,199 @@ mod tests {
         assert!(!bundle.policy_decisions.is_empty());
     }
 
+    #[test]
+    fn similar_image_rule_counts_only_lower_resolution_copies() {
+        let image = |path: &str, width: u32, height: u32, size_bytes: u64| SimilarImageFile {
+            path: path.to_string(),
+            disk_mount: Some("D:\\".to_string()),
+            size_bytes,
+            width,
+            height,
+            perceptual_hash: "0f0f0f0f0f0f0f0f".to_string(),
+            distance: 0,
+        };
+        let mut report = minimal_report(Vec::new());
+        report.similar_images = vec![SimilarImageGroup {
+            files: vec![
+                image("D:\\Photos\\a.jpg", 4000, 3000, 8_000_000),
+                image("D:\\Backup\\a.jpg", 4000, 3000, 8_000_000),
+                image("D:\\Exports\\a-small.jpg", 1600, 1200, 20_000_000),
+            ],
+            max_distance: 2,
+            lower_resolution_bytes: 20_000_000,
+        }];
+
+        let bundle = generate_recommendation_bundle(&report);
+        let recommendation = bundle
+            .recommendations
+            .iter()
+            .find(|item| item.id == "similar-image-cleanup-candidate")
+            .expect("similar image recommendation");
+        assert_eq!(
+            recommendation.estimated_impact.space_saving_bytes,
+            Some(20_000_000)
+        );
+        assert!(recommendation.rationale.contains("1 lower-resolution"));
+        assert!(recommendation
+            .evidence
+            .iter()
+            .any(|item| item.path.as_deref() == Some("D:\\Photos\\a.jpg")));
+    }
+
+    #[test]
+    fn duplicate_cleanup_prefers_directory_trees_over_their_file_groups() {
+        const MIB: u64 = 1024 * 1024;
+        let redundant = || DuplicateIntent {
+            label: DuplicateIntentLabel::LikelyRedundant,
+            rationale: "test".to_string(),
+        };
+        let file_group = |paths: [&str; 2], size_bytes: u64| DuplicateGroup {
+            size_bytes,
+            hash: paths[0].to_string(),
+            files: paths
+                .iter()
+                .map(|path| DuplicateFile {
+                    path: path.to_string(),
+                    disk_mount: Some("D:\\".to_string()),
+                    modified: None,
+                })
+                .collect(),
+            total_wasted_bytes: size_bytes,
+            intent: redundant(),
+        };
+        let directory = |path: &str| DuplicateDirectory {
+            path: path.to_string(),
+            disk_mount: Some("D:\\".to_string()),
+            file_count: 40_000,
+            size_bytes: 1024 * MIB,
+        };
+
+        let mut report = minimal_report(Vec::new());
+        report.duplicate_directories = vec![DuplicateDirectoryGroup {
+            match_kind: DirectoryMatchKind::Identical,
+            tree_hash: Some("tree".to_string()),
+            directories: vec![directory("/data/Copy/Project"), directory("/data/Project")],
+            overlap_ratio: 1.0,
+            total_wasted_bytes: 1024 * MIB,
+            intent: redundant(),
+        }];
+        report.duplicates = vec![
+            file_group(
+                ["/data/Copy/Project/data.bin", "/data/Project/data.bin"],
+                200 * MIB,
+            ),
+            file_group(["/data/Movies/a.mkv", "/data/Old/a.mkv"], 300 * MIB),
+        ];
+
+        let recommendation = generate_recommendations(&report)
+            .into_iter()
+            .find(|item| item.id == "duplicate-cleanup-candidate")
+            .expect("duplicate recommendation");
+        assert_eq!(
+            recommendation.estimated_impact.space_saving_bytes,
+            Some(1324 * MIB)
+        );
+        assert!(recommendation
+            .rationale
+            .starts_with("1 duplicated directory tree(s)"));
+        assert!(recommendation
+            .rationale
+            .contains("1 redundant duplicate group(s) outside"));
+        assert_eq!(
+            recommendation.evidence[0].path.as_deref(),
+            Some("/data/Copy/Project")
+        );
+    }
+
+    #[test]
+    fn recorded_policy_profile_changes_thresholds_rules_and_risk_levels() {
+        let os = disk(
+            "System",
+            "C:\\",
+            DiskStorageType::Ssd,
+            LocalityClass::LocalPhysical,
+            true,
+            false,
+            1_000_000_000_000,
+            200_000_000_000,
+        );
+        let data = disk(
+            "Data",
+            "D:\\",
+            DiskStorageType::Hdd,
+            LocalityClass::LocalPhysical,
+            false,
+            true,
+            1_000_000_000_000,
+            500_000_000_000,
+        );
+        let mut report = minimal_report(vec![os, data]);
+        let ids = generate_recommendations(&report)
+            .into_iter()
+            .map(|item| item.id)
+            .collect::<Vec<_>>();
+        assert!(!ids.iter().any(|id| id == "os-headroom"));
+        assert!(ids.iter().any(|id| id == "backup-gap"));
+
+        let mut profile = PolicyProfile::default();
+        profile.thresholds.os_headroom_min_free_ratio = 0.25;
+        profile.disabled_rules = vec!["backup_gap".to_string()];
+        profile
+            .risk_levels
+            .insert("os-headroom".to_string(), RiskLevel::Medium);
+        report.policy_profile = Some(apply_policy_profile(None, profile));
+
+        let bundle = generate_recommendation_bundle(&report);
+        let headroom = bundle
+            .recommendations
+            .iter()
+            .find(|item| item.id == "os-headroom")
+            .expect("os headroom under stricter profile");
+        assert_eq!(headroom.risk_level, RiskLevel::Medium);
+        assert!(headroom
+            .rationale
+            .contains("below the 25% safety threshold"));
+        assert!(!bundle
+            .recommendations
+            .iter()
+            .any(|item| item.id == "backup-gap"));
+        assert!(bundle.rule_traces.iter().any(|trace| {
+            trace.rule_id == "backup_gap" && trace.detail == "Rule disabled by policy profile."
+        }));
+    }
+
+    #[test]
+    fn duplicate_cleanup_counts_archives_that_are_already_extracted() {
+        const MIB: u64 = 1024 * 1024;
+        let mut report = minimal_report(Vec::new());
+        report.archive_extractions = vec![ArchiveExtraction {
+            archive_path: "/data/Downloads/dataset.zip".to_string(),
+            disk_mount: Some("/data".to_string()),
+            format: ArchiveFormat::Zip,
+            archive_size_bytes: 300 * MIB,
+            extracted_path: "/data/Datasets/dataset".to_string(),
+            member_count: 1_200,
+            extracted_bytes: 900 * MIB,
+        }];
+
+        let recommendation = generate_recommendations(&report)
+            .into_iter()
+            .find(|item| item.id == "duplicate-cleanup-candidate")
+            .expect("duplicate recommendation");
+        assert_eq!(
+            recommendation.estimated_impact.space_saving_bytes,
+            Some(300 * MIB)
+        );
+        assert!(recommendation
+            .rationale
+            .contains("1 archive(s) already fully extracted"));
+        assert!(recommendation.rationale.contains("/data/Datasets/dataset"));
+        assert!(recommendation.evidence.iter().any(|item| {
+            item.kind == RecommendationEvidenceKind::ArchiveExtraction
+                && item.path.as_deref() == Some("/data/Downloads/dataset.zip")
+        }));
+    }
+
     fn minimal_report(disks: Vec<DiskInfo>) -> Report {
         Report {
             report_version: "1.2.0".to_string(),
@@ -1092,6 +2108,15 @@ mod tests {
                 min_ratio: None,
                 emit_progress_events: false,
                 progress_interval_ms: 250,
+                directory_tree: false,
+                directory_tree_min_bytes: 0,
+                size_mode: SizeMode::Apparent,
+                similar_images: false,
+                similar_image_max_distance: 0,
+                dedupe_directories: false,
+                dedupe_directory_min_overlap: 0.0,
+                dedupe_archives: false,
+                disk_inventory: None,
             },
             scan_metrics: ScanMetrics::default(),
             scan_progress_summary: crate::model::ScanProgressSummary::default(),
@@ -1107,6 +2132,14 @@ mod tests {
                 evidence: vec!["work".to_string()],
             }],
             duplicates: Vec::new(),
+            duplicate_directories: Vec::new(),
+            archive_extractions: Vec::new(),
+            similar_images: Vec::new(),
+            git_repositories: Vec::new(),
+            dev_projects: None,
+            capacity_forecasts: Vec::new(),
+            tiering_plan: Vec::new(),
+            policy_profile: None,
             recommendations: Vec::new(),
             policy_decisions: Vec::new(),
             rule_traces: Vec::new(),
@@ -1151,6 +2184,15 @@ mod tests {
             metadata_notes: Vec::new(),
             role_hint: Default::default(),
             target_role_eligibility: Vec::new(),
+            device_id: None,
+            mount_kind: Default::default(),
+            storage_pool: None,
+            parent_mount: None,
+            reserved_bytes: None,
+            snapshot_bytes: None,
+            logical_used_bytes: None,
+            effective_free_space_bytes: None,
+            health: None,
         }
     }
 }
@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::growth::{appeared_large_files, directory_growth, extension_growth, path_snapshot};
 use crate::model::{
     DiskDiff, PathDiff, Recommendation, RecommendationChange, RecommendationChangeKind, Report,
     ReportDiff, ReportImportResult, ReportSummary,
@@ -230,14 +231,7 @@ fn build_path_diffs(left: &Report, right: &Report) -> Vec<PathDiff> {
                 right_path.map(|path| path.file_count),
             );
 
-            if bytes_delta == 0
-                && file_count_delta == 0
-                && left_path.is_some() == right_path.is_some()
-            {
-                return None;
-            }
-
-            Some(PathDiff {
+            let mut diff = PathDiff {
                 root_path,
                 left_total_size_bytes: left_path.map(|path| path.total_size_bytes),
                 right_total_size_bytes: right_path.map(|path| path.total_size_bytes),
@@ -245,7 +239,28 @@ fn build_path_diffs(left: &Report, right: &Report) -> Vec<PathDiff> {
                 left_file_count: left_path.map(|path| path.file_count),
                 right_file_count: right_path.map(|path| path.file_count),
                 file_count_delta,
-            })
+                directory_growth: Vec::new(),
+                extension_growth: Vec::new(),
+                new_large_files: Vec::new(),
+                vanished_large_files: Vec::new(),
+            };
+            if let (Some(left_path), Some(right_path)) = (left_path, right_path) {
+                let left_snapshot = path_snapshot(left_path);
+                let right_snapshot = path_snapshot(right_path);
+                diff.directory_growth = directory_growth(&left_snapshot, &right_snapshot);
+                diff.extension_growth = extension_growth(&left_snapshot, &right_snapshot);
+                diff.new_large_files = appeared_large_files(&left_snapshot, &right_snapshot);
+                diff.vanished_large_files = appeared_large_files(&right_snapshot, &left_snapshot);
+            }
+
+            let unchanged = bytes_delta == 0
+                && file_count_delta == 0
+                && left_path.is_some() == right_path.is_some()
+                && diff.directory_growth.is_empty()
+                && diff.extension_growth.is_empty()
+                && diff.new_large_files.is_empty()
+                && diff.vanished_large_files.is_empty();
+            (!unchanged).then_some(diff)
         })
         .collect()
 }
@@ -545,7 +560,7 @@ mod tests {
     use super::{build_report_diff, get_report, import_report, list_reports, store_report};
     use crate::model::{
         EstimatedImpact, Recommendation, RecommendationEvidence, RecommendationEvidenceKind,
-        Report, RiskLevel, ScanBackendKind, ScanMetadata, ScanMetrics,
+        Report, RiskLevel, ScanBackendKind, ScanMetadata, ScanMetrics, SizeMode,
     };
     use tempfile::tempdir;
 
@@ -665,6 +680,15 @@ mod tests {
                 min_ratio: None,
                 emit_progress_events: false,
                 progress_interval_ms: 250,
+                directory_tree: false,
+                directory_tree_min_bytes: 0,
+                size_mode: SizeMode::Apparent,
+                similar_images: false,
+                similar_image_max_distance: 0,
+                dedupe_directories: false,
+                dedupe_directory_min_overlap: 0.0,
+                dedupe_archives: false,
+                disk_inventory: None,
             },
             scan_metrics: ScanMetrics::default(),
             scan_progress_summary: Default::default(),
@@ -695,6 +719,15 @@ mod tests {
                 metadata_notes: Vec::new(),
                 role_hint: Default::default(),
                 target_role_eligibility: Vec::new(),
+                device_id: None,
+                mount_kind: Default::default(),
+                storage_pool: None,
+                parent_mount: None,
+                reserved_bytes: None,
+                snapshot_bytes: None,
+                logical_used_bytes: None,
+                effective_free_space_bytes: None,
+                health: None,
             }],
             paths: vec![crate::model::PathStats {
                 root_path: "D:\\Games".to_string(),
@@ -718,6 +751,9 @@ mod tests {
                     stale_files: 0,
                     unknown_modified_files: 0,
                 },
+                directory_tree: None,
+                hardlinked_files: 0,
+                hardlink_savings_bytes: 0,
             }],
             categories: Vec::new(),
             duplicates: vec![crate::model::DuplicateGroup {
@@ -730,6 +766,14 @@ mod tests {
                     rationale: "fixture".to_string(),
                 },
             }],
+            duplicate_directories: Vec::new(),
+            archive_extractions: Vec::new(),
+            similar_images: Vec::new(),
+            git_repositories: Vec::new(),
+            dev_projects: None,
+            capacity_forecasts: Vec::new(),
+            tiering_plan: Vec::new(),
+            policy_profile: None,
             recommendations: vec![Recommendation {
                 id: "rec-1".to_string(),
                 title: "Recommendation".to_string(),
@@ -754,6 +798,8 @@ mod tests {
                     risk_notes: None,
                 },
                 risk_level: RiskLevel::Low,
+                priority_score: 0.0,
+                rank: 0,
             }],
             policy_decisions: Vec::new(),
             rule_traces: Vec::new(),
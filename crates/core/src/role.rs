@@ -237,6 +237,15 @@ mod tests {
             metadata_notes: Vec::new(),
             role_hint: Default::default(),
             target_role_eligibility: Vec::new(),
+            device_id: None,
+            mount_kind: Default::default(),
+            storage_pool: None,
+            parent_mount: None,
+            reserved_bytes: None,
+            snapshot_bytes: None,
+            logical_used_bytes: None,
+            effective_free_space_bytes: None,
+            health: None,
         }
     }
 }
@@ -1,50 +1,89 @@
 use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    mpsc, Arc, Mutex,
 };
-use std::time::{Instant, UNIX_EPOCH};
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Duration, SecondsFormat, Utc};
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use sysinfo::{DiskKind as SysDiskKind, Disks};
 use tracing::info;
 use uuid::Uuid;
 use walkdir::WalkDir;
 
-use crate::analyzers::AnalyzerContext;
+use crate::analyzers::dev_artifacts::find_dev_projects;
+use crate::analyzers::{AnalyzerContext, AnalyzerRegistry};
+use crate::archive_dedupe::{find_archive_extractions, ArchiveDedupeOptions};
 use crate::categorize::{aggregate_categories_by_disk, categorize_disks, categorize_paths};
-use crate::dedupe::{find_duplicates, FileRecord};
-use crate::device::{enrich_disks, DiskProbe};
+#[cfg(all(feature = "pdu-backend", unix))]
+use crate::dedupe::allocated_size;
+use crate::dedupe::{
+    find_duplicates_with_options, DedupeOptions, DedupeOutcome, FileRecord, MountHashLimit,
+};
+use crate::device::{
+    enrich_disks, enrich_inventory, load_disk_inventory, path_device_id, probe_local_disks,
+};
+use crate::directory_dedupe::{
+    find_duplicate_directories, DirectoryDedupeOptions, DEFAULT_DIRECTORY_MIN_OVERLAP,
+};
+use crate::forecast::forecast_capacity;
+use crate::growth::path_snapshot;
+use crate::health::{apply_disk_health, collect_disk_health};
 use crate::history;
+use crate::journal::{
+    is_settled, load_journal, modified_unix_nanos, persist_journal, system_time_unix_nanos,
+    DirectoryJournal, FileFacts, JournalDirectory, JournalFile, SubtreeTotals,
+    JOURNAL_SCHEMA_VERSION,
+};
 use crate::model::{
     ActivitySignals, BackendParity, DirectoryUsage, DiskInfo, DiskKind, ExtensionUsage, FileEntry,
-    FileTypeSummary, LargestFiles, PathStats, Report, ScanBackendKind, ScanMetadata, ScanMetrics,
-    ScanPhase, ScanPhaseCount, ScanProgressEvent, ScanProgressSummary, REPORT_VERSION,
+    FileTypeSummary, LargestFiles, PathStats, PolicyProfile, Report, ScanBackendKind, ScanMetadata,
+    ScanMetrics, ScanPhase, ScanPhaseCount, ScanProgressEvent, ScanProgressSummary, SizeMode,
+    REPORT_VERSION,
 };
+use crate::perceptual::{
+    find_similar_images, SimilarImageOptions, DEFAULT_SIMILAR_IMAGE_MAX_DISTANCE,
+};
+use crate::policy_profile::{apply_policy_profile, load_policy_profile};
 use crate::recommend::generate_recommendation_bundle_with_context;
 use crate::role::infer_disk_roles;
+use crate::tree::DirectoryTreeBuilder;
+use crate::vcs::find_git_repositories;
 
 #[cfg(feature = "pdu-backend")]
 use parallel_disk_usage::{
+    data_tree::DataTree,
     fs_tree_builder::FsTreeBuilder,
-    get_size::GetApparentSize,
-    hardlink::HardlinkIgnorant,
+    get_size::{GetApparentSize, GetSize},
+    hardlink::{HardlinkIgnorant, RecordHardlinks},
     os_string_display::OsStringDisplay,
-    reporter::{ErrorOnlyReporter, ErrorReport},
+    reporter::{ErrorOnlyReporter, ErrorReport, Reporter},
     size::Bytes,
 };
+#[cfg(all(feature = "pdu-backend", unix))]
+use parallel_disk_usage::{
+    get_size::GetBlockSize,
+    hardlink::{DeduplicateSharedSize, HardlinkAware},
+};
 
 const PDU_INSPIRED_BANNED_AUTO_ROOTS: &[&str] = &[
     "/dev", "/proc", "/sys", "/run", "/mnt", "/media", "/cdrom", "/Volumes", "/System",
 ];
 const CACHE_SCHEMA_VERSION: u32 = 1;
 const CACHE_DIR_NAME: &str = "storage-strategist-cache";
+const DEDUPE_HASH_CACHE_FILE_NAME: &str = "dedupe-hashes.json";
+const DEFAULT_DEDUPE_WORKERS: usize = 4;
+const DEFAULT_DEDUPE_HDD_WORKERS: usize = 1;
 const DEFAULT_CACHE_TTL_SECONDS: u64 = 900;
+const DEFAULT_DIRECTORY_TREE_MIN_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_JOURNAL_MAX_AGE_SECONDS: u64 = 7 * 24 * 60 * 60;
 
 #[derive(Debug, Clone)]
 pub struct ScanOptions {
@@ -53,10 +92,31 @@ pub struct ScanOptions {
     pub excludes: Vec<String>,
     pub dedupe: bool,
     pub dedupe_min_size: u64,
+    pub dedupe_hash_cache: bool,
+    pub dedupe_workers: usize,
+    /// Concurrent hashing reads on each spinning (HDD or rotational) mount.
+    pub dedupe_hdd_workers: usize,
+    pub dedupe_max_bytes_per_second: Option<u64>,
+    pub dedupe_directories: bool,
+    pub dedupe_directory_min_overlap: f32,
+    pub dedupe_archives: bool,
+    pub similar_images: bool,
+    pub similar_image_max_distance: u32,
+    /// TOML/JSON policy profile overriding recommendation thresholds and rules.
+    pub policy_profile: Option<PathBuf>,
+    /// Directory of saved `smartctl --json` outputs to fold into disk health.
+    pub smart_dir: Option<PathBuf>,
+    /// Query `smartctl` for every drive it finds, when it is installed.
+    pub smartctl: bool,
+    /// Captured `DiskInventory` to analyze instead of probing this machine's disks.
+    pub disk_inventory: Option<PathBuf>,
     pub dry_run: bool,
     pub largest_files_limit: usize,
     pub largest_directories_limit: usize,
     pub top_extensions_limit: usize,
+    pub directory_tree: bool,
+    pub directory_tree_min_bytes: u64,
+    pub size_mode: SizeMode,
     pub backend: ScanBackendKind,
     pub progress: bool,
     pub min_ratio: Option<f32>,
@@ -66,6 +126,10 @@ pub struct ScanOptions {
     pub incremental_cache: bool,
     pub cache_dir: Option<PathBuf>,
     pub cache_ttl_seconds: u64,
+    pub journal_max_age_seconds: u64,
+    /// Re-stat every journaled file before replaying its directory, catching in-place
+    /// edits that leave the directory mtime untouched.
+    pub journal_verify_files: bool,
     pub record_history: bool,
     pub report_store_dir: Option<PathBuf>,
     pub cancel_flag: Option<Arc<AtomicBool>>,
@@ -79,10 +143,26 @@ impl Default for ScanOptions {
             excludes: Vec::new(),
             dedupe: false,
             dedupe_min_size: 1_048_576,
+            dedupe_hash_cache: false,
+            dedupe_workers: DEFAULT_DEDUPE_WORKERS,
+            dedupe_hdd_workers: DEFAULT_DEDUPE_HDD_WORKERS,
+            dedupe_max_bytes_per_second: None,
+            dedupe_directories: false,
+            dedupe_directory_min_overlap: DEFAULT_DIRECTORY_MIN_OVERLAP,
+            dedupe_archives: false,
+            similar_images: false,
+            similar_image_max_distance: DEFAULT_SIMILAR_IMAGE_MAX_DISTANCE,
+            policy_profile: None,
+            smart_dir: None,
+            smartctl: false,
+            disk_inventory: None,
             dry_run: true,
             largest_files_limit: 20,
             largest_directories_limit: 10,
             top_extensions_limit: 12,
+            directory_tree: false,
+            directory_tree_min_bytes: DEFAULT_DIRECTORY_TREE_MIN_BYTES,
+            size_mode: SizeMode::Apparent,
             backend: ScanBackendKind::Native,
             progress: false,
             min_ratio: None,
@@ -92,6 +172,8 @@ impl Default for ScanOptions {
             incremental_cache: false,
             cache_dir: None,
             cache_ttl_seconds: DEFAULT_CACHE_TTL_SECONDS,
+            journal_max_age_seconds: DEFAULT_JOURNAL_MAX_AGE_SECONDS,
+            journal_verify_files: false,
             record_history: true,
             report_store_dir: None,
             cancel_flag: None,
@@ -106,15 +188,36 @@ struct ScanCacheKey {
     excludes: Vec<String>,
     dedupe: bool,
     dedupe_min_size: u64,
+    dedupe_directories: bool,
+    dedupe_directory_min_overlap: f32,
+    dedupe_archives: bool,
+    similar_images: bool,
+    similar_image_max_distance: u32,
+    policy_profile_hash: String,
+    smart_dir: Option<String>,
+    smart_dir_hash: Option<String>,
+    smartctl: bool,
+    disk_inventory: Option<String>,
+    disk_inventory_hash: Option<String>,
     backend: ScanBackendKind,
     min_ratio: Option<f32>,
     largest_files_limit: usize,
     largest_directories_limit: usize,
     top_extensions_limit: usize,
+    directory_tree: bool,
+    directory_tree_min_bytes: u64,
+    size_mode: SizeMode,
     report_version: String,
     cache_schema_version: u32,
 }
 
+#[derive(Debug, Serialize)]
+struct JournalKey {
+    root: String,
+    excludes: Vec<String>,
+    journal_schema_version: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ScanCacheEntry {
     schema_version: u32,
@@ -142,12 +245,14 @@ struct BackendProgress {
 trait ScanBackend {
     fn kind(&self) -> ScanBackendKind;
 
+    #[allow(clippy::too_many_arguments)]
     fn scan(
         &self,
         roots: &[PathBuf],
         disks: &[DiskInfo],
         excludes: &ExcludeMatcher,
         options: &ScanOptions,
+        journals: &RootJournals,
         warnings: &mut Vec<String>,
         on_progress: &mut dyn FnMut(BackendProgress),
     ) -> Result<BackendScanOutput>;
@@ -158,6 +263,8 @@ struct BackendCounters {
     scanned_files: u64,
     scanned_directories: u64,
     scanned_bytes: u64,
+    journal_reused_directories: u64,
+    journal_rescanned_directories: u64,
 }
 
 struct BackendScanOutput {
@@ -166,6 +273,33 @@ struct BackendScanOutput {
     counters: BackendCounters,
 }
 
+impl BackendScanOutput {
+    fn new() -> Self {
+        Self {
+            paths: Vec::new(),
+            files: Vec::new(),
+            counters: BackendCounters::default(),
+        }
+    }
+
+    fn push_root(&mut self, result: RootScanResult) {
+        let counters = &mut self.counters;
+        counters.scanned_files = counters.scanned_files.saturating_add(result.scanned_files);
+        counters.scanned_directories = counters
+            .scanned_directories
+            .saturating_add(result.scanned_directories);
+        counters.scanned_bytes = counters.scanned_bytes.saturating_add(result.scanned_bytes);
+        counters.journal_reused_directories = counters
+            .journal_reused_directories
+            .saturating_add(result.journal_reused_directories);
+        counters.journal_rescanned_directories = counters
+            .journal_rescanned_directories
+            .saturating_add(result.journal_rescanned_directories);
+        self.files.extend(result.files);
+        self.paths.push(result.stats);
+    }
+}
+
 struct NativeBackend;
 
 impl ScanBackend for NativeBackend {
@@ -179,14 +313,11 @@ impl ScanBackend for NativeBackend {
         disks: &[DiskInfo],
         excludes: &ExcludeMatcher,
         options: &ScanOptions,
+        journals: &RootJournals,
         warnings: &mut Vec<String>,
         on_progress: &mut dyn FnMut(BackendProgress),
     ) -> Result<BackendScanOutput> {
-        let mut output = BackendScanOutput {
-            paths: Vec::new(),
-            files: Vec::new(),
-            counters: BackendCounters::default(),
-        };
+        let mut output = BackendScanOutput::new();
 
         for (index, root) in roots.iter().enumerate() {
             if is_cancelled(options) {
@@ -194,22 +325,27 @@ impl ScanBackend for NativeBackend {
                 break;
             }
 
-            match scan_root(root, disks, excludes, options, warnings, None, None) {
+            // With the incremental cache on, the sequential journal-aware walker
+            // replaces walkdir so unchanged directories are replayed, not re-read.
+            let result = if options.incremental_cache {
+                Ok(walk_root(
+                    root,
+                    disks,
+                    excludes,
+                    options,
+                    journals.get(root),
+                    false,
+                    &output.counters,
+                    warnings,
+                    on_progress,
+                ))
+            } else {
+                scan_root(root, disks, excludes, options, warnings, None, None)
+            };
+
+            match result {
                 Ok(result) => {
-                    output.counters.scanned_files = output
-                        .counters
-                        .scanned_files
-                        .saturating_add(result.scanned_files);
-                    output.counters.scanned_directories = output
-                        .counters
-                        .scanned_directories
-                        .saturating_add(result.scanned_directories);
-                    output.counters.scanned_bytes = output
-                        .counters
-                        .scanned_bytes
-                        .saturating_add(result.scanned_bytes);
-                    output.files.extend(result.files);
-                    output.paths.push(result.stats);
+                    output.push_root(result);
 
                     on_progress(BackendProgress {
                         current_path: root.to_string_lossy().to_string(),
@@ -247,6 +383,7 @@ impl ScanBackend for PduLibraryBackend {
         disks: &[DiskInfo],
         excludes: &ExcludeMatcher,
         options: &ScanOptions,
+        journals: &RootJournals,
         warnings: &mut Vec<String>,
         on_progress: &mut dyn FnMut(BackendProgress),
     ) -> Result<BackendScanOutput> {
@@ -256,7 +393,15 @@ impl ScanBackend for PduLibraryBackend {
                     .to_string(),
             );
             let native = NativeBackend;
-            return native.scan(roots, disks, excludes, options, warnings, on_progress);
+            return native.scan(
+                roots,
+                disks,
+                excludes,
+                options,
+                journals,
+                warnings,
+                on_progress,
+            );
         }
 
         #[cfg(not(feature = "pdu-backend"))]
@@ -266,16 +411,20 @@ impl ScanBackend for PduLibraryBackend {
                     .to_string(),
             );
             let native = NativeBackend;
-            return native.scan(roots, disks, excludes, options, warnings, on_progress);
+            return native.scan(
+                roots,
+                disks,
+                excludes,
+                options,
+                journals,
+                warnings,
+                on_progress,
+            );
         }
 
         #[cfg(feature = "pdu-backend")]
         {
-            let mut output = BackendScanOutput {
-                paths: Vec::new(),
-                files: Vec::new(),
-                counters: BackendCounters::default(),
-            };
+            let mut output = BackendScanOutput::new();
 
             for root in roots {
                 if is_cancelled(options) {
@@ -305,20 +454,7 @@ impl ScanBackend for PduLibraryBackend {
                     pdu_total,
                 ) {
                     Ok(result) => {
-                        output.counters.scanned_files = output
-                            .counters
-                            .scanned_files
-                            .saturating_add(result.scanned_files);
-                        output.counters.scanned_directories = output
-                            .counters
-                            .scanned_directories
-                            .saturating_add(result.scanned_directories);
-                        output.counters.scanned_bytes = output
-                            .counters
-                            .scanned_bytes
-                            .saturating_add(result.scanned_bytes);
-                        output.files.extend(result.files);
-                        output.paths.push(result.stats);
+                        output.push_root(result);
 
                         on_progress(BackendProgress {
                             current_path: root.to_string_lossy().to_string(),
@@ -338,12 +474,495 @@ impl ScanBackend for PduLibraryBackend {
     }
 }
 
+struct ParallelBackend;
+
+impl ScanBackend for ParallelBackend {
+    fn kind(&self) -> ScanBackendKind {
+        ScanBackendKind::Parallel
+    }
+
+    fn scan(
+        &self,
+        roots: &[PathBuf],
+        disks: &[DiskInfo],
+        excludes: &ExcludeMatcher,
+        options: &ScanOptions,
+        journals: &RootJournals,
+        warnings: &mut Vec<String>,
+        on_progress: &mut dyn FnMut(BackendProgress),
+    ) -> Result<BackendScanOutput> {
+        let mut output = BackendScanOutput::new();
+
+        for (index, root) in roots.iter().enumerate() {
+            if is_cancelled(options) {
+                warnings.push("scan canceled by caller".to_string());
+                break;
+            }
+
+            let result = walk_root(
+                root,
+                disks,
+                excludes,
+                options,
+                journals.get(root),
+                true,
+                &output.counters,
+                warnings,
+                on_progress,
+            );
+            output.push_root(result);
+
+            on_progress(BackendProgress {
+                current_path: root.to_string_lossy().to_string(),
+                scanned_files: output.counters.scanned_files,
+                scanned_bytes: output.counters.scanned_bytes,
+                errors: warnings.len() as u64,
+            });
+
+            if options.progress {
+                info!(
+                    "scan progress: root {}/{} complete ({})",
+                    index + 1,
+                    roots.len(),
+                    root.display()
+                );
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Journals loaded for the scanned roots, keyed by root.
+type RootJournals = HashMap<PathBuf, DirectoryJournal>;
+
+fn load_root_journals(
+    options: &ScanOptions,
+    roots: &[PathBuf],
+    warnings: &mut Vec<String>,
+) -> RootJournals {
+    if !options.incremental_cache {
+        return RootJournals::new();
+    }
+    roots
+        .iter()
+        .filter_map(|root| {
+            load_journal(&journal_path(options, root), warnings)
+                .map(|journal| (root.clone(), journal))
+        })
+        .collect()
+}
+
+/// Walks one root with [`DirectoryWalk`] and folds the entries through the shared
+/// accumulator. When the incremental cache is enabled, directories whose mtime still
+/// matches `previous` are replayed from it instead of being re-read, and the
+/// refreshed journal is written back once the walk completes.
+#[allow(clippy::too_many_arguments)]
+fn walk_root(
+    root: &Path,
+    disks: &[DiskInfo],
+    excludes: &ExcludeMatcher,
+    options: &ScanOptions,
+    previous: Option<&DirectoryJournal>,
+    parallel: bool,
+    progress_base: &BackendCounters,
+    warnings: &mut Vec<String>,
+    on_progress: &mut dyn FnMut(BackendProgress),
+) -> RootScanResult {
+    let journal_path = options
+        .incremental_cache
+        .then(|| journal_path(options, root));
+    let walk_started_unix_nanos = system_time_unix_nanos(SystemTime::now()).unwrap_or(0);
+
+    let walk = DirectoryWalk {
+        root,
+        excludes,
+        options,
+        parallel,
+        previous,
+        next: journal_path
+            .is_some()
+            .then(|| Mutex::new(DirectoryJournal::new(root))),
+        walk_started_unix_nanos,
+        now_unix_secs: Utc::now().timestamp(),
+        scanned_files: AtomicU64::new(progress_base.scanned_files),
+        scanned_bytes: AtomicU64::new(progress_base.scanned_bytes),
+        errors: AtomicU64::new(warnings.len() as u64),
+        reused_directories: AtomicU64::new(0),
+        rescanned_directories: AtomicU64::new(0),
+        canceled: AtomicBool::new(false),
+        warnings: Mutex::new(Vec::new()),
+    };
+    let mut entries = walk.run(on_progress);
+    warnings.extend(walk.warnings.into_inner().unwrap_or_default());
+    let canceled = walk.canceled.load(Ordering::Relaxed);
+    if canceled {
+        warnings.push(format!(
+            "scan canceled while walking {}; report contains partial data",
+            root.display()
+        ));
+    }
+
+    // A canceled walk leaves whole subtrees unlisted; keep the previous journal so
+    // the next run does not mistake them for empty.
+    if let (Some(path), Some(next), false) = (journal_path.as_deref(), walk.next, canceled) {
+        if let Ok(journal) = next.into_inner() {
+            persist_journal(path, &journal, warnings);
+        }
+    }
+
+    // Work stealing makes completion order nondeterministic; sorting restores a
+    // stable fold order so reports are reproducible across runs.
+    entries.sort_by(|a, b| a.path().cmp(b.path()));
+    let mut accumulator = RootAccumulator::new(root, disks, options);
+    for entry in &entries {
+        match entry {
+            WalkedEntry::Directory(path) => accumulator.record_directory(path),
+            WalkedEntry::File(path, facts) => accumulator.record_file(path, facts),
+        }
+    }
+    let mut result = accumulator.finish(None, None);
+    result.journal_reused_directories = walk.reused_directories.load(Ordering::Relaxed);
+    result.journal_rescanned_directories = walk.rescanned_directories.load(Ordering::Relaxed);
+    result
+}
+
+enum WalkedEntry {
+    Directory(PathBuf),
+    File(PathBuf, FileFacts),
+}
+
+impl WalkedEntry {
+    fn path(&self) -> &Path {
+        match self {
+            WalkedEntry::Directory(path) | WalkedEntry::File(path, _) => path,
+        }
+    }
+}
+
+/// The non-excluded children of one directory, either freshly read or replayed
+/// from the journal.
+struct DirectoryListing {
+    files: Vec<(OsString, FileFacts)>,
+    subdirectories: Vec<OsString>,
+    // False when an entry could not be read, so the listing must not be journaled.
+    complete: bool,
+}
+
+/// Shared state for walking one root. Directories are fanned out onto the rayon
+/// pool when `parallel` is set while the calling thread reports progress from the
+/// counters; `previous`/`next` carry the directory journal across runs.
+struct DirectoryWalk<'a> {
+    root: &'a Path,
+    excludes: &'a ExcludeMatcher,
+    options: &'a ScanOptions,
+    parallel: bool,
+    previous: Option<&'a DirectoryJournal>,
+    next: Option<Mutex<DirectoryJournal>>,
+    walk_started_unix_nanos: u64,
+    now_unix_secs: i64,
+    scanned_files: AtomicU64,
+    scanned_bytes: AtomicU64,
+    errors: AtomicU64,
+    reused_directories: AtomicU64,
+    rescanned_directories: AtomicU64,
+    canceled: AtomicBool,
+    warnings: Mutex<Vec<String>>,
+}
+
+impl DirectoryWalk<'_> {
+    fn run(&self, on_progress: &mut dyn FnMut(BackendProgress)) -> Vec<WalkedEntry> {
+        // Mirror walkdir: a non-directory root yields no entries and no warning, and
+        // a `max_depth` of 0 yields only the root itself, never its children.
+        if !self.root.is_dir() || self.options.max_depth == Some(0) {
+            return Vec::new();
+        }
+        let interval = std::time::Duration::from_millis(self.options.progress_interval_ms);
+        thread::scope(|scope| {
+            let (done_tx, done_rx) = mpsc::channel::<()>();
+            let handle = scope.spawn(move || {
+                let (entries, _) = self.walk_directory(self.root, 0);
+                let _ = done_tx.send(());
+                entries
+            });
+
+            while let Err(mpsc::RecvTimeoutError::Timeout) = done_rx.recv_timeout(interval) {
+                on_progress(BackendProgress {
+                    current_path: self.root.to_string_lossy().to_string(),
+                    scanned_files: self.scanned_files.load(Ordering::Relaxed),
+                    scanned_bytes: self.scanned_bytes.load(Ordering::Relaxed),
+                    errors: self.errors.load(Ordering::Relaxed),
+                });
+            }
+
+            handle.join().unwrap_or_else(|_| {
+                self.warn(format!(
+                    "directory walker panicked under {}; report contains partial data",
+                    self.root.display()
+                ));
+                Vec::new()
+            })
+        })
+    }
+
+    /// Walks `directory` and everything below it, returning the entries together with
+    /// their [`SubtreeTotals`]. Every directory costs one stat; only those whose mtime
+    /// moved (or that have no reusable journal entry) are read again.
+    fn walk_directory(&self, directory: &Path, depth: usize) -> (Vec<WalkedEntry>, SubtreeTotals) {
+        if is_cancelled(self.options) {
+            self.canceled.store(true, Ordering::Relaxed);
+            return (Vec::new(), SubtreeTotals::default());
+        }
+
+        // The directory's own mtime is only needed to consult and refresh the journal.
+        let modified_unix_nanos = self
+            .next
+            .as_ref()
+            .and_then(|_| fs::metadata(directory).ok())
+            .and_then(|metadata| modified_unix_nanos(&metadata));
+        let (listing, recorded_unix_secs) =
+            match self.journaled_listing(directory, modified_unix_nanos) {
+                Some(journaled) => {
+                    self.reused_directories.fetch_add(1, Ordering::Relaxed);
+                    journaled
+                }
+                None => {
+                    self.rescanned_directories.fetch_add(1, Ordering::Relaxed);
+                    match self.read_listing(directory) {
+                        Some(listing) => (listing, self.now_unix_secs),
+                        None => return (Vec::new(), SubtreeTotals::default()),
+                    }
+                }
+            };
+
+        let child_depth = depth + 1;
+        let descend = self
+            .options
+            .max_depth
+            .is_none_or(|max_depth| child_depth < max_depth);
+        let mut entries = Vec::with_capacity(listing.files.len() + listing.subdirectories.len());
+        let mut subdirectories = Vec::new();
+        let mut totals = SubtreeTotals {
+            directory_count: listing.subdirectories.len() as u64,
+            ..SubtreeTotals::default()
+        };
+
+        for (name, facts) in &listing.files {
+            self.scanned_files.fetch_add(1, Ordering::Relaxed);
+            self.scanned_bytes
+                .fetch_add(facts.size_bytes, Ordering::Relaxed);
+            totals.add_file(facts);
+            entries.push(WalkedEntry::File(directory.join(name), facts.clone()));
+        }
+        for name in &listing.subdirectories {
+            let path = directory.join(name);
+            if descend {
+                subdirectories.push(path.clone());
+            }
+            entries.push(WalkedEntry::Directory(path));
+        }
+
+        let merge =
+            |(mut left, mut left_totals): (Vec<WalkedEntry>, SubtreeTotals),
+             (mut right, right_totals): (Vec<WalkedEntry>, SubtreeTotals)| {
+                left.append(&mut right);
+                left_totals.add_subtree(&right_totals);
+                (left, left_totals)
+            };
+        let (nested, nested_totals) = if self.parallel {
+            subdirectories
+                .par_iter()
+                .map(|subdirectory| self.walk_directory(subdirectory, child_depth))
+                .reduce(|| (Vec::new(), SubtreeTotals::default()), merge)
+        } else {
+            subdirectories
+                .iter()
+                .map(|subdirectory| self.walk_directory(subdirectory, child_depth))
+                .fold((Vec::new(), SubtreeTotals::default()), merge)
+        };
+        entries.extend(nested);
+        totals.add_subtree(&nested_totals);
+
+        // Recorded after the children so the entry carries the finished subtree totals.
+        if let Some(modified_unix_nanos) = modified_unix_nanos {
+            self.record_listing(
+                directory,
+                modified_unix_nanos,
+                recorded_unix_secs,
+                &listing,
+                totals,
+            );
+        }
+        (entries, totals)
+    }
+
+    /// Returns the journaled listing of `directory` and when it was first recorded,
+    /// provided the journal entry is still valid for the directory's current mtime.
+    /// Listed files are replayed without a stat unless `journal_verify_files` is set.
+    fn journaled_listing(
+        &self,
+        directory: &Path,
+        modified_unix_nanos: Option<u64>,
+    ) -> Option<(DirectoryListing, i64)> {
+        let entry = self.previous?.directories.get(directory.to_str()?)?;
+        if !entry.is_reusable(
+            modified_unix_nanos?,
+            self.now_unix_secs,
+            self.options.journal_max_age_seconds,
+        ) || (self.options.journal_verify_files && !entry.files_match(directory))
+        {
+            return None;
+        }
+        let listing = DirectoryListing {
+            files: entry
+                .files
+                .iter()
+                .map(|file| (OsString::from(&file.name), file.facts.clone()))
+                .collect(),
+            subdirectories: entry.subdirectories.iter().map(OsString::from).collect(),
+            complete: true,
+        };
+        Some((listing, entry.recorded_unix_secs))
+    }
+
+    fn read_listing(&self, directory: &Path) -> Option<DirectoryListing> {
+        let read_dir = match fs::read_dir(directory) {
+            Ok(read_dir) => read_dir,
+            Err(err) => {
+                self.warn(format!(
+                    "walk error under {}: {}: {}",
+                    self.root.display(),
+                    directory.display(),
+                    err
+                ));
+                return None;
+            }
+        };
+
+        let mut listing = DirectoryListing {
+            files: Vec::new(),
+            subdirectories: Vec::new(),
+            complete: true,
+        };
+        for item in read_dir {
+            let entry = match item {
+                Ok(entry) => entry,
+                Err(err) => {
+                    self.warn(format!("walk error under {}: {}", self.root.display(), err));
+                    listing.complete = false;
+                    continue;
+                }
+            };
+            let path = entry.path();
+            if self.excludes.is_excluded(&path) {
+                continue;
+            }
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(err) => {
+                    self.warn(format!(
+                        "walk error under {}: {}: {}",
+                        self.root.display(),
+                        path.display(),
+                        err
+                    ));
+                    listing.complete = false;
+                    continue;
+                }
+            };
+
+            if file_type.is_dir() {
+                listing.subdirectories.push(entry.file_name());
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+
+            match entry.metadata() {
+                Ok(metadata) => listing
+                    .files
+                    .push((entry.file_name(), FileFacts::from_metadata(&metadata))),
+                Err(err) => {
+                    self.warn(format!(
+                        "metadata read failed for {}: {}",
+                        path.display(),
+                        err
+                    ));
+                    listing.complete = false;
+                }
+            }
+        }
+        Some(listing)
+    }
+
+    fn record_listing(
+        &self,
+        directory: &Path,
+        modified_unix_nanos: u64,
+        recorded_unix_secs: i64,
+        listing: &DirectoryListing,
+        subtree: SubtreeTotals,
+    ) {
+        let Some(next) = self.next.as_ref() else {
+            return;
+        };
+        if !listing.complete {
+            return;
+        }
+        let Some(key) = directory.to_str() else {
+            return;
+        };
+        let files = listing
+            .files
+            .iter()
+            .map(|(name, facts)| {
+                name.to_str().map(|name| JournalFile {
+                    name: name.to_string(),
+                    facts: facts.clone(),
+                })
+            })
+            .collect::<Option<Vec<_>>>();
+        let subdirectories = listing
+            .subdirectories
+            .iter()
+            .map(|name| name.to_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>();
+        let (Some(files), Some(subdirectories)) = (files, subdirectories) else {
+            return;
+        };
+
+        let entry = JournalDirectory {
+            modified_unix_nanos,
+            // Replayed listings keep their original record time so the max-age bound
+            // eventually forces every directory to be re-read.
+            recorded_unix_secs,
+            reusable: is_settled(modified_unix_nanos, self.walk_started_unix_nanos),
+            files,
+            subdirectories,
+            subtree,
+        };
+        if let Ok(mut journal) = next.lock() {
+            journal.directories.insert(key.to_string(), entry);
+        }
+    }
+
+    fn warn(&self, warning: String) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(warning);
+        }
+    }
+}
+
 struct RootScanResult {
     stats: PathStats,
     files: Vec<FileRecord>,
     scanned_files: u64,
     scanned_directories: u64,
     scanned_bytes: u64,
+    journal_reused_directories: u64,
+    journal_rescanned_directories: u64,
 }
 
 pub struct ScanRunOutput {
@@ -366,6 +985,10 @@ where
     F: FnMut(ScanProgressEvent),
 {
     validate_scan_options(options)?;
+    let policy_profile = match &options.policy_profile {
+        Some(path) => apply_policy_profile(Some(path), load_policy_profile(path)?),
+        None => apply_policy_profile(None, PolicyProfile::default()),
+    };
     let started = Instant::now();
     let scan_id = options
         .scan_id
@@ -389,13 +1012,42 @@ where
         0,
     );
 
-    let mut disks = enumerate_disks();
+    // An inventory carries its own drive health; SMART data read here would describe
+    // this machine's drives, not the inventory's.
+    let mut disks = match options.disk_inventory.as_deref() {
+        Some(path) => enrich_inventory(&load_disk_inventory(path)?),
+        None => {
+            let mut disks = enrich_disks(probe_local_disks(), &mut warnings);
+            let health = collect_disk_health(
+                options.smart_dir.as_deref(),
+                options.smartctl,
+                &mut warnings,
+            );
+            apply_disk_health(&mut disks, &health);
+            disks
+        }
+    };
     let roots = resolve_roots(options, &disks, &mut warnings)?;
     let excludes = ExcludeMatcher::new(&options.excludes, &mut warnings);
+    if !cfg!(unix) && options.size_mode != SizeMode::Apparent {
+        warnings.push(format!(
+            "size mode {:?} needs POSIX block and inode metadata; totals use apparent sizes on this platform",
+            options.size_mode
+        ));
+    }
 
-    if let Some(mut cached_report) =
-        try_load_cached_report(options, &scan_id, &roots, &mut warnings, started.elapsed())
-    {
+    // Journals are read once: the cache check compares them against disk and, on a
+    // miss, the walkers replay the same copies.
+    let journals = load_root_journals(options, &roots, &mut warnings);
+    if let Some(mut cached_report) = try_load_cached_report(
+        options,
+        &scan_id,
+        &roots,
+        &journals,
+        &policy_profile.hash,
+        &mut warnings,
+        started.elapsed(),
+    ) {
         emit_scan_event(
             options,
             &mut on_event,
@@ -439,9 +1091,19 @@ where
     let backend: Box<dyn ScanBackend> = match options.backend {
         ScanBackendKind::Native => Box::new(NativeBackend),
         ScanBackendKind::PduLibrary => Box::new(PduLibraryBackend),
+        ScanBackendKind::Parallel => Box::new(ParallelBackend),
     };
 
-    let (backend_output, categories, duplicates) = {
+    let (
+        backend_output,
+        categories,
+        dedupe,
+        duplicate_directories,
+        archive_extractions,
+        similar_images,
+        git_repositories,
+        dev_projects,
+    ) = {
         let mut progress_hook = |progress: BackendProgress| {
             emit_scan_event(
                 options,
@@ -462,6 +1124,7 @@ where
             &disks,
             &excludes,
             options,
+            &journals,
             &mut warnings,
             &mut progress_hook,
         )?;
@@ -497,17 +1160,87 @@ where
             warnings.len() as u64,
         );
 
-        let duplicates = if options.dedupe {
-            find_duplicates(
+        let dedupe_options = DedupeOptions {
+            // Tree matching needs content hashes for small files as well; their
+            // file-level groups are dropped again below.
+            min_size_bytes: if options.dedupe_directories {
+                1
+            } else {
+                options.dedupe_min_size
+            },
+            hash_cache_path: options
+                .dedupe_hash_cache
+                .then(|| scan_cache_dir(options).join(DEDUPE_HASH_CACHE_FILE_NAME)),
+            max_workers: options.dedupe_workers,
+            mount_limits: dedupe_mount_limits(&disks, options),
+            default_mount_limit: MountHashLimit {
+                concurrency: options.dedupe_workers,
+                max_bytes_per_second: options.dedupe_max_bytes_per_second,
+            },
+            cancel_flag: options.cancel_flag.clone(),
+        };
+        let mut dedupe = if options.dedupe {
+            find_duplicates_with_options(&backend_output.files, &dedupe_options, &mut warnings)
+        } else {
+            DedupeOutcome::default()
+        };
+        let duplicate_directories = if options.dedupe && options.dedupe_directories {
+            find_duplicate_directories(
+                &roots,
+                &backend_output.files,
+                &dedupe.groups,
+                &DirectoryDedupeOptions {
+                    min_size_bytes: options.dedupe_min_size,
+                    min_overlap_ratio: options.dedupe_directory_min_overlap,
+                },
+            )
+        } else {
+            Vec::new()
+        };
+        // Runs before small groups are dropped so their hashes spare loose-file reads.
+        let archive_extractions = if options.dedupe && options.dedupe_archives {
+            find_archive_extractions(
+                &backend_output.files,
+                &dedupe.groups,
+                &ArchiveDedupeOptions {
+                    min_size_bytes: options.dedupe_min_size,
+                    hashing: dedupe_options,
+                },
+                &mut warnings,
+            )
+        } else {
+            Vec::new()
+        };
+        dedupe
+            .groups
+            .retain(|group| group.size_bytes >= options.dedupe_min_size);
+
+        let similar_images = if options.similar_images {
+            find_similar_images(
                 &backend_output.files,
-                options.dedupe_min_size,
+                &SimilarImageOptions {
+                    max_distance: options.similar_image_max_distance,
+                    cancel_flag: options.cancel_flag.clone(),
+                },
                 &mut warnings,
             )
         } else {
             Vec::new()
         };
 
-        (backend_output, categories, duplicates)
+        let git_repositories = find_git_repositories(&backend_output.files);
+        let dev_projects = find_dev_projects(&backend_output.paths);
+
+        (
+            backend_output,
+            categories,
+            dedupe,
+            duplicate_directories,
+            archive_extractions,
+            similar_images,
+            git_repositories,
+            dev_projects,
+        )
     };
 
     emit_scan_event(
@@ -538,6 +1271,18 @@ where
         min_ratio: options.min_ratio,
         emit_progress_events: options.emit_progress_events,
         progress_interval_ms: options.progress_interval_ms,
+        directory_tree: options.directory_tree,
+        directory_tree_min_bytes: options.directory_tree_min_bytes,
+        size_mode: options.size_mode.clone(),
+        similar_images: options.similar_images,
+        similar_image_max_distance: options.similar_image_max_distance,
+        dedupe_directories: options.dedupe_directories,
+        dedupe_directory_min_overlap: options.dedupe_directory_min_overlap,
+        dedupe_archives: options.dedupe_archives,
+        disk_inventory: options
+            .disk_inventory
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string()),
     };
 
     emit_scan_event(
@@ -567,29 +1312,70 @@ where
             scanned_bytes: backend_output.counters.scanned_bytes,
             permission_denied_warnings: 0,
             contradiction_count: 0,
+            journal_reused_directories: backend_output.counters.journal_reused_directories,
+            journal_rescanned_directories: backend_output.counters.journal_rescanned_directories,
+            dedupe_hash_cache_hits: dedupe.hash_cache_hits,
+            dedupe_hash_cache_misses: dedupe.hash_cache_misses,
+            dedupe_stages: dedupe.stages,
         },
         scan_progress_summary: ScanProgressSummary::default(),
         backend_parity: None,
         disks,
         paths: backend_output.paths,
         categories,
-        duplicates,
+        duplicates: dedupe.groups,
+        duplicate_directories,
+        archive_extractions,
+        similar_images,
+        git_repositories,
+        dev_projects: Some(dev_projects),
+        capacity_forecasts: Vec::new(),
+        tiering_plan: Vec::new(),
+        policy_profile: Some(policy_profile),
         recommendations: Vec::new(),
         policy_decisions: Vec::new(),
         rule_traces: Vec::new(),
         warnings,
     };
 
+    // Forecasts feed the trend analyzer and recommendation ranking, so they are
+    // fitted before recommendations run.
+    match history::load_history(options.report_store_dir.as_deref()) {
+        Ok(history) => {
+            let min_samples = report
+                .policy_profile
+                .as_ref()
+                .map(|applied| applied.profile.thresholds.forecast_min_samples)
+                .unwrap_or_default();
+            report.capacity_forecasts = forecast_capacity(&history, &report, min_samples);
+        }
+        Err(err) => append_warning_once(
+            &mut report.warnings,
+            format!(
+                "Failed to load scan history for capacity forecasts: {}",
+                err
+            ),
+        ),
+    }
+
     // Run general recommendations
     let recommendation_bundle = generate_recommendation_bundle_with_context(
         &report,
         &AnalyzerContext {
             report_store_dir: options.report_store_dir.clone(),
+            policy_profile: report
+                .policy_profile
+                .as_ref()
+                .map(|applied| applied.profile.clone())
+                .unwrap_or_default(),
+            ..AnalyzerContext::default()
         },
+        &AnalyzerRegistry::default(),
     );
     report.recommendations = recommendation_bundle.recommendations;
     report.policy_decisions = recommendation_bundle.policy_decisions;
     report.rule_traces = recommendation_bundle.rule_traces;
+    report.tiering_plan = recommendation_bundle.tiering_plan;
     report.scan_metrics.contradiction_count = recommendation_bundle.contradiction_count;
 
     report.scan_metrics.permission_denied_warnings = report
@@ -652,15 +1438,7 @@ fn append_scan_to_history(report: &Report, report_store_dir: Option<&Path>) -> R
                 free_space_bytes: d.free_space_bytes,
             })
             .collect(),
-        paths: report
-            .paths
-            .iter()
-            .map(|p| crate::model::PathSnapshot {
-                root_path: p.root_path.clone(),
-                total_size_bytes: p.total_size_bytes,
-                file_count: p.file_count,
-            })
-            .collect(),
+        paths: report.paths.iter().map(path_snapshot).collect(),
     };
 
     history.snapshots.push(snapshot);
@@ -701,26 +1479,30 @@ fn emit_scan_event<F>(
 }
 
 pub fn compare_backends(options: &ScanOptions) -> Result<BackendParity> {
-    let mut native = options.clone();
-    native.backend = ScanBackendKind::Native;
-    native.emit_progress_events = false;
-    native.record_history = false;
-
-    let mut pdu = options.clone();
-    pdu.backend = ScanBackendKind::PduLibrary;
-    pdu.emit_progress_events = false;
-    pdu.record_history = false;
+    let run_with_backend = |backend: ScanBackendKind| {
+        let mut variant = options.clone();
+        variant.backend = backend;
+        variant.emit_progress_events = false;
+        variant.record_history = false;
+        run_scan(&variant)
+    };
 
-    let native_report = run_scan(&native)?;
-    let pdu_report = run_scan(&pdu)?;
+    let native_report = run_with_backend(ScanBackendKind::Native)?;
+    let pdu_report = run_with_backend(ScanBackendKind::PduLibrary)?;
+    let parallel_report = run_with_backend(ScanBackendKind::Parallel)?;
 
     let scanned_files_delta = pdu_report.scan_metrics.scanned_files as i64
         - native_report.scan_metrics.scanned_files as i64;
     let scanned_bytes_delta = pdu_report.scan_metrics.scanned_bytes as i64
         - native_report.scan_metrics.scanned_bytes as i64;
+    let parallel_scanned_files_delta = parallel_report.scan_metrics.scanned_files as i64
+        - native_report.scan_metrics.scanned_files as i64;
+    let parallel_scanned_bytes_delta = parallel_report.scan_metrics.scanned_bytes as i64
+        - native_report.scan_metrics.scanned_bytes as i64;
 
     let denom = native_report.scan_metrics.scanned_bytes.max(1) as f64;
     let ratio = (scanned_bytes_delta.unsigned_abs() as f64 / denom) as f32;
+    let parallel_ratio = (parallel_scanned_bytes_delta.unsigned_abs() as f64 / denom) as f32;
     let tolerance_ratio = 0.05;
 
     Ok(BackendParity {
@@ -728,8 +1510,11 @@ pub fn compare_backends(options: &ScanOptions) -> Result<BackendParity> {
         pdu_library_elapsed_ms: pdu_report.scan_metrics.elapsed_ms,
         scanned_files_delta,
         scanned_bytes_delta,
+        parallel_elapsed_ms: parallel_report.scan_metrics.elapsed_ms,
+        parallel_scanned_files_delta,
+        parallel_scanned_bytes_delta,
         tolerance_ratio,
-        within_tolerance: ratio <= tolerance_ratio,
+        within_tolerance: ratio <= tolerance_ratio && parallel_ratio <= tolerance_ratio,
     })
 }
 
@@ -793,23 +1578,7 @@ fn scan_root(
     largest_directories_override: Option<Vec<DirectoryUsage>>,
     total_size_override: Option<u64>,
 ) -> Result<RootScanResult> {
-    let mut file_count = 0_u64;
-    let mut directory_count = 0_u64;
-    let mut total_size_bytes = 0_u64;
-    let mut top_file_types: HashMap<String, (u64, u64)> = HashMap::new();
-    let mut top_directory_sizes: HashMap<String, u64> = HashMap::new();
-    let mut largest_files: Vec<FileEntry> = Vec::new();
-    let mut files: Vec<FileRecord> = Vec::new();
-    let disk_mount = match_disk_mount(root, disks);
-
-    let now = Utc::now();
-    let recent_cutoff = now - Duration::days(90);
-    let stale_cutoff = now - Duration::days(365 * 2);
-    let mut activity = ActivitySignals {
-        recent_files: 0,
-        stale_files: 0,
-        unknown_modified_files: 0,
-    };
+    let mut accumulator = RootAccumulator::new(root, disks, options);
 
     let mut walker = WalkDir::new(root).follow_links(false);
     if let Some(depth) = options.max_depth {
@@ -842,7 +1611,7 @@ fn scan_root(
             continue;
         }
         if entry.file_type().is_dir() {
-            directory_count += 1;
+            accumulator.record_directory(entry.path());
             continue;
         }
         if !entry.file_type().is_file() {
@@ -861,91 +1630,207 @@ fn scan_root(
             }
         };
 
-        let size_bytes = metadata.len();
-        let path = entry.path();
-        file_count += 1;
-        total_size_bytes = total_size_bytes.saturating_add(size_bytes);
+        accumulator.record_file(entry.path(), &FileFacts::from_metadata(&metadata));
+    }
 
-        let modified_dt = metadata.modified().ok().map(DateTime::<Utc>::from);
-        let modified_text = modified_dt.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true));
-        match modified_dt {
-            Some(time) if time >= recent_cutoff => activity.recent_files += 1,
-            Some(time) if time <= stale_cutoff => activity.stale_files += 1,
-            Some(_) => {}
-            None => activity.unknown_modified_files += 1,
-        }
+    Ok(accumulator.finish(largest_directories_override, total_size_override))
+}
 
-        let extension = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.to_lowercase())
-            .unwrap_or_else(|| "none".to_string());
-        let type_entry = top_file_types.entry(extension).or_insert((0, 0));
-        type_entry.0 += 1;
+/// Folds walked entries of a single root into its `PathStats` and dedupe records.
+/// Every backend feeds the same accumulator so their outputs stay comparable.
+struct RootAccumulator<'a> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    disk_mount: Option<String>,
+    device_mounts: HashMap<u64, String>,
+    file_count: u64,
+    directory_count: u64,
+    total_size_bytes: u64,
+    top_file_types: HashMap<String, (u64, u64)>,
+    top_directory_sizes: HashMap<String, u64>,
+    largest_files: Vec<FileEntry>,
+    files: Vec<FileRecord>,
+    tree_builder: Option<DirectoryTreeBuilder>,
+    seen_inodes: HashSet<(u64, u64)>,
+    hardlinked_files: u64,
+    hardlink_savings_bytes: u64,
+    recent_cutoff: DateTime<Utc>,
+    stale_cutoff: DateTime<Utc>,
+    activity: ActivitySignals,
+}
+
+impl<'a> RootAccumulator<'a> {
+    fn new(root: &'a Path, disks: &[DiskInfo], options: &'a ScanOptions) -> Self {
+        let now = Utc::now();
+        let disk_mount = match_disk_mount(root, disks);
+        Self {
+            root,
+            options,
+            device_mounts: device_mounts(disks, disk_mount.as_deref()),
+            disk_mount,
+            file_count: 0,
+            directory_count: 0,
+            total_size_bytes: 0,
+            top_file_types: HashMap::new(),
+            top_directory_sizes: HashMap::new(),
+            largest_files: Vec::new(),
+            files: Vec::new(),
+            tree_builder: options
+                .directory_tree
+                .then(|| DirectoryTreeBuilder::new(root)),
+            seen_inodes: HashSet::new(),
+            hardlinked_files: 0,
+            hardlink_savings_bytes: 0,
+            recent_cutoff: now - Duration::days(90),
+            stale_cutoff: now - Duration::days(365 * 2),
+            activity: ActivitySignals {
+                recent_files: 0,
+                stale_files: 0,
+                unknown_modified_files: 0,
+            },
+        }
+    }
+
+    fn record_directory(&mut self, path: &Path) {
+        self.directory_count += 1;
+        if let Some(builder) = self.tree_builder.as_mut() {
+            builder.record_directory(path);
+        }
+    }
+
+    fn record_file(&mut self, path: &Path, facts: &FileFacts) {
+        let apparent_bytes = facts.size_bytes;
+        let identity = facts.identity();
+        let mut repeated_link = false;
+        if facts.link_count > 1 {
+            self.hardlinked_files += 1;
+            if let Some(identity) = identity {
+                repeated_link = !self.seen_inodes.insert(identity);
+            }
+        }
+        if repeated_link {
+            self.hardlink_savings_bytes =
+                self.hardlink_savings_bytes.saturating_add(apparent_bytes);
+        }
+
+        // `size_bytes` is what this file contributes to path totals under the
+        // configured size mode; dedupe and largest-file listings keep apparent sizes.
+        let size_bytes = match self.options.size_mode {
+            SizeMode::Apparent => apparent_bytes,
+            SizeMode::Allocated => facts.allocated_bytes.unwrap_or(apparent_bytes),
+            SizeMode::HardlinkDeduplicated if repeated_link => 0,
+            SizeMode::HardlinkDeduplicated => apparent_bytes,
+        };
+        self.file_count += 1;
+        self.total_size_bytes = self.total_size_bytes.saturating_add(size_bytes);
+
+        let modified_dt = facts.modified();
+        let modified_text = modified_dt.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true));
+        match modified_dt {
+            Some(time) if time >= self.recent_cutoff => self.activity.recent_files += 1,
+            Some(time) if time <= self.stale_cutoff => self.activity.stale_files += 1,
+            Some(_) => {}
+            None => self.activity.unknown_modified_files += 1,
+        }
+        if let Some(builder) = self.tree_builder.as_mut() {
+            builder.record_file(path, size_bytes, modified_dt);
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_else(|| "none".to_string());
+        let type_entry = self.top_file_types.entry(extension).or_insert((0, 0));
+        type_entry.0 += 1;
         type_entry.1 = type_entry.1.saturating_add(size_bytes);
 
         update_largest_files(
-            &mut largest_files,
-            options.largest_files_limit,
+            &mut self.largest_files,
+            self.options.largest_files_limit,
             FileEntry {
                 path: path.to_string_lossy().to_string(),
-                size_bytes,
+                size_bytes: apparent_bytes,
                 modified: modified_text.clone(),
             },
         );
 
-        if let Ok(relative) = path.strip_prefix(root) {
+        if let Ok(relative) = path.strip_prefix(self.root) {
             let mut components = relative.components();
             if let Some(first) = components.next() {
                 if components.next().is_some() {
-                    let bucket = root.join(first.as_os_str()).to_string_lossy().to_string();
-                    let current = top_directory_sizes.entry(bucket).or_insert(0);
+                    let bucket = self
+                        .root
+                        .join(first.as_os_str())
+                        .to_string_lossy()
+                        .to_string();
+                    let current = self.top_directory_sizes.entry(bucket).or_insert(0);
                     *current = current.saturating_add(size_bytes);
                 }
             }
         }
 
-        files.push(FileRecord {
+        self.files.push(FileRecord {
             path: path.to_path_buf(),
-            size_bytes,
-            disk_mount: disk_mount.clone(),
+            size_bytes: apparent_bytes,
+            disk_mount: identity
+                .and_then(|(device, _)| self.device_mounts.get(&device).cloned())
+                .or_else(|| self.disk_mount.clone()),
             modified: modified_text,
+            device_id: identity.map(|(device, _)| device),
+            inode: identity.map(|(_, inode)| inode),
+            modified_unix_nanos: facts.modified_unix_nanos,
+            changed_unix_nanos: facts.changed_unix_nanos,
         });
     }
 
-    let file_type_summary = finalize_type_summary(
-        top_file_types,
-        options.top_extensions_limit,
-        file_count,
-        total_size_bytes,
-    );
-    let largest_directories = largest_directories_override.unwrap_or_else(|| {
-        finalize_largest_directories(top_directory_sizes, options.largest_directories_limit)
-    });
-
-    if let Some(override_total) = total_size_override {
-        total_size_bytes = override_total;
-    }
-
-    Ok(RootScanResult {
-        stats: PathStats {
-            root_path: root.to_string_lossy().to_string(),
-            disk_mount,
-            total_size_bytes,
-            file_count,
-            directory_count,
-            largest_files: LargestFiles {
-                entries: largest_files,
+    fn finish(
+        self,
+        largest_directories_override: Option<Vec<DirectoryUsage>>,
+        total_size_override: Option<u64>,
+    ) -> RootScanResult {
+        let file_type_summary = finalize_type_summary(
+            self.top_file_types,
+            self.options.top_extensions_limit,
+            self.file_count,
+            self.total_size_bytes,
+        );
+        let largest_directories = largest_directories_override.unwrap_or_else(|| {
+            finalize_largest_directories(
+                self.top_directory_sizes,
+                self.options.largest_directories_limit,
+            )
+        });
+        let total_size_bytes = total_size_override.unwrap_or(self.total_size_bytes);
+        let directory_tree_min_bytes = self.options.directory_tree_min_bytes;
+
+        RootScanResult {
+            stats: PathStats {
+                root_path: self.root.to_string_lossy().to_string(),
+                disk_mount: self.disk_mount,
+                total_size_bytes,
+                file_count: self.file_count,
+                directory_count: self.directory_count,
+                largest_files: LargestFiles {
+                    entries: self.largest_files,
+                },
+                largest_directories,
+                file_type_summary,
+                activity: self.activity,
+                directory_tree: self
+                    .tree_builder
+                    .map(|builder| builder.build(directory_tree_min_bytes)),
+                hardlinked_files: self.hardlinked_files,
+                hardlink_savings_bytes: self.hardlink_savings_bytes,
             },
-            largest_directories,
-            file_type_summary,
-            activity,
-        },
-        files,
-        scanned_files: file_count,
-        scanned_directories: directory_count,
-        scanned_bytes: total_size_bytes,
-    })
+            files: self.files,
+            scanned_files: self.file_count,
+            scanned_directories: self.directory_count,
+            scanned_bytes: total_size_bytes,
+            journal_reused_directories: 0,
+            journal_rescanned_directories: 0,
+        }
+    }
 }
 
 #[cfg(feature = "pdu-backend")]
@@ -954,17 +1839,27 @@ fn build_pdu_tree_summary(
     options: &ScanOptions,
 ) -> Result<(Option<u64>, Option<Vec<DirectoryUsage>>)> {
     let reporter = ErrorOnlyReporter::new(ErrorReport::SILENT);
-    let tree: parallel_disk_usage::data_tree::DataTree<OsStringDisplay, Bytes> = FsTreeBuilder {
-        root: root.to_path_buf(),
-        size_getter: GetApparentSize,
-        hardlinks_recorder: &HardlinkIgnorant,
-        reporter: &reporter,
-        max_depth: options
-            .max_depth
-            .map(|depth| depth as u64)
-            .unwrap_or(u64::MAX),
-    }
-    .into();
+    let root_metadata = fs::metadata(root).ok().filter(|metadata| metadata.is_dir());
+    let apparent_root_size = root_metadata.as_ref().map(|metadata| metadata.len());
+
+    let (tree, root_directory_size) = match options.size_mode {
+        #[cfg(unix)]
+        SizeMode::Allocated => (
+            build_pdu_tree(root, options, GetBlockSize, &HardlinkIgnorant, &reporter),
+            root_metadata.as_ref().and_then(allocated_size),
+        ),
+        #[cfg(unix)]
+        SizeMode::HardlinkDeduplicated => {
+            let hardlinks = HardlinkAware::<Bytes>::new();
+            let mut tree = build_pdu_tree(root, options, GetApparentSize, &hardlinks, &reporter);
+            let _ = hardlinks.deduplicate(&mut tree);
+            (tree, apparent_root_size)
+        }
+        _ => (
+            build_pdu_tree(root, options, GetApparentSize, &HardlinkIgnorant, &reporter),
+            apparent_root_size,
+        ),
+    };
 
     let mut largest_directories = tree
         .children()
@@ -987,17 +1882,38 @@ fn build_pdu_tree_summary(
     // The native walker reports the sum of file sizes only. The PDU tree summary
     // includes the root directory entry size on some platforms, which creates a
     // stable one-directory delta (commonly 4096 bytes on Linux) in parity checks.
-    let root_directory_size = fs::metadata(root)
-        .ok()
-        .filter(|metadata| metadata.is_dir())
-        .map(|metadata| metadata.len())
-        .unwrap_or(0);
     let total_size: u64 = tree.size().into();
-    let normalized_total_size = total_size.saturating_sub(root_directory_size);
+    let normalized_total_size = total_size.saturating_sub(root_directory_size.unwrap_or(0));
 
     Ok((Some(normalized_total_size), Some(largest_directories)))
 }
 
+#[cfg(feature = "pdu-backend")]
+fn build_pdu_tree<SizeGetter, Hardlinks, Report>(
+    root: &Path,
+    options: &ScanOptions,
+    size_getter: SizeGetter,
+    hardlinks_recorder: &Hardlinks,
+    reporter: &Report,
+) -> DataTree<OsStringDisplay, Bytes>
+where
+    SizeGetter: GetSize<Size = Bytes> + Sync,
+    Hardlinks: RecordHardlinks<Bytes, Report> + Sync,
+    Report: Reporter<Bytes> + Sync,
+{
+    FsTreeBuilder {
+        root: root.to_path_buf(),
+        size_getter,
+        hardlinks_recorder,
+        reporter,
+        max_depth: options
+            .max_depth
+            .map(|depth| depth as u64)
+            .unwrap_or(u64::MAX),
+    }
+    .into()
+}
+
 #[cfg(not(feature = "pdu-backend"))]
 fn build_pdu_tree_summary(
     _root: &Path,
@@ -1067,7 +1983,25 @@ fn finalize_type_summary(
     }
 }
 
+/// The disk a root lives on. Disks whose `st_dev` matches the root win over plain
+/// path prefixes, so a bind mount or subvolume is attributed to the filesystem it
+/// really belongs to.
 fn match_disk_mount(path: &Path, disks: &[DiskInfo]) -> Option<String> {
+    if let Some(device) = path_device_id(path) {
+        let same_device = disks
+            .iter()
+            .filter(|disk| disk.device_id == Some(device))
+            .collect::<Vec<_>>();
+        let by_prefix = same_device
+            .iter()
+            .filter(|disk| path.starts_with(Path::new(&disk.mount_point)))
+            .max_by_key(|disk| disk.mount_point.len());
+        let primary = same_device.iter().find(|disk| disk.parent_mount.is_none());
+        if let Some(disk) = by_prefix.or(primary).or(same_device.first()) {
+            return Some(disk.mount_point.clone());
+        }
+    }
+
     let mut best: Option<(&DiskInfo, usize)> = None;
     for disk in disks {
         let mount = Path::new(&disk.mount_point);
@@ -1083,30 +2017,31 @@ fn match_disk_mount(path: &Path, disks: &[DiskInfo]) -> Option<String> {
     best.map(|(disk, _)| disk.mount_point.clone())
 }
 
-fn enumerate_disks() -> Vec<DiskInfo> {
-    let disks = Disks::new_with_refreshed_list();
-    let probes = disks
-        .list()
-        .iter()
-        .map(|disk| {
-            let disk_kind = match disk.kind() {
-                SysDiskKind::HDD => DiskKind::Hdd,
-                SysDiskKind::SSD => DiskKind::Ssd,
-                _ => DiskKind::Unknown,
-            };
-
-            DiskProbe {
-                name: disk.name().to_string_lossy().to_string(),
-                mount_point: disk.mount_point().to_string_lossy().to_string(),
-                total_space_bytes: disk.total_space(),
-                free_space_bytes: disk.available_space(),
-                disk_kind,
-                file_system: Some(disk.file_system().to_string_lossy().to_string()),
-                is_removable: disk.is_removable(),
+/// Mount to attribute files to per `st_dev`, so files under nested mounts inside a
+/// root count against their own filesystem. The root's own disk keeps its device;
+/// otherwise the pool primary wins over its children.
+fn device_mounts(disks: &[DiskInfo], root_mount: Option<&str>) -> HashMap<u64, String> {
+    let mut mounts: HashMap<u64, &DiskInfo> = HashMap::new();
+    for disk in disks {
+        let Some(device) = disk.device_id else {
+            continue;
+        };
+        let replace = match mounts.get(&device) {
+            None => true,
+            Some(current) if Some(current.mount_point.as_str()) == root_mount => false,
+            Some(current) => {
+                Some(disk.mount_point.as_str()) == root_mount
+                    || (current.parent_mount.is_some() && disk.parent_mount.is_none())
             }
-        })
-        .collect::<Vec<_>>();
-    enrich_disks(probes)
+        };
+        if replace {
+            mounts.insert(device, disk);
+        }
+    }
+    mounts
+        .into_iter()
+        .map(|(device, disk)| (device, disk.mount_point.clone()))
+        .collect()
 }
 
 struct ExcludeMatcher {
@@ -1198,14 +2133,69 @@ fn validate_scan_options(options: &ScanOptions) -> Result<()> {
     if options.progress_interval_ms == 0 {
         return Err(anyhow!("progress_interval_ms must be greater than zero"));
     }
+    if options.dedupe_directories
+        && !(options.dedupe_directory_min_overlap > 0.0
+            && options.dedupe_directory_min_overlap <= 1.0)
+    {
+        return Err(anyhow!(
+            "dedupe_directory_min_overlap must be greater than 0 and at most 1"
+        ));
+    }
+    if options.dedupe && options.dedupe_workers == 0 {
+        return Err(anyhow!(
+            "dedupe_workers must be greater than zero when dedupe is enabled"
+        ));
+    }
+    if options.dedupe && options.dedupe_hdd_workers == 0 {
+        return Err(anyhow!(
+            "dedupe_hdd_workers must be greater than zero when dedupe is enabled"
+        ));
+    }
+    if options.similar_images && options.similar_image_max_distance > 64 {
+        return Err(anyhow!(
+            "similar_image_max_distance must be at most 64, the perceptual hash width"
+        ));
+    }
     if options.incremental_cache && options.cache_ttl_seconds == 0 {
         return Err(anyhow!(
             "cache_ttl_seconds must be greater than zero when incremental_cache is enabled"
         ));
     }
+    if options.disk_inventory.is_some() && (options.smartctl || options.smart_dir.is_some()) {
+        return Err(anyhow!(
+            "smartctl and smart_dir read drive health for this machine; capture it into the disk inventory instead"
+        ));
+    }
     Ok(())
 }
 
+/// Rotational disks get a single hashing reader so parallel seeks do not thrash
+/// them; every mount shares the configured read-rate cap.
+fn dedupe_mount_limits(
+    disks: &[DiskInfo],
+    options: &ScanOptions,
+) -> HashMap<String, MountHashLimit> {
+    disks
+        .iter()
+        .map(|disk| {
+            // sysinfo often reports `Unknown` where sysfs or lsblk still saw a spindle.
+            let spinning = disk.disk_kind == DiskKind::Hdd || disk.rotational == Some(true);
+            let concurrency = if spinning {
+                options.dedupe_hdd_workers.min(options.dedupe_workers)
+            } else {
+                options.dedupe_workers
+            };
+            (
+                disk.mount_point.clone(),
+                MountHashLimit {
+                    concurrency,
+                    max_bytes_per_second: options.dedupe_max_bytes_per_second,
+                },
+            )
+        })
+        .collect()
+}
+
 fn is_cancelled(options: &ScanOptions) -> bool {
     options
         .cancel_flag
@@ -1217,6 +2207,8 @@ fn try_load_cached_report(
     options: &ScanOptions,
     scan_id: &str,
     roots: &[PathBuf],
+    journals: &RootJournals,
+    policy_profile_hash: &str,
     pending_warnings: &mut Vec<String>,
     elapsed: std::time::Duration,
 ) -> Option<Report> {
@@ -1224,7 +2216,7 @@ fn try_load_cached_report(
         return None;
     }
 
-    let cache_key = match build_scan_cache_key(roots, options) {
+    let cache_key = match build_scan_cache_key(roots, options, policy_profile_hash) {
         Ok(value) => value,
         Err(err) => {
             pending_warnings.push(format!("cache key generation failed: {err}"));
@@ -1286,6 +2278,19 @@ fn try_load_cached_report(
     if current_signatures != entry.root_signatures {
         return None;
     }
+    // Root mtimes only move when direct children change; the directory journals
+    // written alongside the report catch changes deeper in each tree. A journal
+    // whose root totals disagree with the cached report was rewritten by a walk of
+    // a different shape (another `max_depth`, say) and cannot vouch for it.
+    let nested_change = roots.iter().any(|root| {
+        journals.get(root).is_some_and(|journal| {
+            !journal_describes_report(journal, root, &entry.report)
+                || !journal.matches_disk(options.journal_verify_files)
+        })
+    });
+    if nested_change {
+        return None;
+    }
 
     let mut report = entry.report;
     report.scan_id = scan_id.to_string();
@@ -1304,6 +2309,14 @@ fn try_load_cached_report(
     report.scan.min_ratio = options.min_ratio;
     report.scan.emit_progress_events = options.emit_progress_events;
     report.scan.progress_interval_ms = options.progress_interval_ms;
+    report.scan.directory_tree = options.directory_tree;
+    report.scan.directory_tree_min_bytes = options.directory_tree_min_bytes;
+    report.scan.size_mode = options.size_mode.clone();
+    report.scan.similar_images = options.similar_images;
+    report.scan.similar_image_max_distance = options.similar_image_max_distance;
+    report.scan.dedupe_directories = options.dedupe_directories;
+    report.scan.dedupe_directory_min_overlap = options.dedupe_directory_min_overlap;
+    report.scan.dedupe_archives = options.dedupe_archives;
     report.scan_metrics.backend = options.backend.clone();
     report.scan_metrics.elapsed_ms = elapsed.as_millis().try_into().unwrap_or(u64::MAX);
     report.scan_metrics.scanned_roots = roots.len() as u64;
@@ -1331,7 +2344,12 @@ fn persist_cached_report(options: &ScanOptions, roots: &[PathBuf], report: &mut
         return;
     }
 
-    let cache_key = match build_scan_cache_key(roots, options) {
+    let policy_profile_hash = report
+        .policy_profile
+        .as_ref()
+        .map(|applied| applied.hash.clone())
+        .unwrap_or_default();
+    let cache_key = match build_scan_cache_key(roots, options, &policy_profile_hash) {
         Ok(value) => value,
         Err(err) => {
             append_warning_once(
@@ -1387,7 +2405,11 @@ fn persist_cached_report(options: &ScanOptions, roots: &[PathBuf], report: &mut
     }
 }
 
-fn build_scan_cache_key(roots: &[PathBuf], options: &ScanOptions) -> Result<String> {
+fn build_scan_cache_key(
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    policy_profile_hash: &str,
+) -> Result<String> {
     let key = ScanCacheKey {
         roots: roots
             .iter()
@@ -1397,11 +2419,33 @@ fn build_scan_cache_key(roots: &[PathBuf], options: &ScanOptions) -> Result<Stri
         excludes: options.excludes.clone(),
         dedupe: options.dedupe,
         dedupe_min_size: options.dedupe_min_size,
+        dedupe_directories: options.dedupe_directories,
+        dedupe_directory_min_overlap: options.dedupe_directory_min_overlap,
+        dedupe_archives: options.dedupe_archives,
+        similar_images: options.similar_images,
+        similar_image_max_distance: options.similar_image_max_distance,
+        policy_profile_hash: policy_profile_hash.to_string(),
+        smart_dir: options.smart_dir.as_deref().map(normalize_cache_path),
+        smart_dir_hash: options.smart_dir.as_deref().and_then(hash_smart_dir),
+        smartctl: options.smartctl,
+        disk_inventory: options.disk_inventory.as_deref().map(normalize_cache_path),
+        disk_inventory_hash: options
+            .disk_inventory
+            .as_deref()
+            .map(|path| {
+                fs::read(path)
+                    .map(|payload| blake3::hash(&payload).to_hex().to_string())
+                    .with_context(|| format!("failed to read disk inventory {}", path.display()))
+            })
+            .transpose()?,
         backend: options.backend.clone(),
         min_ratio: options.min_ratio,
         largest_files_limit: options.largest_files_limit,
         largest_directories_limit: options.largest_directories_limit,
         top_extensions_limit: options.top_extensions_limit,
+        directory_tree: options.directory_tree,
+        directory_tree_min_bytes: options.directory_tree_min_bytes,
+        size_mode: options.size_mode.clone(),
         report_version: REPORT_VERSION.to_string(),
         cache_schema_version: CACHE_SCHEMA_VERSION,
     };
@@ -1409,6 +2453,24 @@ fn build_scan_cache_key(roots: &[PathBuf], options: &ScanOptions) -> Result<Stri
     Ok(blake3::hash(&payload).to_hex().to_string())
 }
 
+/// Digest of the `*.json` files health import would read from `dir`, so edited or
+/// added SMART outputs invalidate cached reports.
+fn hash_smart_dir(dir: &Path) -> Option<String> {
+    let mut paths = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut hasher = blake3::Hasher::new();
+    for path in paths {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update(&fs::read(&path).unwrap_or_default());
+    }
+    Some(hasher.finalize().to_hex().to_string())
+}
+
 fn scan_cache_dir(options: &ScanOptions) -> PathBuf {
     options
         .cache_dir
@@ -1420,6 +2482,31 @@ fn scan_cache_path(options: &ScanOptions, cache_key: &str) -> PathBuf {
     scan_cache_dir(options).join(format!("{cache_key}.json"))
 }
 
+fn journal_describes_report(journal: &DirectoryJournal, root: &Path, report: &Report) -> bool {
+    let root_path = root.to_string_lossy();
+    let Some(stats) = report
+        .paths
+        .iter()
+        .find(|stats| stats.root_path == root_path)
+    else {
+        return false;
+    };
+    journal.root_totals().is_some_and(|totals| {
+        totals.file_count == stats.file_count && totals.directory_count == stats.directory_count
+    })
+}
+
+fn journal_path(options: &ScanOptions, root: &Path) -> PathBuf {
+    let key = JournalKey {
+        root: normalize_cache_path(root),
+        excludes: options.excludes.clone(),
+        journal_schema_version: JOURNAL_SCHEMA_VERSION,
+    };
+    let payload = serde_json::to_vec(&key).unwrap_or_default();
+    let digest = blake3::hash(&payload).to_hex().to_string();
+    scan_cache_dir(options).join(format!("journal-{digest}.json"))
+}
+
 fn collect_root_signatures(
     roots: &[PathBuf],
     warnings: &mut Vec<String>,
@@ -1485,12 +2572,17 @@ fn normalize_cache_path(path: &Path) -> String {
 #[cfg(test)]
 mod tests {
     use super::{
-        run_scan, should_skip_auto_root, validate_scan_options, ExcludeMatcher, ScanOptions,
+        compare_backends, dedupe_mount_limits, run_scan, should_skip_auto_root,
+        validate_scan_options, ExcludeMatcher, ScanOptions,
     };
+    use crate::device::{enrich_disks_without_space, DiskProbe};
+    use crate::model::{DiskKind, ScanBackendKind, SizeMode};
     use std::fs;
+    use std::io::Write;
     use std::path::Path;
+    use std::sync::{atomic::AtomicBool, Arc};
     use std::thread;
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
     use tempfile::tempdir;
 
     #[test]
@@ -1528,6 +2620,44 @@ mod tests {
         assert!(validate_scan_options(&options).is_err());
     }
 
+    #[test]
+    fn dedupe_limits_spinning_mounts_to_hdd_workers() {
+        let probe = |mount: &str, disk_kind| DiskProbe {
+            name: mount.to_string(),
+            mount_point: mount.to_string(),
+            total_space_bytes: 1_000,
+            free_space_bytes: 500,
+            disk_kind,
+            file_system: Some("ext4".to_string()),
+            is_removable: false,
+        };
+        let mut disks = enrich_disks_without_space(vec![
+            probe("/mnt/hdd", DiskKind::Hdd),
+            probe("/mnt/rotational", DiskKind::Unknown),
+            probe("/mnt/ssd", DiskKind::Ssd),
+        ]);
+        for disk in &mut disks {
+            disk.rotational = Some(disk.mount_point == "/mnt/rotational");
+        }
+        let options = ScanOptions {
+            dedupe: true,
+            dedupe_workers: 6,
+            dedupe_hdd_workers: 2,
+            ..ScanOptions::default()
+        };
+
+        let limits = dedupe_mount_limits(&disks, &options);
+        assert_eq!(limits["/mnt/hdd"].concurrency, 2);
+        assert_eq!(limits["/mnt/rotational"].concurrency, 2);
+        assert_eq!(limits["/mnt/ssd"].concurrency, 6);
+
+        assert!(validate_scan_options(&ScanOptions {
+            dedupe_hdd_workers: 0,
+            ..options
+        })
+        .is_err());
+    }
+
     #[test]
     fn validates_cache_ttl_when_incremental_cache_enabled() {
         let options = ScanOptions {
@@ -1539,6 +2669,39 @@ mod tests {
         assert!(validate_scan_options(&options).is_err());
     }
 
+    #[test]
+    fn rejects_local_smart_data_with_a_disk_inventory() {
+        let options = ScanOptions {
+            disk_inventory: Some("inventory.json".into()),
+            smartctl: true,
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        assert!(validate_scan_options(&options).is_err());
+        let options = ScanOptions {
+            smartctl: false,
+            smart_dir: Some("smart".into()),
+            ..options
+        };
+        assert!(validate_scan_options(&options).is_err());
+    }
+
+    #[test]
+    fn rejects_similar_image_distances_beyond_the_hash_width() {
+        let options = ScanOptions {
+            similar_images: true,
+            similar_image_max_distance: 65,
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        assert!(validate_scan_options(&options).is_err());
+        let options = ScanOptions {
+            similar_image_max_distance: 64,
+            ..options
+        };
+        assert!(validate_scan_options(&options).is_ok());
+    }
+
     #[test]
     fn incremental_cache_hits_on_second_run() {
         let root = tempdir().expect("temp root");
@@ -1599,4 +2762,401 @@ mod tests {
             .any(|warning| warning.contains("scan result loaded from incremental cache")));
         assert!(second.scan_metrics.scanned_files > first.scan_metrics.scanned_files);
     }
+
+    #[test]
+    fn incremental_cache_misses_when_disk_inventory_changes() {
+        let root = tempdir().expect("temp root");
+        let cache_dir = tempdir().expect("cache root");
+        fs::write(root.path().join("a.bin"), vec![7_u8; 512]).expect("seed file");
+        let inventory_path = cache_dir.path().join("inventory.json");
+        let inventory = include_str!("../../../fixtures/disk-inventory-linux-homelab.json");
+        fs::write(&inventory_path, inventory).expect("write inventory");
+
+        let options = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            incremental_cache: true,
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            disk_inventory: Some(inventory_path.clone()),
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        let from_cache = |report: &crate::model::Report| {
+            report
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("scan result loaded from incremental cache"))
+        };
+
+        let first = run_scan(&options).expect("first scan succeeds");
+        assert!(!from_cache(&first));
+        assert!(from_cache(
+            &run_scan(&options).expect("second scan succeeds")
+        ));
+
+        let edited = inventory.replace("500107862016", "1000204886016");
+        fs::write(&inventory_path, edited).expect("edit inventory");
+        let third = run_scan(&options).expect("third scan succeeds");
+        assert!(!from_cache(&third));
+        let os_disk = third
+            .disks
+            .iter()
+            .find(|disk| disk.mount_point == "/")
+            .expect("os disk");
+        assert_eq!(os_disk.total_space_bytes, 1_000_204_886_016);
+    }
+
+    #[test]
+    fn directory_journal_rereads_only_changed_directories() {
+        let root = tempdir().expect("temp root");
+        let cache_dir = tempdir().expect("cache root");
+        for name in ["a", "b", "c"] {
+            let directory = root.path().join(name);
+            fs::create_dir_all(&directory).expect("seed dir");
+            fs::write(directory.join("seed.bin"), vec![1_u8; 300]).expect("seed file");
+        }
+        fs::write(root.path().join("top.bin"), vec![2_u8; 50]).expect("top file");
+        // Age every directory past the volatile window so its listing can be replayed.
+        let an_hour_ago = SystemTime::now() - Duration::from_secs(3_600);
+        for directory in ["", "a", "b", "c"] {
+            fs::File::open(root.path().join(directory))
+                .and_then(|handle| handle.set_modified(an_hour_ago))
+                .expect("age directory");
+        }
+
+        let options = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            incremental_cache: true,
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        let first = run_scan(&options).expect("first scan succeeds");
+        assert_eq!(first.scan_metrics.journal_reused_directories, 0);
+        assert_eq!(first.scan_metrics.journal_rescanned_directories, 4);
+
+        // The root mtime is untouched, so only the journal can notice this change.
+        fs::write(root.path().join("b/new.bin"), vec![3_u8; 700]).expect("nested change");
+
+        let full = run_scan(&ScanOptions {
+            incremental_cache: false,
+            ..options.clone()
+        })
+        .expect("full scan succeeds");
+        for backend in [ScanBackendKind::Native, ScanBackendKind::Parallel] {
+            let rescan = run_scan(&ScanOptions {
+                backend,
+                ..options.clone()
+            })
+            .expect("journaled rescan succeeds");
+            assert!(!rescan
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("scan result loaded from incremental cache")));
+            assert_eq!(rescan.scan_metrics.journal_reused_directories, 3);
+            assert_eq!(rescan.scan_metrics.journal_rescanned_directories, 1);
+            assert_eq!(rescan.paths[0].file_count, 5);
+            assert_eq!(
+                rescan.paths[0].total_size_bytes,
+                full.paths[0].total_size_bytes
+            );
+            assert_eq!(
+                rescan.paths[0].largest_directories,
+                full.paths[0].largest_directories
+            );
+        }
+    }
+
+    #[test]
+    fn directory_journal_restats_files_edited_in_place_only_when_verifying() {
+        let root = tempdir().expect("temp root");
+        let cache_dir = tempdir().expect("cache root");
+        let logs = root.path().join("logs");
+        fs::create_dir_all(&logs).expect("seed dir");
+        fs::write(logs.join("app.log"), vec![1_u8; 100]).expect("seed log");
+        fs::write(logs.join("old.log"), vec![2_u8; 40]).expect("seed log");
+        let an_hour_ago = SystemTime::now() - Duration::from_secs(3_600);
+        let age = |path: &Path| {
+            fs::File::open(path)
+                .and_then(|handle| handle.set_modified(an_hour_ago))
+                .expect("age directory");
+        };
+        age(&logs);
+        age(root.path());
+
+        let options = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            incremental_cache: true,
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        let first = run_scan(&options).expect("first scan succeeds");
+        assert_eq!(first.paths[0].total_size_bytes, 140);
+
+        // Appending rewrites the file but not its directory entry.
+        fs::OpenOptions::new()
+            .append(true)
+            .open(logs.join("app.log"))
+            .and_then(|mut handle| handle.write_all(&[3_u8; 400]))
+            .expect("append in place");
+        age(&logs);
+        age(root.path());
+        let from_cache = |report: &crate::model::Report| {
+            report
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("scan result loaded from incremental cache"))
+        };
+
+        // By default only directories are stat'd, so the edit goes unnoticed.
+        let trusted = run_scan(&options).expect("trusting rescan succeeds");
+        assert!(from_cache(&trusted));
+        assert_eq!(trusted.paths[0].total_size_bytes, 140);
+
+        let verified = run_scan(&ScanOptions {
+            journal_verify_files: true,
+            ..options.clone()
+        })
+        .expect("verifying rescan succeeds");
+        assert!(!from_cache(&verified));
+        assert_eq!(verified.paths[0].total_size_bytes, 540);
+        assert_eq!(verified.scan_metrics.journal_reused_directories, 1);
+        assert_eq!(verified.scan_metrics.journal_rescanned_directories, 1);
+    }
+
+    #[test]
+    fn incremental_cache_misses_when_journal_was_rewritten_at_another_depth() {
+        let root = tempdir().expect("temp root");
+        let cache_dir = tempdir().expect("cache root");
+        fs::create_dir_all(root.path().join("nested")).expect("seed dir");
+        fs::write(root.path().join("nested/a.bin"), vec![1_u8; 64]).expect("seed file");
+        let an_hour_ago = SystemTime::now() - Duration::from_secs(3_600);
+        let age = |path: &Path| {
+            fs::File::open(path)
+                .and_then(|handle| handle.set_modified(an_hour_ago))
+                .expect("age directory");
+        };
+        age(&root.path().join("nested"));
+        age(root.path());
+
+        let options = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            incremental_cache: true,
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        run_scan(&options).expect("full scan succeeds");
+        // A shallow walk shares the journal but not the cached report, and leaves the
+        // nested directory out of the journal it writes back.
+        run_scan(&ScanOptions {
+            max_depth: Some(1),
+            ..options.clone()
+        })
+        .expect("shallow scan succeeds");
+        fs::write(root.path().join("nested/b.bin"), vec![2_u8; 64]).expect("nested change");
+        age(&root.path().join("nested"));
+
+        let rescan = run_scan(&options).expect("rescan succeeds");
+        assert!(!rescan
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("scan result loaded from incremental cache")));
+        assert_eq!(rescan.paths[0].file_count, 2);
+    }
+
+    #[test]
+    fn directory_tree_is_pruned_by_threshold() {
+        let root = tempdir().expect("temp root");
+        fs::create_dir_all(root.path().join("big/nested")).expect("big dirs");
+        fs::create_dir_all(root.path().join("small")).expect("small dir");
+        fs::write(root.path().join("big/nested/a.bin"), vec![1_u8; 4096]).expect("seed a");
+        fs::write(root.path().join("big/b.bin"), vec![2_u8; 2048]).expect("seed b");
+        fs::write(root.path().join("small/c.txt"), vec![3_u8; 16]).expect("seed c");
+
+        let options = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            directory_tree: true,
+            directory_tree_min_bytes: 1024,
+            record_history: false,
+            ..ScanOptions::default()
+        };
+
+        let report = run_scan(&options).expect("scan succeeds");
+        assert!(report.scan.directory_tree);
+        let tree = report.paths[0]
+            .directory_tree
+            .as_ref()
+            .expect("tree present");
+        assert_eq!(tree.size_bytes, 4096 + 2048 + 16);
+        assert_eq!(tree.file_count, 3);
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].size_bytes, 4096 + 2048);
+        assert_eq!(tree.children[0].children.len(), 1);
+        assert!(tree.latest_modified.is_some());
+    }
+
+    #[test]
+    fn parallel_backend_matches_native_with_excludes_and_depth() {
+        let root = tempdir().expect("temp root");
+        for dir in ["a/b/c", "a/skip", "d"] {
+            fs::create_dir_all(root.path().join(dir)).expect("seed dirs");
+        }
+        fs::write(root.path().join("top.bin"), vec![1_u8; 100]).expect("seed");
+        fs::write(root.path().join("a/one.bin"), vec![2_u8; 200]).expect("seed");
+        fs::write(root.path().join("a/b/two.bin"), vec![3_u8; 300]).expect("seed");
+        fs::write(root.path().join("a/b/c/deep.bin"), vec![4_u8; 400]).expect("seed");
+        fs::write(root.path().join("a/skip/ignored.tmp"), vec![5_u8; 500]).expect("seed");
+        fs::write(root.path().join("d/three.bin"), vec![6_u8; 600]).expect("seed");
+
+        let native = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            max_depth: Some(3),
+            excludes: vec!["**/*.tmp".to_string()],
+            directory_tree: true,
+            directory_tree_min_bytes: 0,
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        let parallel = ScanOptions {
+            backend: ScanBackendKind::Parallel,
+            ..native.clone()
+        };
+
+        let native_report = run_scan(&native).expect("native scan");
+        let parallel_report = run_scan(&parallel).expect("parallel scan");
+        assert_eq!(
+            parallel_report.scan_metrics.backend,
+            ScanBackendKind::Parallel
+        );
+        assert_eq!(
+            native_report.scan_metrics.scanned_files,
+            parallel_report.scan_metrics.scanned_files
+        );
+        assert_eq!(
+            native_report.scan_metrics.scanned_directories,
+            parallel_report.scan_metrics.scanned_directories
+        );
+        assert_eq!(native_report.paths, parallel_report.paths);
+        assert_eq!(
+            native_report.scan_metrics.scanned_bytes,
+            100 + 200 + 300 + 600
+        );
+    }
+
+    #[test]
+    fn depth_zero_scans_no_entries_on_every_walker() {
+        let root = tempdir().expect("temp root");
+        let cache_dir = tempdir().expect("cache root");
+        fs::create_dir_all(root.path().join("a")).expect("seed dir");
+        fs::write(root.path().join("top.bin"), vec![1_u8; 100]).expect("seed");
+        fs::write(root.path().join("a/one.bin"), vec![2_u8; 200]).expect("seed");
+
+        let native = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            max_depth: Some(0),
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        let parallel = ScanOptions {
+            backend: ScanBackendKind::Parallel,
+            ..native.clone()
+        };
+        // The incremental cache swaps walkdir for the journal-aware walker.
+        let journaled = ScanOptions {
+            incremental_cache: true,
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            ..native.clone()
+        };
+
+        let native_report = run_scan(&native).expect("native scan");
+        assert_eq!(native_report.scan_metrics.scanned_files, 0);
+        for options in [parallel, journaled] {
+            let report = run_scan(&options).expect("scan");
+            assert_eq!(report.scan_metrics.scanned_files, 0);
+            assert_eq!(
+                report.scan_metrics.scanned_directories,
+                native_report.scan_metrics.scanned_directories
+            );
+            assert_eq!(report.paths, native_report.paths);
+        }
+    }
+
+    #[test]
+    fn parallel_backend_honors_cancel_flag() {
+        let root = tempdir().expect("temp root");
+        fs::write(root.path().join("a.bin"), vec![1_u8; 64]).expect("seed");
+
+        let options = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            backend: ScanBackendKind::Parallel,
+            cancel_flag: Some(Arc::new(AtomicBool::new(true))),
+            record_history: false,
+            ..ScanOptions::default()
+        };
+
+        let report = run_scan(&options).expect("scan returns partial report");
+        assert_eq!(report.scan_metrics.scanned_files, 0);
+        assert!(report
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("scan canceled")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hardlink_deduplicated_mode_counts_each_inode_once() {
+        let root = tempdir().expect("temp root");
+        let original = root.path().join("original.bin");
+        fs::write(&original, vec![1_u8; 8192]).expect("seed original");
+        fs::hard_link(&original, root.path().join("linked.bin")).expect("hard link");
+        fs::write(root.path().join("other.bin"), vec![2_u8; 1024]).expect("seed other");
+
+        let apparent = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        let deduplicated = ScanOptions {
+            size_mode: SizeMode::HardlinkDeduplicated,
+            ..apparent.clone()
+        };
+
+        let apparent_report = run_scan(&apparent).expect("apparent scan");
+        let deduplicated_report = run_scan(&deduplicated).expect("deduplicated scan");
+
+        assert_eq!(apparent_report.paths[0].total_size_bytes, 8192 * 2 + 1024);
+        assert_eq!(apparent_report.paths[0].hardlinked_files, 2);
+        assert_eq!(apparent_report.paths[0].hardlink_savings_bytes, 8192);
+        assert_eq!(deduplicated_report.paths[0].total_size_bytes, 8192 + 1024);
+        assert_eq!(
+            deduplicated_report.scan.size_mode,
+            SizeMode::HardlinkDeduplicated
+        );
+
+        let parity = compare_backends(&deduplicated).expect("parity runs");
+        assert_eq!(parity.scanned_bytes_delta, 0);
+        assert_eq!(parity.parallel_scanned_bytes_delta, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allocated_mode_sums_block_usage() {
+        use std::os::unix::fs::MetadataExt;
+
+        let root = tempdir().expect("temp root");
+        let path = root.path().join("small.bin");
+        fs::write(&path, vec![1_u8; 10]).expect("seed");
+        let expected = fs::metadata(&path).expect("metadata").blocks() * 512;
+
+        let options = ScanOptions {
+            paths: vec![root.path().to_path_buf()],
+            size_mode: SizeMode::Allocated,
+            record_history: false,
+            ..ScanOptions::default()
+        };
+        let report = run_scan(&options).expect("scan");
+        assert_eq!(report.paths[0].total_size_bytes, expected);
+        assert_eq!(report.paths[0].largest_files.entries[0].size_bytes, 10);
+    }
 }
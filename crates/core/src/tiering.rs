@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::device::{effective_free_space_bytes, shares_storage_pool};
+use crate::model::{
+    Category, DirectoryNode, DiskInfo, DiskRole, LocalityClass, PathStats, PolicyThresholds,
+    Report, TieringMove,
+};
+use crate::recommend::{performance_rank, sanitize_id};
+
+/// Root activity fallback: at least this share of a root's dated files must be older
+/// than the scan's stale cutoff before its largest directories are treated as cold.
+const FALLBACK_MIN_STALE_SHARE: f64 = 0.8;
+
+struct ColdDirectory<'a> {
+    path: String,
+    bytes: u64,
+    source: &'a DiskInfo,
+    last_modified: Option<String>,
+}
+
+struct Destination<'a> {
+    disk: &'a DiskInfo,
+    rank: f32,
+    role_preference: u8,
+    remaining_bytes: u64,
+}
+
+/// Picks directories that have been cold for at least `tiering_cold_days` and
+/// assigns each to a slower eligible local disk, largest first. Destinations are
+/// tried coldest role and slowest tier first, and within a tier the tightest fit
+/// wins, so large moves are not blocked by small ones. A destination keeps at least
+/// `tiering_target_min_free_ratio` of its capacity free after every planned move.
+///
+/// The result is advisory: it names what could move where and performs nothing.
+/// `recommendation_id` is filled per destination so policy enforcement can prune
+/// the plan later.
+pub fn plan_cold_data_tiering(report: &Report, thresholds: &PolicyThresholds) -> Vec<TieringMove> {
+    let now = report
+        .generated_at
+        .parse::<DateTime<Utc>>()
+        .unwrap_or_else(|_| Utc::now());
+    let cutoff = now - Duration::days(i64::from(thresholds.tiering_cold_days));
+    let disk_by_mount = report
+        .disks
+        .iter()
+        .map(|disk| (disk.mount_point.as_str(), disk))
+        .collect::<HashMap<_, _>>();
+    let games_roots = report
+        .categories
+        .iter()
+        .filter(|suggestion| suggestion.category == Category::Games)
+        .map(|suggestion| suggestion.target.as_str())
+        .collect::<Vec<_>>();
+
+    let mut candidates = Vec::new();
+    for path in &report.paths {
+        let Some(source) = path
+            .disk_mount
+            .as_deref()
+            .and_then(|mount| disk_by_mount.get(mount).copied())
+        else {
+            continue;
+        };
+        if source.locality_class != LocalityClass::LocalPhysical
+            || source.role_hint.role == DiskRole::GamesLibrary
+        {
+            continue;
+        }
+        let mut found = Vec::new();
+        match &path.directory_tree {
+            Some(tree) => collect_cold_nodes(tree, source, cutoff, thresholds, &mut found),
+            None => collect_stale_root_directories(path, source, thresholds, &mut found),
+        }
+        // Game installs are rarely rewritten while still being played.
+        found.retain(|directory| {
+            !games_roots
+                .iter()
+                .any(|root| is_within(&directory.path, root))
+        });
+        candidates.extend(found);
+    }
+    candidates.sort_by(|left, right| {
+        right
+            .bytes
+            .cmp(&left.bytes)
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    // Overlapping scan roots can report the same subtree twice; keep the outermost.
+    let mut kept: Vec<ColdDirectory> = Vec::new();
+    for candidate in candidates {
+        if !kept.iter().any(|other| {
+            is_within(&candidate.path, &other.path) || is_within(&other.path, &candidate.path)
+        }) {
+            kept.push(candidate);
+        }
+    }
+
+    let mut destinations = report
+        .disks
+        .iter()
+        .filter(|disk| {
+            // Pool children share their primary's free space; counting them too
+            // would book the same bytes twice.
+            disk.eligible_for_local_target
+                && !disk.is_os_drive
+                && disk.locality_class == LocalityClass::LocalPhysical
+                && disk.parent_mount.is_none()
+        })
+        .filter_map(|disk| {
+            let role_preference = match disk.role_hint.role {
+                DiskRole::Archive => 0,
+                DiskRole::MediaLibrary => 1,
+                DiskRole::Mixed | DiskRole::Unknown => 2,
+                // Active disks should stay fast, and a backup target holding the only
+                // copy of moved data stops being a backup.
+                DiskRole::ActiveWorkload | DiskRole::GamesLibrary | DiskRole::BackupTarget => {
+                    return None
+                }
+            };
+            let reserve =
+                (disk.total_space_bytes as f64 * thresholds.tiering_target_min_free_ratio) as u64;
+            Some(Destination {
+                disk,
+                rank: performance_rank(disk),
+                role_preference,
+                remaining_bytes: effective_free_space_bytes(disk).saturating_sub(reserve),
+            })
+        })
+        .collect::<Vec<_>>();
+    destinations.sort_by(|left, right| {
+        left.role_preference
+            .cmp(&right.role_preference)
+            .then_with(|| left.rank.total_cmp(&right.rank))
+            .then_with(|| left.disk.mount_point.cmp(&right.disk.mount_point))
+    });
+
+    let mut moves = Vec::new();
+    for candidate in kept {
+        let source_rank = performance_rank(candidate.source);
+        let fits = |destination: &Destination| {
+            destination.disk.mount_point != candidate.source.mount_point
+                && !shares_storage_pool(destination.disk, candidate.source)
+                && (destination.rank < source_rank || candidate.source.is_os_drive)
+                && destination.remaining_bytes >= candidate.bytes
+        };
+        let Some(first) = destinations.iter().position(fits) else {
+            continue;
+        };
+        let tier = (
+            destinations[first].role_preference,
+            destinations[first].rank,
+        );
+        let chosen = destinations
+            .iter()
+            .enumerate()
+            .filter(|(_, destination)| {
+                (destination.role_preference, destination.rank) == tier && fits(destination)
+            })
+            .min_by_key(|(_, destination)| destination.remaining_bytes)
+            .map(|(index, _)| index)
+            .unwrap_or(first);
+        let destination = &mut destinations[chosen];
+        destination.remaining_bytes -= candidate.bytes;
+        moves.push(TieringMove {
+            recommendation_id: tiering_recommendation_id(&destination.disk.mount_point),
+            rationale: move_rationale(&candidate, destination.disk, thresholds),
+            source_path: candidate.path,
+            source_mount: candidate.source.mount_point.clone(),
+            bytes: candidate.bytes,
+            target_mount: destination.disk.mount_point.clone(),
+            last_modified: candidate.last_modified,
+        });
+    }
+    moves
+}
+
+fn tiering_recommendation_id(target_mount: &str) -> String {
+    format!("cold-data-tiering-{}", sanitize_id(target_mount))
+}
+
+// Takes the highest cold subtrees; a subtree with any recent file is split into its
+// children instead. The scan root itself is never a candidate when it is a mount.
+fn collect_cold_nodes<'a>(
+    node: &DirectoryNode,
+    source: &'a DiskInfo,
+    cutoff: DateTime<Utc>,
+    thresholds: &PolicyThresholds,
+    out: &mut Vec<ColdDirectory<'a>>,
+) {
+    if node.size_bytes < thresholds.tiering_min_directory_bytes {
+        return;
+    }
+    let modified = node
+        .latest_modified
+        .as_deref()
+        .and_then(|value| value.parse::<DateTime<Utc>>().ok());
+    let is_mount_root = same_path(&node.path, &source.mount_point);
+    if !is_mount_root && modified.is_some_and(|time| time <= cutoff) {
+        out.push(ColdDirectory {
+            path: node.path.clone(),
+            bytes: node.size_bytes,
+            source,
+            last_modified: node.latest_modified.clone(),
+        });
+        return;
+    }
+    for child in &node.children {
+        collect_cold_nodes(child, source, cutoff, thresholds, out);
+    }
+}
+
+// Without a directory tree there are no per-directory mtimes. A root with no recent
+// files and mostly stale ones is cold as a whole, so its largest directories are.
+fn collect_stale_root_directories<'a>(
+    path: &PathStats,
+    source: &'a DiskInfo,
+    thresholds: &PolicyThresholds,
+    out: &mut Vec<ColdDirectory<'a>>,
+) {
+    let activity = &path.activity;
+    let dated_files = path
+        .file_count
+        .saturating_sub(activity.unknown_modified_files);
+    if activity.recent_files > 0
+        || dated_files == 0
+        || (activity.stale_files as f64 / dated_files as f64) < FALLBACK_MIN_STALE_SHARE
+    {
+        return;
+    }
+    for directory in &path.largest_directories {
+        if directory.size_bytes < thresholds.tiering_min_directory_bytes
+            || same_path(&directory.path, &source.mount_point)
+        {
+            continue;
+        }
+        out.push(ColdDirectory {
+            path: directory.path.clone(),
+            bytes: directory.size_bytes,
+            source,
+            last_modified: None,
+        });
+    }
+}
+
+fn move_rationale(
+    candidate: &ColdDirectory,
+    target: &DiskInfo,
+    thresholds: &PolicyThresholds,
+) -> String {
+    let age = match &candidate.last_modified {
+        Some(modified) => format!(
+            "has not changed since {}",
+            modified.get(..10).unwrap_or(modified)
+        ),
+        None => "sits under a root whose files are almost all stale".to_string(),
+    };
+    format!(
+        "{} ({}) {}, beyond the {}-day cold threshold. {} is a {:?} {:?} disk with room for it while keeping {:.0}% free.",
+        candidate.path,
+        human_bytes(candidate.bytes),
+        age,
+        thresholds.tiering_cold_days,
+        target.mount_point,
+        target.performance_class,
+        target.role_hint.role,
+        thresholds.tiering_target_min_free_ratio * 100.0
+    )
+}
+
+fn is_within(path: &str, root: &str) -> bool {
+    let root = root.trim_end_matches(['/', '\\']);
+    path.strip_prefix(root)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '\\']))
+}
+
+fn same_path(left: &str, right: &str) -> bool {
+    left.trim_end_matches(['/', '\\']) == right.trim_end_matches(['/', '\\'])
+}
+
+fn human_bytes(value: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if value == 0 {
+        return "0 B".to_string();
+    }
+    let mut size = value as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan_cold_data_tiering;
+    use crate::analyzers::{AnalyzerContext, AnalyzerRegistry};
+    use crate::model::{DirectoryNode, DiskRole, PolicyThresholds, Report};
+    use crate::recommend::generate_recommendation_bundle_with_context;
+
+    const GB: u64 = 1_000_000_000;
+
+    fn node(path: &str, gb: u64, modified: &str, children: Vec<DirectoryNode>) -> DirectoryNode {
+        DirectoryNode {
+            path: path.to_string(),
+            size_bytes: gb * GB,
+            file_count: 10,
+            latest_modified: Some(modified.to_string()),
+            children,
+        }
+    }
+
+    #[test]
+    fn packs_cold_subtrees_onto_slower_disks_and_drops_blocked_moves() {
+        let mut report: Report =
+            serde_json::from_str(include_str!("../../../fixtures/sample-report.json"))
+                .expect("fixture");
+        report.paths[0].directory_tree = Some(node(
+            "C:\\Media",
+            400,
+            "2026-02-01T00:00:00Z",
+            vec![
+                node("C:\\Media\\Movies", 200, "2025-01-01T00:00:00Z", Vec::new()),
+                node(
+                    "C:\\Media\\Current",
+                    50,
+                    "2026-02-01T00:00:00Z",
+                    vec![node(
+                        "C:\\Media\\Current\\Old",
+                        5,
+                        "2024-06-01T00:00:00Z",
+                        Vec::new(),
+                    )],
+                ),
+            ],
+        ));
+        // A small archive disk: 40 GB usable after keeping 20% of 300 GB free.
+        let mut archive = report.disks[1].clone();
+        archive.name = "Archive".to_string();
+        archive.mount_point = "E:\\".to_string();
+        archive.total_space_bytes = 300 * GB;
+        archive.free_space_bytes = 100 * GB;
+        archive.role_hint.role = DiskRole::Archive;
+        report.disks.push(archive);
+
+        let moves = plan_cold_data_tiering(&report, &PolicyThresholds::default());
+        let placed: Vec<(&str, &str)> = moves
+            .iter()
+            .map(|planned| (planned.source_path.as_str(), planned.target_mount.as_str()))
+            .collect();
+        assert_eq!(
+            placed,
+            vec![
+                ("C:\\Media\\Movies", "D:\\"),
+                ("C:\\Media\\Current\\Old", "E:\\"),
+            ]
+        );
+        assert_eq!(moves[0].bytes, 200 * GB);
+        assert_eq!(moves[0].source_mount, "C:\\");
+        assert!(moves[0].rationale.contains("2025-01-01"));
+
+        let mut context = AnalyzerContext::default();
+        context
+            .policy_profile
+            .disabled_rules
+            .push(moves[1].recommendation_id.clone());
+        let bundle = generate_recommendation_bundle_with_context(
+            &report,
+            &context,
+            &AnalyzerRegistry::empty(),
+        );
+        assert!(bundle
+            .recommendations
+            .iter()
+            .any(|recommendation| recommendation.id == moves[0].recommendation_id));
+        assert_eq!(bundle.tiering_plan, moves[..1].to_vec());
+
+        // Snapshots eat the archive disk's headroom: 60 GB writable is all reserve.
+        let archive = report.disks.last_mut().expect("archive disk");
+        archive.effective_free_space_bytes = Some(60 * GB);
+        let moves = plan_cold_data_tiering(&report, &PolicyThresholds::default());
+        assert!(moves.iter().all(|planned| planned.target_mount != "E:\\"));
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::model::DirectoryNode;
+
+#[derive(Debug, Default, Clone)]
+struct DirectoryTotals {
+    size_bytes: u64,
+    file_count: u64,
+    latest_modified: Option<DateTime<Utc>>,
+}
+
+/// Accumulates per-directory totals while a root is walked and turns them into a
+/// pruned [`DirectoryNode`] hierarchy once the walk completes.
+#[derive(Debug)]
+pub(crate) struct DirectoryTreeBuilder {
+    root: PathBuf,
+    totals: HashMap<PathBuf, DirectoryTotals>,
+}
+
+impl DirectoryTreeBuilder {
+    pub(crate) fn new(root: &Path) -> Self {
+        let mut totals = HashMap::new();
+        totals.insert(root.to_path_buf(), DirectoryTotals::default());
+        Self {
+            root: root.to_path_buf(),
+            totals,
+        }
+    }
+
+    pub(crate) fn record_directory(&mut self, path: &Path) {
+        if path.starts_with(&self.root) && !self.totals.contains_key(path) {
+            self.totals
+                .insert(path.to_path_buf(), DirectoryTotals::default());
+        }
+    }
+
+    pub(crate) fn record_file(
+        &mut self,
+        path: &Path,
+        size_bytes: u64,
+        modified: Option<DateTime<Utc>>,
+    ) {
+        let mut current = path.parent();
+        while let Some(directory) = current {
+            if !directory.starts_with(&self.root) {
+                break;
+            }
+            let totals = match self.totals.get_mut(directory) {
+                Some(totals) => totals,
+                None => self.totals.entry(directory.to_path_buf()).or_default(),
+            };
+            totals.size_bytes = totals.size_bytes.saturating_add(size_bytes);
+            totals.file_count += 1;
+            if let Some(time) = modified {
+                if totals.latest_modified.is_none_or(|latest| time > latest) {
+                    totals.latest_modified = Some(time);
+                }
+            }
+            if directory == self.root {
+                break;
+            }
+            current = directory.parent();
+        }
+    }
+
+    pub(crate) fn build(self, min_bytes: u64) -> DirectoryNode {
+        let mut children_by_parent: HashMap<&Path, Vec<&Path>> = HashMap::new();
+        for path in self.totals.keys() {
+            if path == &self.root {
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                children_by_parent
+                    .entry(parent)
+                    .or_default()
+                    .push(path.as_path());
+            }
+        }
+
+        build_node(&self.root, &self.totals, &children_by_parent, min_bytes)
+    }
+}
+
+fn build_node(
+    path: &Path,
+    totals: &HashMap<PathBuf, DirectoryTotals>,
+    children_by_parent: &HashMap<&Path, Vec<&Path>>,
+    min_bytes: u64,
+) -> DirectoryNode {
+    let own = totals.get(path).cloned().unwrap_or_default();
+    let mut children = children_by_parent
+        .get(path)
+        .map(|children| {
+            children
+                .iter()
+                .filter(|child| {
+                    totals
+                        .get(**child)
+                        .is_some_and(|child| child.size_bytes >= min_bytes)
+                })
+                .map(|child| build_node(child, totals, children_by_parent, min_bytes))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    children.sort_by(|a, b| {
+        b.size_bytes
+            .cmp(&a.size_bytes)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    DirectoryNode {
+        path: path.to_string_lossy().to_string(),
+        size_bytes: own.size_bytes,
+        file_count: own.file_count,
+        latest_modified: own
+            .latest_modified
+            .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        children,
+    }
+}
+
+/// Returns every node of the tree in depth-first order, starting with `root`.
+pub fn flatten_directory_tree(root: &DirectoryNode) -> Vec<&DirectoryNode> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        nodes.push(node);
+        stack.extend(node.children.iter().rev());
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flatten_directory_tree, DirectoryTreeBuilder};
+    use chrono::{TimeZone, Utc};
+    use std::path::Path;
+
+    #[test]
+    fn prunes_small_children_but_keeps_their_bytes_in_ancestors() {
+        let root = Path::new("/data");
+        let mut builder = DirectoryTreeBuilder::new(root);
+        builder.record_directory(Path::new("/data/empty"));
+        builder.record_file(
+            Path::new("/data/videos/a.mkv"),
+            4_000,
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+        );
+        builder.record_file(
+            Path::new("/data/videos/raw/b.mkv"),
+            6_000,
+            Some(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()),
+        );
+        builder.record_file(Path::new("/data/notes/c.txt"), 10, None);
+        builder.record_file(Path::new("/data/top.bin"), 5, None);
+
+        let tree = builder.build(1_000);
+        assert_eq!(tree.size_bytes, 10_015);
+        assert_eq!(tree.file_count, 4);
+        assert_eq!(
+            tree.latest_modified.as_deref(),
+            Some("2025-06-01T00:00:00Z")
+        );
+        assert_eq!(tree.children.len(), 1);
+
+        let videos = &tree.children[0];
+        assert_eq!(videos.path, "/data/videos");
+        assert_eq!(videos.size_bytes, 10_000);
+        assert_eq!(videos.children[0].path, "/data/videos/raw");
+
+        let paths = flatten_directory_tree(&tree)
+            .iter()
+            .map(|node| node.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["/data", "/data/videos", "/data/videos/raw"]);
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use walkdir::WalkDir;
+
+use crate::dedupe::FileRecord;
+use crate::model::GitRepository;
+
+/// Reflog messages that record new commits rather than moving a ref around.
+const COMMIT_REFLOG_PREFIXES: &[&str] = &[
+    "commit",
+    "merge",
+    "rebase",
+    "cherry-pick",
+    "revert",
+    "pull",
+    "am",
+];
+
+#[derive(Default)]
+struct RepositoryFiles<'a> {
+    disk_mount: Option<String>,
+    git_dir_bytes: u64,
+    pack_bytes: u64,
+    worktree: Vec<&'a FileRecord>,
+}
+
+/// Finds git repositories among scanned files: any file below a `.git` directory
+/// marks its parent as a working copy. Sizes come from the scan; reflogs, the index
+/// and `.gitignore` are read directly and never modified.
+pub(crate) fn find_git_repositories(files: &[FileRecord]) -> Vec<GitRepository> {
+    let mut repositories: HashMap<PathBuf, RepositoryFiles> = HashMap::new();
+    for record in files {
+        let Some(root) = git_dir_owner(&record.path) else {
+            continue;
+        };
+        let repository = repositories.entry(root.clone()).or_default();
+        repository.disk_mount = record.disk_mount.clone();
+        repository.git_dir_bytes = repository.git_dir_bytes.saturating_add(record.size_bytes);
+        let is_pack = record
+            .path
+            .strip_prefix(root.join(".git").join("objects").join("pack"))
+            .is_ok()
+            && record.path.extension().is_some_and(|ext| ext == "pack");
+        if is_pack {
+            repository.pack_bytes = repository.pack_bytes.saturating_add(record.size_bytes);
+        }
+    }
+    if repositories.is_empty() {
+        return Vec::new();
+    }
+
+    // Worktree files belong to the nearest enclosing repository, so a nested clone
+    // does not count towards its parent.
+    for record in files {
+        if git_dir_owner(&record.path).is_some() {
+            continue;
+        }
+        let owner = record
+            .path
+            .ancestors()
+            .skip(1)
+            .find(|ancestor| repositories.contains_key(*ancestor));
+        if let Some(repository) = owner.and_then(|root| repositories.get_mut(root)) {
+            repository.worktree.push(record);
+        }
+    }
+
+    let mut output: Vec<GitRepository> = repositories
+        .into_iter()
+        .map(|(root, files)| inspect_repository(&root, files))
+        .collect();
+    output.sort_by(|left, right| left.path.cmp(&right.path));
+    output
+}
+
+/// Working-copy root for a path inside a `.git` directory. A `.git` file (worktree or
+/// submodule pointer) is the last component and does not count.
+fn git_dir_owner(path: &Path) -> Option<PathBuf> {
+    let components: Vec<Component<'_>> = path.components().collect();
+    let position = components[..components.len().saturating_sub(1)]
+        .iter()
+        .position(|component| component.as_os_str() == ".git")?;
+    Some(components[..position].iter().collect())
+}
+
+fn inspect_repository(root: &Path, files: RepositoryFiles<'_>) -> GitRepository {
+    let git_dir = root.join(".git");
+    let index_modified = fs::metadata(git_dir.join("index"))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .map(DateTime::<Utc>::from);
+    let ignored = root_gitignore(root);
+    let (last_commit, last_ref_update) = read_reflogs(&git_dir);
+
+    let mut worktree_bytes = 0u64;
+    let mut latest_worktree: Option<DateTime<Utc>> = None;
+    let mut files_newer_than_index = 0u64;
+    for record in files.worktree {
+        worktree_bytes = worktree_bytes.saturating_add(record.size_bytes);
+        let relative = record.path.strip_prefix(root).unwrap_or(&record.path);
+        if ignored.as_ref().is_some_and(|set| set.is_match(relative)) {
+            continue;
+        }
+        let Some(modified) = record
+            .modified
+            .as_deref()
+            .and_then(|text| text.parse::<DateTime<Utc>>().ok())
+        else {
+            continue;
+        };
+        latest_worktree = latest_worktree.max(Some(modified));
+        if index_modified.is_some_and(|index| modified > index) {
+            files_newer_than_index += 1;
+        }
+    }
+
+    let format = |time: DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::Secs, true);
+    GitRepository {
+        path: root.to_string_lossy().to_string(),
+        disk_mount: files.disk_mount,
+        last_commit_at: last_commit.map(format),
+        last_ref_update_at: last_ref_update.map(format),
+        latest_worktree_modified_at: latest_worktree.map(format),
+        index_modified_at: index_modified.map(format),
+        files_newer_than_index,
+        pack_bytes: files.pack_bytes,
+        git_dir_bytes: files.git_dir_bytes,
+        worktree_bytes,
+    }
+}
+
+/// Latest commit-like and latest overall reflog timestamps across `logs/HEAD` and
+/// every branch log.
+fn read_reflogs(git_dir: &Path) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let mut last_commit = None;
+    let mut last_update = None;
+    let logs = git_dir.join("logs");
+    let reflogs = std::iter::once(logs.join("HEAD")).chain(
+        WalkDir::new(logs.join("refs").join("heads"))
+            .follow_links(false)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path()),
+    );
+    for path in reflogs {
+        let Ok(payload) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in payload.lines() {
+            let Some((timestamp, message)) = parse_reflog_line(line) else {
+                continue;
+            };
+            last_update = last_update.max(Some(timestamp));
+            if COMMIT_REFLOG_PREFIXES
+                .iter()
+                .any(|prefix| message.starts_with(prefix))
+            {
+                last_commit = last_commit.max(Some(timestamp));
+            }
+        }
+    }
+    (last_commit, last_update)
+}
+
+/// Parses `<old> <new> <name> <email> <unix-time> <tz>\t<message>`.
+fn parse_reflog_line(line: &str) -> Option<(DateTime<Utc>, &str)> {
+    let (header, message) = line.split_once('\t').unwrap_or((line, ""));
+    let mut fields = header.rsplitn(3, ' ');
+    let _timezone = fields.next()?;
+    let seconds = fields.next()?.parse::<i64>().ok()?;
+    let timestamp = Utc.timestamp_opt(seconds, 0).single()?;
+    Some((timestamp, message))
+}
+
+/// Approximates the repository's root `.gitignore`: plain patterns only, negations
+/// and nested ignore files are not evaluated.
+fn root_gitignore(root: &Path) -> Option<GlobSet> {
+    let payload = fs::read_to_string(root.join(".gitignore")).ok()?;
+    let mut builder = GlobSetBuilder::new();
+    for line in payload.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        let pattern = line.trim_end_matches('/');
+        let anchored = pattern.trim_start_matches('/');
+        let globs = if pattern.contains('/') {
+            vec![anchored.to_string(), format!("{anchored}/**")]
+        } else {
+            vec![format!("**/{pattern}"), format!("**/{pattern}/**")]
+        };
+        for glob in globs {
+            if let Ok(glob) = Glob::new(&glob) {
+                builder.add(glob);
+            }
+        }
+    }
+    builder.build().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use chrono::{DateTime, SecondsFormat, Utc};
+    use tempfile::TempDir;
+
+    use super::find_git_repositories;
+    use crate::dedupe::FileRecord;
+
+    fn record(path: &Path) -> FileRecord {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(|time| DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true));
+        FileRecord::from_path(path.to_path_buf(), None, modified).expect("record")
+    }
+
+    #[test]
+    fn reads_reflogs_pack_size_and_worktree_changes_without_git() {
+        let temp = TempDir::new().expect("tempdir");
+        let repo = temp.path().join("tool");
+        let git = repo.join(".git");
+        fs::create_dir_all(git.join("logs/refs/heads")).expect("logs");
+        fs::create_dir_all(git.join("objects/pack")).expect("pack dir");
+        fs::create_dir_all(repo.join("src")).expect("src");
+        fs::create_dir_all(repo.join("target")).expect("target");
+        fs::write(
+            git.join("logs/HEAD"),
+            "0000 1111 Ana <ana@example.com> 1600000000 +0200\tcommit (initial): init\n\
+             1111 2222 Ana <ana@example.com> 1650000000 +0200\tcommit: work\n\
+             2222 2222 Ana <ana@example.com> 1700000000 +0200\tcheckout: moving from main to dev\n",
+        )
+        .expect("reflog");
+        fs::write(
+            git.join("logs/refs/heads/dev"),
+            "2222 3333 Ana <ana@example.com> 1660000000 +0200\tmerge main: Fast-forward\n",
+        )
+        .expect("branch reflog");
+        fs::write(git.join("objects/pack/pack-1.pack"), vec![0u8; 4096]).expect("pack");
+        fs::write(git.join("objects/pack/pack-1.idx"), vec![0u8; 100]).expect("idx");
+        fs::write(git.join("index"), "").expect("index");
+        let an_hour_ago = SystemTime::now() - Duration::from_secs(3600);
+        File::open(git.join("index"))
+            .and_then(|file| file.set_modified(an_hour_ago))
+            .expect("index mtime");
+        fs::write(repo.join(".gitignore"), "/target\n*.log\n").expect("gitignore");
+        fs::write(repo.join("src/main.rs"), "fn main() {}").expect("source");
+        fs::write(repo.join("target/app"), "binary").expect("build output");
+        fs::write(repo.join("src/debug.log"), "log").expect("log");
+        fs::write(repo.join(".git-credentials"), "").expect("dotfile");
+        File::open(repo.join(".git-credentials"))
+            .and_then(|file| file.set_modified(an_hour_ago - Duration::from_secs(60)))
+            .expect("old mtime");
+
+        let files: Vec<FileRecord> = [
+            "tool/.git/logs/HEAD",
+            "tool/.git/logs/refs/heads/dev",
+            "tool/.git/objects/pack/pack-1.pack",
+            "tool/.git/objects/pack/pack-1.idx",
+            "tool/.git/index",
+            "tool/.gitignore",
+            "tool/src/main.rs",
+            "tool/target/app",
+            "tool/src/debug.log",
+            "tool/.git-credentials",
+        ]
+        .iter()
+        .map(|path| record(&temp.path().join(path)))
+        .collect();
+
+        let repositories = find_git_repositories(&files);
+        assert_eq!(repositories.len(), 1);
+        let repository = &repositories[0];
+        assert_eq!(repository.path, repo.to_string_lossy());
+        assert_eq!(
+            repository.last_commit_at.as_deref(),
+            Some("2022-08-08T23:06:40Z")
+        );
+        assert_eq!(
+            repository.last_ref_update_at.as_deref(),
+            Some("2023-11-14T22:13:20Z")
+        );
+        assert_eq!(repository.pack_bytes, 4096);
+        // `.gitignore` and `src/main.rs` are newer than the index; ignored build
+        // output and logs are not counted.
+        assert_eq!(repository.files_newer_than_index, 2);
+        assert_eq!(repository.worktree_bytes, 14 + 12 + 6 + 3);
+    }
+}
@@ -47,6 +47,14 @@ fn test_backend_parity_on_fixtures() -> Result<()> {
     // shouldn't have ambiguity between walkers.
     assert_eq!(parity.scanned_files_delta, 0, "File count mismatch");
     assert_eq!(parity.scanned_bytes_delta, 0, "Byte count mismatch");
+    assert_eq!(
+        parity.parallel_scanned_files_delta, 0,
+        "Parallel file count mismatch"
+    );
+    assert_eq!(
+        parity.parallel_scanned_bytes_delta, 0,
+        "Parallel byte count mismatch"
+    );
 
     Ok(())
 }
@@ -17,7 +17,7 @@ use storage_strategist_core::{
     list_reports as list_saved_reports, render_markdown_summary, run_scan_with_callback,
     store_report, write_diagnostics_bundle, DiagnosticsBundle, DoctorInfo, RecommendationBundle,
     Report, ReportDiff, ReportImportResult, ReportSummary, ScanBackendKind, ScanOptions,
-    ScanProgressEvent, ScenarioPlan,
+    ScanProgressEvent, ScenarioPlan, SizeMode,
 };
 use uuid::Uuid;
 
@@ -37,6 +37,38 @@ pub struct ScanRequest {
     pub dedupe: bool,
     #[serde(default = "default_dedupe_min_size")]
     pub dedupe_min_size: u64,
+    #[serde(default = "default_dedupe_hash_cache")]
+    pub dedupe_hash_cache: bool,
+    #[serde(default = "default_dedupe_workers")]
+    pub dedupe_workers: usize,
+    #[serde(default = "default_dedupe_hdd_workers")]
+    pub dedupe_hdd_workers: usize,
+    #[serde(default)]
+    pub dedupe_max_bytes_per_second: Option<u64>,
+    #[serde(default)]
+    pub dedupe_directories: bool,
+    #[serde(default = "default_dedupe_directory_min_overlap")]
+    pub dedupe_directory_min_overlap: f32,
+    #[serde(default)]
+    pub dedupe_archives: bool,
+    #[serde(default)]
+    pub similar_images: bool,
+    #[serde(default = "default_similar_image_max_distance")]
+    pub similar_image_max_distance: u32,
+    #[serde(default)]
+    pub policy_profile: Option<PathBuf>,
+    #[serde(default)]
+    pub smart_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub smartctl: bool,
+    #[serde(default)]
+    pub disk_inventory: Option<PathBuf>,
+    #[serde(default)]
+    pub directory_tree: bool,
+    #[serde(default = "default_directory_tree_min_bytes")]
+    pub directory_tree_min_bytes: u64,
+    #[serde(default)]
+    pub size_mode: SizeMode,
     #[serde(default)]
     pub backend: ScanBackendKind,
     #[serde(default)]
@@ -53,6 +85,10 @@ pub struct ScanRequest {
     pub cache_dir: Option<PathBuf>,
     #[serde(default = "default_cache_ttl_seconds")]
     pub cache_ttl_seconds: u64,
+    #[serde(default = "default_journal_max_age_seconds")]
+    pub journal_max_age_seconds: u64,
+    #[serde(default)]
+    pub journal_verify_files: bool,
     #[serde(default = "default_record_history")]
     pub record_history: bool,
     #[serde(default)]
@@ -63,6 +99,30 @@ fn default_dedupe_min_size() -> u64 {
     1_048_576
 }
 
+fn default_dedupe_hash_cache() -> bool {
+    true
+}
+
+fn default_dedupe_workers() -> usize {
+    4
+}
+
+fn default_dedupe_hdd_workers() -> usize {
+    1
+}
+
+fn default_dedupe_directory_min_overlap() -> f32 {
+    0.9
+}
+
+fn default_similar_image_max_distance() -> u32 {
+    6
+}
+
+fn default_directory_tree_min_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
 fn default_progress_interval() -> u64 {
     250
 }
@@ -75,6 +135,10 @@ fn default_cache_ttl_seconds() -> u64 {
     900
 }
 
+fn default_journal_max_age_seconds() -> u64 {
+    7 * 24 * 60 * 60
+}
+
 fn default_record_history() -> bool {
     true
 }
@@ -89,6 +153,22 @@ impl Default for ScanRequest {
             excludes: Vec::new(),
             dedupe: false,
             dedupe_min_size: default_dedupe_min_size(),
+            dedupe_hash_cache: default_dedupe_hash_cache(),
+            dedupe_workers: default_dedupe_workers(),
+            dedupe_hdd_workers: default_dedupe_hdd_workers(),
+            dedupe_max_bytes_per_second: None,
+            dedupe_directories: false,
+            dedupe_directory_min_overlap: default_dedupe_directory_min_overlap(),
+            dedupe_archives: false,
+            similar_images: false,
+            similar_image_max_distance: default_similar_image_max_distance(),
+            policy_profile: None,
+            smart_dir: None,
+            smartctl: false,
+            disk_inventory: None,
+            directory_tree: false,
+            directory_tree_min_bytes: default_directory_tree_min_bytes(),
+            size_mode: SizeMode::Apparent,
             backend: ScanBackendKind::Native,
             progress: false,
             min_ratio: None,
@@ -97,6 +177,8 @@ impl Default for ScanRequest {
             incremental_cache: default_incremental_cache(),
             cache_dir: None,
             cache_ttl_seconds: default_cache_ttl_seconds(),
+            journal_max_age_seconds: default_journal_max_age_seconds(),
+            journal_verify_files: false,
             record_history: default_record_history(),
             report_store_dir: None,
         }
@@ -172,6 +254,22 @@ pub fn start_scan(request: ScanRequest) -> Result<String> {
             excludes: request.excludes,
             dedupe: request.dedupe,
             dedupe_min_size: request.dedupe_min_size,
+            dedupe_hash_cache: request.dedupe_hash_cache,
+            dedupe_workers: request.dedupe_workers,
+            dedupe_hdd_workers: request.dedupe_hdd_workers,
+            dedupe_max_bytes_per_second: request.dedupe_max_bytes_per_second,
+            dedupe_directories: request.dedupe_directories,
+            dedupe_directory_min_overlap: request.dedupe_directory_min_overlap,
+            dedupe_archives: request.dedupe_archives,
+            similar_images: request.similar_images,
+            similar_image_max_distance: request.similar_image_max_distance,
+            policy_profile: request.policy_profile,
+            smart_dir: request.smart_dir,
+            smartctl: request.smartctl,
+            disk_inventory: request.disk_inventory,
+            directory_tree: request.directory_tree,
+            directory_tree_min_bytes: request.directory_tree_min_bytes,
+            size_mode: request.size_mode,
             dry_run: true,
             backend: request.backend,
             progress: request.progress,
@@ -182,6 +280,8 @@ pub fn start_scan(request: ScanRequest) -> Result<String> {
             incremental_cache: request.incremental_cache,
             cache_dir: request.cache_dir,
             cache_ttl_seconds: request.cache_ttl_seconds,
+            journal_max_age_seconds: request.journal_max_age_seconds,
+            journal_verify_files: request.journal_verify_files,
             record_history: request.record_history,
             report_store_dir: report_store_dir.clone(),
             cancel_flag: Some(Arc::clone(&cancel_flag)),
@@ -408,7 +508,7 @@ mod tests {
         DiskInfo, DiskKind, DiskRole, DiskRoleHint, DiskStorageType, DuplicateGroup,
         DuplicateIntent, DuplicateIntentLabel, EstimatedImpact, LocalityClass, PathStats,
         PerformanceClass, Recommendation, RecommendationEvidence, RecommendationEvidenceKind,
-        Report, RiskLevel, ScanBackendKind, ScanMetadata, ScanMetrics,
+        Report, RiskLevel, ScanBackendKind, ScanMetadata, ScanMetrics, SizeMode,
     };
     use tempfile::tempdir;
 
@@ -495,6 +595,15 @@ mod tests {
                 min_ratio: None,
                 emit_progress_events: false,
                 progress_interval_ms: 250,
+                directory_tree: false,
+                directory_tree_min_bytes: 0,
+                size_mode: SizeMode::Apparent,
+                similar_images: false,
+                similar_image_max_distance: 0,
+                dedupe_directories: false,
+                dedupe_directory_min_overlap: 0.0,
+                dedupe_archives: false,
+                disk_inventory: None,
             },
             scan_metrics: ScanMetrics {
                 contradiction_count: 2,
@@ -532,6 +641,15 @@ mod tests {
                     evidence: vec!["fixture".to_string()],
                 },
                 target_role_eligibility: Vec::new(),
+                device_id: None,
+                mount_kind: Default::default(),
+                storage_pool: None,
+                parent_mount: None,
+                reserved_bytes: None,
+                snapshot_bytes: None,
+                logical_used_bytes: None,
+                effective_free_space_bytes: None,
+                health: None,
             }],
             paths: vec![PathStats {
                 root_path: "D:\\Demo".to_string(),
@@ -555,6 +673,9 @@ mod tests {
                     stale_files: 0,
                     unknown_modified_files: 0,
                 },
+                directory_tree: None,
+                hardlinked_files: 0,
+                hardlink_savings_bytes: 0,
             }],
             categories: Vec::new(),
             duplicates: vec![DuplicateGroup {
@@ -567,6 +688,14 @@ mod tests {
                     rationale: "fixture".to_string(),
                 },
             }],
+            duplicate_directories: Vec::new(),
+            archive_extractions: Vec::new(),
+            similar_images: Vec::new(),
+            git_repositories: Vec::new(),
+            dev_projects: None,
+            capacity_forecasts: Vec::new(),
+            tiering_plan: Vec::new(),
+            policy_profile: None,
             recommendations: vec![Recommendation {
                 id: "stored-rec".to_string(),
                 title: "Stored recommendation".to_string(),
@@ -591,6 +720,8 @@ mod tests {
                     risk_notes: Some("Stored risk note".to_string()),
                 },
                 risk_level: RiskLevel::High,
+                priority_score: 0.0,
+                rank: 0,
             }],
             policy_decisions: vec![storage_strategist_core::PolicyDecision {
                 policy_id: "stored_policy".to_string(),
@@ -23,6 +23,12 @@ def parse_args() -> argparse.Namespace:
         default=0.70,
         help="Minimum allowed precision@3 (default: 0.70)",
     )
+    parser.add_argument(
+        "--min-ndcg-at-3",
+        type=float,
+        default=0.95,
+        help="Minimum allowed NDCG@3 of the ranked recommendations (default: 0.95)",
+    )
     parser.add_argument(
         "--max-contradiction-rate",
         type=float,
@@ -51,6 +57,7 @@ def main() -> int:
     payload = load_json(args.input)
 
     precision = float(payload.get("precision_at_3", 0.0))
+    ndcg = float(payload.get("ndcg_at_3", 0.0))
     contradiction_rate = float(payload.get("contradiction_rate", 1.0))
     unsafe_recommendations = int(payload.get("unsafe_recommendations", 0))
     passed_cases = int(payload.get("passed_cases", 0))
@@ -60,6 +67,7 @@ def main() -> int:
         "Evaluation KPIs: "
         f"passed={passed_cases}/{total_cases} "
         f"precision@3={precision:.3f} "
+        f"ndcg@3={ndcg:.3f} "
         f"contradiction_rate={contradiction_rate:.3f} "
         f"unsafe={unsafe_recommendations}"
     )
@@ -69,6 +77,8 @@ def main() -> int:
         failures.append(
             f"precision@3 {precision:.3f} is below minimum {args.min_precision_at_3:.3f}"
         )
+    if ndcg < args.min_ndcg_at_3:
+        failures.append(f"ndcg@3 {ndcg:.3f} is below minimum {args.min_ndcg_at_3:.3f}")
     if contradiction_rate > args.max_contradiction_rate:
         failures.append(
             "contradiction_rate "